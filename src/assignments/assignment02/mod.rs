@@ -17,5 +17,8 @@
 pub mod small_exercises;
 mod small_exercises_grade;
 
+pub mod temperature;
+mod temperature_grade;
+
 pub mod vec_and_mat;
 mod vec_and_mat_grade;
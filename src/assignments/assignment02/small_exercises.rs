@@ -1,18 +1,18 @@
 //! Small problems.
 
-use std::iter;
+use std::{fmt, iter};
 
 use itertools::Itertools;
 use rayon::range;
 
 use crate::assignments::assignment07::small_exercises::range;
-
-const FAHRENHEIT_OFFSET: f64 = 32.0;
-const FAHRENHEIT_SCALE: f64 = 5.0 / 9.0;
+use crate::assignments::assignment09::bigint::BigInt;
 
 /// Converts Fahrenheit to Celsius temperature degree.
+///
+/// See [`temperature`](super::temperature) for the other conversions.
 pub fn fahrenheit_to_celsius(degree: f64) -> f64 {
-    FAHRENHEIT_SCALE * (degree - FAHRENHEIT_OFFSET)
+    super::temperature::fahrenheit_to_celsius(degree)
 }
 
 /// Capitalizes English alphabets (leaving the other characters intact).
@@ -41,44 +41,152 @@ pub fn sum_array(input: &[u64]) -> u64 {
 ///
 /// For instance, up3(6) = 9, up3(9) = 9, up3(10) = 27. (We assume the absence of integer overflow.)
 pub fn up3(n: u64) -> u64 {
-    if n <= 1 {
-        return 1;
+    up_k(n, 3).unwrap_or(u64::MAX)
+}
+
+/// Returns the smallest integer of the form `k^m` that's greater than or equal to `n`.
+///
+/// Returns `None` if `k < 2`, or if the result does not fit in `u64`.
+pub fn up_k(n: u64, k: u64) -> Option<u64> {
+    if k < 2 {
+        return None;
     }
     let mut p = 1u64;
     while p < n {
-        match p.checked_mul(3) {
-            Some(next) => p = next,
-            None => return u64::MAX, // or handle as you prefer
-        }
+        p = p.checked_mul(k)?;
     }
-    p
+    Some(p)
 }
 
-/// Returns the greatest common divisor (GCD) of two non-negative integers. (We assume the absence
-/// of integer overflow.)
+/// Returns the largest integer of the form `k^m` that's less than or equal to `n`.
+///
+/// Returns `None` if `k < 2` or `n == 0`.
+pub fn down_k(n: u64, k: u64) -> Option<u64> {
+    Some(k.pow(ilog_k(n, k)?))
+}
+
+/// Returns the largest `m` such that `k^m` is less than or equal to `n`, i.e. the logarithm of `n`
+/// base `k` rounded down.
+///
+/// Returns `None` if `k < 2` or `n == 0`.
+pub fn ilog_k(n: u64, k: u64) -> Option<u32> {
+    n.checked_ilog(k)
+}
+
+/// Returns `true` if `n` is of the form `k^m` for some `m >= 0`.
+pub fn is_power_of(n: u64, k: u64) -> bool {
+    match k {
+        0 => n <= 1,
+        1 => n == 1,
+        _ => down_k(n, k) == Some(n),
+    }
+}
+
+/// Returns the greatest common divisor (GCD) of two non-negative integers.
+///
+/// `gcd(n, 0)` is `n`, and in particular `gcd(0, 0)` is `0`.
 pub fn gcd(lhs: u64, rhs: u64) -> u64 {
-    if lhs % rhs == 0 {
-        rhs
+    if rhs == 0 {
+        lhs
+    } else {
+        gcd(rhs, lhs % rhs)
+    }
+}
+
+/// Returns the least common multiple (LCM) of two non-negative integers. (We assume the absence
+/// of integer overflow.)
+///
+/// `lcm(n, 0)` is `0`.
+pub fn lcm(lhs: u64, rhs: u64) -> u64 {
+    if lhs == 0 || rhs == 0 {
+        0
+    } else {
+        lhs / gcd(lhs, rhs) * rhs
+    }
+}
+
+/// Returns `(g, x, y)` where `g` is the non-negative GCD of `a` and `b`, and `x` and `y` are
+/// Bézout coefficients, i.e. `a * x + b * y == g`. (We assume the absence of integer overflow.)
+///
+/// For instance, `extended_gcd(240, 46)` is `(2, -9, 47)`.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
     } else {
-        let temp = lhs % rhs;
-        gcd(rhs, temp)
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Error for results that do not fit in `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverflowError;
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "result does not fit in u64")
     }
 }
 
-/// Returns the array of nC0, nC1, nC2, ..., nCn, where nCk = n! / (k! * (n-k)!). (We assume the
-/// absence of integer overflow.)
+impl std::error::Error for OverflowError {}
+
+/// Returns the array of nC0, nC1, nC2, ..., nCn, where nCk = n! / (k! * (n-k)!).
 ///
 /// Consult <https://en.wikipedia.org/wiki/Pascal%27s_triangle> for computation of binomial
 /// coefficients without integer overflow.
-pub fn chooses(n: u64) -> Vec<u64> {
-    let mut row = Vec::with_capacity((n as usize) + 1);
+///
+/// Returns `OverflowError` if a coefficient does not fit in `u64`, i.e. for `n > 67`. Use
+/// [`chooses_big`] for larger `n`.
+pub fn chooses(n: u64) -> Result<Vec<u64>, OverflowError> {
+    let mut row = vec![1];
     let mut c = 1u128;
-    row.push(c as u64);
     for k in 0..n {
+        // `c` fits in `u64`, so the product fits in `u128`.
         c = c * u128::from(n - k) / u128::from(k + 1);
-        // If you want to detect overflow instead of truncating:
-        // let val = u64::try_from(c).expect("binomial coefficient overflowed u64");
-        row.push(c as u64);
+        row.push(u64::try_from(c).map_err(|_| OverflowError)?);
+    }
+    Ok(row)
+}
+
+/// Returns nCk, or 0 if `k > n`.
+///
+/// Returns `OverflowError` if the result does not fit in `u64`.
+pub fn binomial(n: u64, k: u64) -> Result<u64, OverflowError> {
+    if k > n {
+        return Ok(0);
+    }
+    // nCk = nC(n-k), and nCi increases for i up to k.
+    let k = k.min(n - k);
+    let mut c = 1u128;
+    for i in 0..k {
+        c = c * u128::from(n - i) / u128::from(i + 1);
+        if c > u128::from(u64::MAX) {
+            return Err(OverflowError);
+        }
+    }
+    Ok(c as u64)
+}
+
+/// Like [`chooses`], but returns the coefficients as [`BigInt`]s, which never overflow.
+pub fn chooses_big(n: u64) -> Vec<BigInt> {
+    let mut row = vec![BigInt::new(1)];
+    for _ in 0..n {
+        let mut next = vec![BigInt::new(1)];
+        next.extend(
+            row.iter()
+                .tuple_windows()
+                .map(|(lhs, rhs)| lhs.clone() + rhs.clone()),
+        );
+        next.push(BigInt::new(1));
+        row = next;
     }
     row
 }
@@ -87,6 +195,17 @@ pub fn chooses(n: u64) -> Vec<u64> {
 ///
 /// For instance, `zip(vec![1, 2, 3], vec![4, 5])` equals to `vec![(1, 4), (2, 5)]`. Here, `3` is
 /// ignored because it doesn't have a partner.
-pub fn zip(lhs: Vec<u64>, rhs: Vec<u64>) -> Vec<(u64, u64)> {
+pub fn zip<A, B>(lhs: Vec<A>, rhs: Vec<B>) -> Vec<(A, B)> {
     lhs.into_iter().zip(rhs).collect()
 }
+
+/// Combines the elements of two vectors pairwise with `f`, ignoring the elements without a partner
+/// like [`zip`].
+///
+/// For instance, `zip_with(|x, y| x + y, vec![1, 2, 3], vec![4, 5])` equals to `vec![5, 7]`.
+pub fn zip_with<A, B, C, F>(mut f: F, lhs: Vec<A>, rhs: Vec<B>) -> Vec<C>
+where
+    F: FnMut(A, B) -> C,
+{
+    lhs.into_iter().zip(rhs).map(|(a, b)| f(a, b)).collect()
+}
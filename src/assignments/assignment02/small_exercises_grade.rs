@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod test {
     use crate::assignments::assignment02::small_exercises::*;
+    use crate::assignments::assignment09::bigint::BigInt;
 
     #[test]
     fn test_fahrenheit() {
@@ -29,6 +30,42 @@ mod test {
         assert_eq!(up3(3u64.pow(40)), 3u64.pow(40));
     }
 
+    #[test]
+    fn test_powers_of_k() {
+        assert_eq!(up_k(0, 2), Some(1));
+        assert_eq!(up_k(5, 2), Some(8));
+        assert_eq!(up_k(100, 10), Some(100));
+        assert_eq!(up_k(101, 10), Some(1000));
+        assert_eq!(up_k(1 << 63, 2), Some(1 << 63));
+        assert_eq!(up_k((1 << 63) + 1, 2), None);
+        assert_eq!(up_k(5, 1), None);
+        assert_eq!(up_k(5, 0), None);
+
+        assert_eq!(down_k(1, 3), Some(1));
+        assert_eq!(down_k(26, 3), Some(9));
+        assert_eq!(down_k(27, 3), Some(27));
+        assert_eq!(down_k(u64::MAX, 2), Some(1 << 63));
+        assert_eq!(down_k(0, 3), None);
+        assert_eq!(down_k(5, 1), None);
+
+        assert_eq!(ilog_k(1, 2), Some(0));
+        assert_eq!(ilog_k(1000, 10), Some(3));
+        assert_eq!(ilog_k(999, 10), Some(2));
+        assert_eq!(ilog_k(u64::MAX, 3), Some(40));
+        assert_eq!(ilog_k(0, 10), None);
+        assert_eq!(ilog_k(10, 1), None);
+
+        assert!(is_power_of(1, 7));
+        assert!(is_power_of(49, 7));
+        assert!(!is_power_of(50, 7));
+        assert!(!is_power_of(0, 7));
+        assert!(is_power_of(3u64.pow(40), 3));
+        assert!(is_power_of(1, 1));
+        assert!(!is_power_of(2, 1));
+        assert!(is_power_of(0, 0));
+        assert!(!is_power_of(2, 0));
+    }
+
     #[test]
     fn test_gcd() {
         assert_eq!(gcd(5, 1), 1);
@@ -39,6 +76,40 @@ mod test {
         assert_eq!(gcd(0, 33), 33);
     }
 
+    #[test]
+    fn test_gcd_zero_and_lcm() {
+        assert_eq!(gcd(33, 0), 33);
+        assert_eq!(gcd(0, 0), 0);
+
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(5, 1), 5);
+        assert_eq!(lcm(0, 7), 0);
+        assert_eq!(lcm(7, 0), 0);
+        assert_eq!(lcm(1 << 40, 1 << 41), 1 << 41);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+        assert_eq!(extended_gcd(0, 0), (0, 1, 0));
+        assert_eq!(extended_gcd(0, 5).0, 5);
+        assert_eq!(extended_gcd(5, 0), (5, 1, 0));
+
+        for (a, b) in [
+            (3, 7),
+            (-12, 18),
+            (12, -18),
+            (-4, -6),
+            (0, -3),
+            (99, 78),
+            (1, 1),
+        ] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a.unsigned_abs(), b.unsigned_abs()) as i64);
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
     #[test]
     fn test_sum_array() {
         assert_eq!(sum_array(&[]), 0);
@@ -48,12 +119,12 @@ mod test {
 
     #[test]
     fn test_chooses() {
-        assert_eq!(chooses(0), vec![1]);
-        assert_eq!(chooses(1), vec![1, 1]);
-        assert_eq!(chooses(5), vec![1, 5, 10, 10, 5, 1]);
-        assert_eq!(chooses(6), vec![1, 6, 15, 20, 15, 6, 1]);
+        assert_eq!(chooses(0).unwrap(), vec![1]);
+        assert_eq!(chooses(1).unwrap(), vec![1, 1]);
+        assert_eq!(chooses(5).unwrap(), vec![1, 5, 10, 10, 5, 1]);
+        assert_eq!(chooses(6).unwrap(), vec![1, 6, 15, 20, 15, 6, 1]);
         assert_eq!(
-            chooses(67),
+            chooses(67).unwrap(),
             vec![
                 1,
                 67,
@@ -127,11 +198,72 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_chooses_overflow() {
+        assert_eq!(chooses(68), Err(OverflowError));
+        assert_eq!(chooses(u64::MAX), Err(OverflowError));
+
+        assert_eq!(binomial(5, 2), Ok(10));
+        assert_eq!(binomial(5, 6), Ok(0));
+        assert_eq!(binomial(0, 0), Ok(1));
+        assert_eq!(binomial(67, 33), Ok(14226520737620288370));
+        assert_eq!(binomial(68, 34), Err(OverflowError));
+        assert_eq!(binomial(68, 1), Ok(68));
+        assert_eq!(binomial(u64::MAX, u64::MAX - 1), Ok(u64::MAX));
+        for k in 0..=67 {
+            assert_eq!(binomial(67, k), Ok(chooses(67).unwrap()[k as usize]));
+        }
+
+        let carriers = |row: Vec<BigInt>| row.into_iter().map(|c| c.carrier).collect::<Vec<_>>();
+        assert_eq!(
+            carriers(chooses_big(67)),
+            carriers(chooses(67).unwrap().into_iter().map(BigInt::from).collect())
+        );
+        // 68C34 = 28453041475240576740 = 0x1_8add8278_972bc6e4
+        let row = chooses_big(68);
+        assert_eq!(row.len(), 69);
+        assert_eq!(row[34].carrier, vec![1, 0x8add8278, 0x972bc6e4]);
+        assert_eq!(row[68].carrier, vec![1]);
+    }
+
     #[test]
     fn test_zip() {
         assert_eq!(zip(vec![1, 2], vec![4, 5]), vec![(1, 4), (2, 5)]);
         assert_eq!(zip(vec![1, 2, 3], vec![4, 5]), vec![(1, 4), (2, 5)]);
         assert_eq!(zip(vec![1, 2], vec![4, 5, 6]), vec![(1, 4), (2, 5)]);
-        assert_eq!(zip(vec![], vec![4, 5]), vec![]);
+        assert_eq!(zip(Vec::<u64>::new(), vec![4, 5]), vec![]);
+    }
+
+    #[test]
+    fn test_zip_generic() {
+        assert_eq!(
+            zip(vec!["a", "b", "c"], vec!['x', 'y']),
+            vec![("a", 'x'), ("b", 'y')]
+        );
+        assert_eq!(
+            zip(vec![1.5], vec![String::from("s")]),
+            vec![(1.5, String::from("s"))]
+        );
+
+        assert_eq!(
+            zip_with(|x, y| x + y, vec![1, 2, 3], vec![4, 5]),
+            vec![5, 7]
+        );
+        assert_eq!(
+            zip_with(|s: &str, n| s.repeat(n), vec!["ab", "c"], vec![2, 3]),
+            vec!["abab".to_string(), "ccc".to_string()]
+        );
+        assert!(zip_with(|x: u8, y: u8| x * y, vec![], vec![1, 2]).is_empty());
+
+        let mut calls = 0;
+        let _unused = zip_with(
+            |x: u8, y: u8| {
+                calls += 1;
+                x.max(y)
+            },
+            vec![1, 2],
+            vec![3, 4, 5],
+        );
+        assert_eq!(calls, 2);
     }
 }
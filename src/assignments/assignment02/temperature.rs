@@ -0,0 +1,124 @@
+//! Temperature conversion.
+
+use std::fmt;
+
+const FAHRENHEIT_OFFSET: f64 = 32.0;
+const KELVIN_OFFSET: f64 = 273.15;
+
+/// Converts Fahrenheit to Celsius temperature degree.
+pub fn fahrenheit_to_celsius(degree: f64) -> f64 {
+    (degree - FAHRENHEIT_OFFSET) * 5.0 / 9.0
+}
+
+/// Converts Celsius to Fahrenheit temperature degree.
+pub fn celsius_to_fahrenheit(degree: f64) -> f64 {
+    degree * 9.0 / 5.0 + FAHRENHEIT_OFFSET
+}
+
+/// Converts Celsius to Kelvin temperature.
+pub fn celsius_to_kelvin(degree: f64) -> f64 {
+    degree + KELVIN_OFFSET
+}
+
+/// Converts Kelvin to Celsius temperature degree.
+pub fn kelvin_to_celsius(kelvin: f64) -> f64 {
+    kelvin - KELVIN_OFFSET
+}
+
+/// Converts Fahrenheit to Kelvin temperature.
+pub fn fahrenheit_to_kelvin(degree: f64) -> f64 {
+    celsius_to_kelvin(fahrenheit_to_celsius(degree))
+}
+
+/// Converts Kelvin to Fahrenheit temperature degree.
+pub fn kelvin_to_fahrenheit(kelvin: f64) -> f64 {
+    celsius_to_fahrenheit(kelvin_to_celsius(kelvin))
+}
+
+/// Temperature unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+    /// Degree Celsius.
+    Celsius,
+    /// Degree Fahrenheit.
+    Fahrenheit,
+    /// Kelvin.
+    Kelvin,
+}
+
+impl Unit {
+    /// The unit symbol, e.g. `°C`.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Unit::Celsius => "°C",
+            Unit::Fahrenheit => "°F",
+            Unit::Kelvin => "K",
+        }
+    }
+}
+
+/// Temperature in a unit.
+///
+/// Equality compares the values and units as they are, so `0°C` is not equal to `32°F`; convert
+/// to the same unit first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Temperature {
+    value: f64,
+    unit: Unit,
+}
+
+impl Temperature {
+    /// Creates a temperature.
+    pub fn new(value: f64, unit: Unit) -> Self {
+        Self { value, unit }
+    }
+
+    /// Creates a temperature in degree Celsius.
+    pub fn celsius(value: f64) -> Self {
+        Self::new(value, Unit::Celsius)
+    }
+
+    /// Creates a temperature in degree Fahrenheit.
+    pub fn fahrenheit(value: f64) -> Self {
+        Self::new(value, Unit::Fahrenheit)
+    }
+
+    /// Creates a temperature in Kelvin.
+    pub fn kelvin(value: f64) -> Self {
+        Self::new(value, Unit::Kelvin)
+    }
+
+    /// Returns the value in the temperature's unit.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Returns the unit.
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    /// Converts the temperature to the unit.
+    ///
+    /// `Temperature::fahrenheit(212.0).to(Unit::Celsius)` is `Temperature::celsius(100.0)`.
+    pub fn to(self, unit: Unit) -> Self {
+        let value = match (self.unit, unit) {
+            (from, to) if from == to => self.value,
+            (Unit::Celsius, Unit::Fahrenheit) => celsius_to_fahrenheit(self.value),
+            (Unit::Celsius, Unit::Kelvin) => celsius_to_kelvin(self.value),
+            (Unit::Fahrenheit, Unit::Celsius) => fahrenheit_to_celsius(self.value),
+            (Unit::Fahrenheit, Unit::Kelvin) => fahrenheit_to_kelvin(self.value),
+            (Unit::Kelvin, Unit::Celsius) => kelvin_to_celsius(self.value),
+            (Unit::Kelvin, Unit::Fahrenheit) => kelvin_to_fahrenheit(self.value),
+            _ => unreachable!("same units are handled above"),
+        };
+        Self::new(value, unit)
+    }
+}
+
+impl fmt::Display for Temperature {
+    /// Formats like `100°C` or `273.15K`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.value, self.unit.symbol())
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod test {
+    use crate::assignments::assignment02::temperature::*;
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(fahrenheit_to_celsius(32.0), 0.0);
+        assert_eq!(fahrenheit_to_celsius(212.0), 100.0);
+        assert_eq!(celsius_to_fahrenheit(0.0), 32.0);
+        assert_eq!(celsius_to_fahrenheit(100.0), 212.0);
+        assert_eq!(celsius_to_fahrenheit(-40.0), -40.0);
+        assert_eq!(celsius_to_kelvin(0.0), 273.15);
+        assert_eq!(kelvin_to_celsius(0.0), -273.15);
+        assert!((fahrenheit_to_kelvin(212.0) - 373.15).abs() < 1e-9);
+        assert!((kelvin_to_fahrenheit(0.0) - -459.67).abs() < 1e-9);
+
+        for degree in [-100.0, -1.5, 0.0, 36.6, 1000.0] {
+            assert!((fahrenheit_to_celsius(celsius_to_fahrenheit(degree)) - degree).abs() < 1e-9);
+            assert!((kelvin_to_fahrenheit(fahrenheit_to_kelvin(degree)) - degree).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_temperature() {
+        let boiling = Temperature::fahrenheit(212.0);
+        assert_eq!(boiling.unit(), Unit::Fahrenheit);
+        assert_eq!(boiling.to(Unit::Celsius), Temperature::celsius(100.0));
+        assert_eq!(boiling.to(Unit::Fahrenheit), boiling);
+        assert_ne!(Temperature::celsius(0.0), Temperature::fahrenheit(32.0));
+
+        let kelvin = Temperature::celsius(-273.15).to(Unit::Kelvin);
+        assert_eq!(kelvin.unit(), Unit::Kelvin);
+        assert_eq!(kelvin.value(), 0.0);
+        assert_eq!(
+            Temperature::new(0.0, Unit::Kelvin).to(Unit::Celsius),
+            Temperature::celsius(-273.15)
+        );
+
+        assert_eq!(Temperature::celsius(100.0).to_string(), "100°C");
+        assert_eq!(Temperature::fahrenheit(-40.5).to_string(), "-40.5°F");
+        assert_eq!(Temperature::kelvin(273.15).to_string(), "273.15K");
+    }
+}
@@ -0,0 +1,131 @@
+//! Calendar dates.
+//!
+//! Dates are in the proleptic Gregorian calendar, and their days of week are computed with
+//! Sakamoto's method.
+
+use super::small_exercises::DayOfWeek;
+
+/// Calendar date.
+///
+/// Dates are ordered chronologically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+/// Returns `true` if the year has 29 February.
+pub fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// Returns the number of days in the month, or `None` if the month is not in `1..=12`.
+pub fn days_in_month(year: i32, month: u32) -> Option<u32> {
+    Some(match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => return None,
+    })
+}
+
+impl Date {
+    /// Creates a date, or returns `None` if there is no such date.
+    ///
+    /// `Date::new(2024, 2, 29)` is valid; but `Date::new(2023, 2, 29)` is not.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        let days = days_in_month(year, month)?;
+        (1..=days)
+            .contains(&day)
+            .then_some(Self { year, month, day })
+    }
+
+    /// Returns the year.
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Returns the month, in `1..=12`.
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    /// Returns the day of month, starting from 1.
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// Returns the day of week.
+    pub fn day_of_week(&self) -> DayOfWeek {
+        const OFFSETS: [i64; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+
+        let year = i64::from(self.year) - i64::from(self.month < 3);
+        let days = year + year.div_euclid(4) - year.div_euclid(100)
+            + year.div_euclid(400)
+            + OFFSETS[self.month as usize - 1]
+            + i64::from(self.day);
+        // `DayOfWeek::Sun` is 0.
+        DayOfWeek::Sun.add_days(days)
+    }
+
+    /// The next date.
+    pub fn succ(&self) -> Self {
+        if let Some(date) = Self::new(self.year, self.month, self.day + 1) {
+            date
+        } else if self.month < 12 {
+            Self {
+                month: self.month + 1,
+                day: 1,
+                ..*self
+            }
+        } else {
+            Self {
+                year: self.year + 1,
+                month: 1,
+                day: 1,
+            }
+        }
+    }
+
+    /// The previous date.
+    pub fn pred(&self) -> Self {
+        if self.day > 1 {
+            Self {
+                day: self.day - 1,
+                ..*self
+            }
+        } else if self.month > 1 {
+            let month = self.month - 1;
+            Self {
+                month,
+                day: days_in_month(self.year, month).unwrap_or(31),
+                ..*self
+            }
+        } else {
+            Self {
+                year: self.year - 1,
+                month: 12,
+                day: 31,
+            }
+        }
+    }
+
+    /// The date `n` business days (i.e. weekdays) later, or earlier if `n` is negative.
+    ///
+    /// Friday 2024-03-01 plus 1 business day is Monday 2024-03-04. If `n` is 0, the date is
+    /// returned as it is even if it is on a weekend.
+    pub fn add_business_days(&self, n: i64) -> Self {
+        let mut date = *self;
+        for _ in 0..n.unsigned_abs() {
+            loop {
+                date = if n > 0 { date.succ() } else { date.pred() };
+                if !date.day_of_week().is_weekend() {
+                    break;
+                }
+            }
+        }
+        date
+    }
+}
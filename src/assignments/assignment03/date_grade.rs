@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod test {
+    use crate::assignments::assignment03::date::*;
+    use crate::assignments::assignment03::small_exercises::DayOfWeek;
+
+    #[test]
+    fn test_date_new() {
+        assert!(Date::new(2024, 2, 29).is_some());
+        assert!(Date::new(2023, 2, 29).is_none());
+        assert!(Date::new(2000, 2, 29).is_some());
+        assert!(Date::new(1900, 2, 29).is_none());
+        assert!(Date::new(2024, 4, 31).is_none());
+        assert!(Date::new(2024, 13, 1).is_none());
+        assert!(Date::new(2024, 1, 0).is_none());
+
+        let date = Date::new(2024, 12, 25).unwrap();
+        assert_eq!((date.year(), date.month(), date.day()), (2024, 12, 25));
+
+        assert_eq!(days_in_month(2024, 2), Some(29));
+        assert_eq!(days_in_month(2024, 0), None);
+    }
+
+    #[test]
+    fn test_date_day_of_week() {
+        let day_of_week = |year, month, day| Date::new(year, month, day).unwrap().day_of_week();
+
+        assert_eq!(day_of_week(1970, 1, 1), DayOfWeek::Thu);
+        assert_eq!(day_of_week(2000, 1, 1), DayOfWeek::Sat);
+        assert_eq!(day_of_week(2000, 2, 29), DayOfWeek::Tue);
+        assert_eq!(day_of_week(2024, 3, 1), DayOfWeek::Fri);
+        assert_eq!(day_of_week(2024, 12, 25), DayOfWeek::Wed);
+        assert_eq!(day_of_week(1582, 10, 15), DayOfWeek::Fri);
+        assert_eq!(day_of_week(0, 1, 1), DayOfWeek::Sat);
+        assert_eq!(day_of_week(-1, 12, 31), DayOfWeek::Fri);
+    }
+
+    #[test]
+    fn test_date_arithmetic() {
+        let date = |year, month, day| Date::new(year, month, day).unwrap();
+
+        assert_eq!(date(2023, 12, 31).succ(), date(2024, 1, 1));
+        assert_eq!(date(2024, 2, 28).succ(), date(2024, 2, 29));
+        assert_eq!(date(2024, 3, 1).pred(), date(2024, 2, 29));
+        assert_eq!(date(2024, 1, 1).pred(), date(2023, 12, 31));
+
+        assert_eq!(date(2024, 3, 1).add_business_days(1), date(2024, 3, 4));
+        assert_eq!(date(2024, 3, 1).add_business_days(5), date(2024, 3, 8));
+        assert_eq!(date(2024, 3, 2).add_business_days(1), date(2024, 3, 4));
+        assert_eq!(date(2024, 3, 2).add_business_days(0), date(2024, 3, 2));
+        assert_eq!(date(2024, 3, 4).add_business_days(-1), date(2024, 3, 1));
+        assert_eq!(date(2023, 12, 29).add_business_days(1), date(2024, 1, 1));
+
+        assert!(date(2023, 12, 31) < date(2024, 1, 1));
+        assert!(date(2024, 2, 1) > date(2024, 1, 31));
+        assert!(date(2024, 2, 2) > date(2024, 2, 1));
+        let mut dates = vec![date(2024, 3, 1), date(1999, 12, 31), date(2024, 1, 15)];
+        dates.sort();
+        assert_eq!(
+            dates,
+            vec![date(1999, 12, 31), date(2024, 1, 15), date(2024, 3, 1)]
+        );
+    }
+}
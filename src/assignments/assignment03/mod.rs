@@ -13,6 +13,9 @@
 pub mod small_exercises;
 mod small_exercises_grade;
 
+pub mod date;
+mod date_grade;
+
 pub mod parse_shell;
 mod parse_shell_grade;
 
@@ -6,9 +6,8 @@
 //!
 //! You will parse a given string consists of a small number of shell commands.
 
-use core::prelude::v1;
-
-use itertools::Itertools;
+use std::collections::HashMap;
+use std::fmt;
 
 /// Parse the string as a shell command.
 ///
@@ -26,33 +25,520 @@ use itertools::Itertools;
 /// ls VirtualBox' 'VMs  -->  ["ls", 'VirtualBox VMs']
 /// ```
 ///
-/// For simplicity, you may assume that the string only contains alphanumeric characters, spaces
-/// (" "), and single quotes ("'").
+/// Double quotes work like single quotes, except that `\"`, `\\`, and `\'` inside them are escapes
+/// for `"`, `\`, and `'`, respectively. Outside quotes, a backslash escapes the next character.
+///
+/// ```text
+/// echo "it's \"quoted\""  -->  ["echo", 'it's "quoted"']
+/// echo 'a "b"'" 'c'"  -->  ["echo", 'a "b" 'c'']
+/// ```
+///
+/// Returns an error if the command is empty, a quote is not closed, or the command ends with a
+/// backslash outside quotes.
 ///
 /// See `test_shell` for more examples.
-pub fn parse_shell_command(command: &str) -> Vec<String> {
-    let mut result: Vec<String> = Vec::new();
-    let v: Vec<&str> = command.splitn(2, ' ').collect();
-    let mut buf: String;
+pub fn parse_shell_command(command: &str) -> Result<Vec<String>, ShellParseError> {
+    parse_shell_command_with(command, Expansions::new())
+}
+
+/// Parse the string as a shell command like [`parse_shell_command`], applying the expansions to
+/// the arguments.
+pub fn parse_shell_command_with(
+    command: &str,
+    expansions: Expansions<'_>,
+) -> Result<Vec<String>, ShellParseError> {
+    let result = expand(tokenize(command, false)?, expansions)?
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Word(word) => Some(word),
+            Token::Op(_) => None,
+        })
+        .collect::<Vec<_>>();
+    if result.is_empty() {
+        return Err(ShellParseError::Empty);
+    }
 
-    result.push(v[0].to_string());
+    Ok(result)
+}
 
-    match v[1].find('\'') {
-        Some(0) => {
-            buf = v[1].to_string();
-            if let Some(x) = buf.pop() {}
-            let _unused = buf.remove(0);
-            result.push(buf);
+/// Parse the string as a command line of pipelines, redirections and sequencing.
+///
+/// ```text
+/// cat a | sort > b; ls && echo ok  -->  [cat a | sort (> b)] ; [ls] && [echo ok]
+/// ```
+///
+/// Operators are `|`, `>`, `<`, `>>`, `;`, `&&`, and `||`, which need not be surrounded by
+/// whitespaces. Quoted or escaped operators are regular arguments, and a trailing `;` is allowed.
+pub fn parse_shell_pipeline(command: &str) -> Result<Sequence, ShellParseError> {
+    parse_shell_pipeline_with(command, Expansions::new())
+}
+
+/// Parse the string as a command line like [`parse_shell_pipeline`], applying the expansions to
+/// the arguments and redirection targets.
+pub fn parse_shell_pipeline_with(
+    command: &str,
+    expansions: Expansions<'_>,
+) -> Result<Sequence, ShellParseError> {
+    let mut tokens = expand(tokenize(command, true)?, expansions)?
+        .into_iter()
+        .peekable();
+    if tokens.peek().is_none() {
+        return Err(ShellParseError::Empty);
+    }
+
+    let first = parse_pipeline(&mut tokens)?;
+    let mut rest = Vec::new();
+    while let Some(token) = tokens.next() {
+        let connector = match token {
+            Token::Op(Operator::Semicolon) => {
+                if tokens.peek().is_none() {
+                    break;
+                }
+                Connector::Then
+            }
+            Token::Op(Operator::And) => Connector::And,
+            Token::Op(Operator::Or) => Connector::Or,
+            _ => return Err(ShellParseError::MissingCommand),
+        };
+        rest.push((connector, parse_pipeline(&mut tokens)?));
+    }
+
+    Ok(Sequence { first, rest })
+}
+
+fn parse_pipeline(
+    tokens: &mut std::iter::Peekable<std::vec::IntoIter<Token>>,
+) -> Result<Pipeline, ShellParseError> {
+    let mut commands = vec![parse_simple_command(tokens)?];
+    while tokens.next_if_eq(&Token::Op(Operator::Pipe)).is_some() {
+        commands.push(parse_simple_command(tokens)?);
+    }
+    Ok(Pipeline { commands })
+}
+
+fn parse_simple_command(
+    tokens: &mut std::iter::Peekable<std::vec::IntoIter<Token>>,
+) -> Result<SimpleCommand, ShellParseError> {
+    let mut command = SimpleCommand::default();
+    loop {
+        let kind = match tokens.peek() {
+            Some(Token::Word(_)) => {
+                if let Some(Token::Word(word)) = tokens.next() {
+                    command.args.push(word);
+                }
+                continue;
+            }
+            Some(Token::Op(Operator::Input)) => RedirectKind::Input,
+            Some(Token::Op(Operator::Output)) => RedirectKind::Output,
+            Some(Token::Op(Operator::Append)) => RedirectKind::Append,
+            _ => break,
+        };
+        let _unused = tokens.next();
+        match tokens.next() {
+            Some(Token::Word(target)) => command.redirects.push(Redirect { kind, target }),
+            _ => return Err(ShellParseError::MissingRedirectTarget),
         }
-        Some(_) => {
-            let tmp: String = v[1].split('\'').collect();
-            result.push(tmp);
+    }
+    if command.args.is_empty() {
+        return Err(ShellParseError::MissingCommand);
+    }
+    Ok(command)
+}
+
+/// Expansions applied to the arguments after tokenization.
+///
+/// Quoting is respected: nothing is expanded inside single quotes or after a backslash, and
+/// wildcards are only expanded outside quotes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Expansions<'a> {
+    vars: Option<&'a HashMap<String, String>>,
+    files: Option<&'a [String]>,
+}
+
+impl<'a> Expansions<'a> {
+    /// Creates an empty set of expansions, which leaves the arguments as they are.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expands `$VAR` and `${VAR}` to the value of `VAR` in `vars`, or to the empty string if it is
+    /// not defined, and `$$` to `$`. Inside double quotes, `\$` is also an escape for `$`.
+    ///
+    /// An unquoted argument that expands to the empty string is removed.
+    pub fn vars(self, vars: &'a HashMap<String, String>) -> Self {
+        Self {
+            vars: Some(vars),
+            ..self
+        }
+    }
+
+    /// Expands arguments with the wildcards `*` (any string) and `?` (any character) to the
+    /// matching names in `files`, in sorted order. The file system is not accessed.
+    ///
+    /// An argument that matches no file is left as it is.
+    ///
+    /// ```text
+    /// ls *.rs  -->  ["ls", "lib.rs", "main.rs"]  (for files ["Cargo.toml", "main.rs", "lib.rs"])
+    /// ```
+    pub fn files(self, files: &'a [String]) -> Self {
+        Self {
+            files: Some(files),
+            ..self
+        }
+    }
+}
+
+// Expand the words of the raw tokens
+fn expand(
+    tokens: Vec<RawToken>,
+    expansions: Expansions<'_>,
+) -> Result<Vec<Token>, ShellParseError> {
+    let mut result = Vec::new();
+    for token in tokens {
+        let word = match token {
+            RawToken::Op(op) => {
+                result.push(Token::Op(op));
+                continue;
+            }
+            RawToken::Word(word) => word,
+        };
+
+        let mut expanded = String::new();
+        // Characters of `expanded`, and whether each is a wildcard
+        let mut pattern = Vec::new();
+        for piece in &word {
+            let start = expanded.len();
+            match (piece.quoting, expansions.vars) {
+                (Quoting::Unquoted | Quoting::Double, Some(vars)) => {
+                    expand_vars(&piece.text, vars, &mut expanded)?
+                }
+                _ => expanded.push_str(&piece.text),
+            }
+            let unquoted = piece.quoting == Quoting::Unquoted;
+            pattern.extend(
+                expanded[start..]
+                    .chars()
+                    .map(|c| (c, unquoted && matches!(c, '*' | '?'))),
+            );
+        }
+
+        if let Some(files) = expansions.files {
+            if pattern.iter().any(|&(_, wildcard)| wildcard) {
+                let mut matches = files
+                    .iter()
+                    .filter(|file| glob_match(&pattern, &file.chars().collect::<Vec<_>>()))
+                    .collect::<Vec<_>>();
+                if !matches.is_empty() {
+                    matches.sort();
+                    result.extend(matches.into_iter().cloned().map(Token::Word));
+                    continue;
+                }
+            }
         }
-        None => {
-            let tmp: Vec<String> = v[1].split_whitespace().map_into().collect();
-            result.extend(tmp);
+
+        let unquoted = word.iter().all(|piece| piece.quoting == Quoting::Unquoted);
+        if !(unquoted && expanded.is_empty()) {
+            result.push(Token::Word(expanded));
         }
     }
+    Ok(result)
+}
 
-    result
+// Match the name against the pattern of characters and wildcard flags
+fn glob_match(pattern: &[(char, bool)], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((('*', true), rest)) => (0..=name.len()).any(|i| glob_match(rest, &name[i..])),
+        Some((&(c, wildcard), rest)) => match name.split_first() {
+            Some((&n, name)) => (n == c || (wildcard && c == '?')) && glob_match(rest, name),
+            None => false,
+        },
+    }
 }
+
+fn expand_vars(
+    text: &str,
+    vars: &HashMap<String, String>,
+    out: &mut String,
+) -> Result<(), ShellParseError> {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut rest = text;
+    while let Some(i) = rest.find('$') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i + 1..];
+
+        let (name, len) = if let Some(braced) = rest.strip_prefix('{') {
+            let end = braced.find('}').ok_or(ShellParseError::BadSubstitution)?;
+            let name = &braced[..end];
+            if name.is_empty()
+                || name.starts_with(|c: char| c.is_ascii_digit())
+                || !name.chars().all(is_name_char)
+            {
+                return Err(ShellParseError::BadSubstitution);
+            }
+            (name, end + 2)
+        } else if rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            let end = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+            (&rest[..end], end)
+        } else {
+            // `$$`, or a `$` not followed by a name
+            out.push('$');
+            rest = rest.strip_prefix('$').unwrap_or(rest);
+            continue;
+        };
+
+        if let Some(value) = vars.get(name) {
+            out.push_str(value);
+        }
+        rest = &rest[len..];
+    }
+    out.push_str(rest);
+    Ok(())
+}
+
+// Split the command into words, and operators if `operators` is set
+fn tokenize(command: &str, operators: bool) -> Result<Vec<RawToken>, ShellParseError> {
+    let mut result = Vec::new();
+    let mut word: Vec<Piece> = Vec::new();
+    // Whether `word` is an argument, which may be empty as in `''`.
+    let mut in_arg = false;
+    let mut state = State::Unquoted;
+    let mut chars = command.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match (state, c) {
+            (State::Unquoted, c)
+                if c.is_whitespace() || (operators && is_operator_start(c, chars.peek())) =>
+            {
+                if in_arg {
+                    result.push(RawToken::Word(std::mem::take(&mut word)));
+                    in_arg = false;
+                }
+                if c.is_whitespace() {
+                    continue;
+                }
+                let doubled =
+                    matches!(c, '|' | '&' | '>') && chars.next_if(|&(_, next)| next == c).is_some();
+                let op = match (c, doubled) {
+                    ('|', false) => Operator::Pipe,
+                    ('|', true) => Operator::Or,
+                    ('&', _) => Operator::And,
+                    ('<', _) => Operator::Input,
+                    ('>', false) => Operator::Output,
+                    ('>', true) => Operator::Append,
+                    _ => Operator::Semicolon,
+                };
+                result.push(RawToken::Op(op));
+            }
+            (State::Unquoted, '\\') => {
+                let (_, c) = chars.next().ok_or(ShellParseError::TrailingBackslash)?;
+                push(&mut word, c, Quoting::Literal);
+                in_arg = true;
+            }
+            (State::Unquoted, '\'') => {
+                state = State::Single(i);
+                open(&mut word, Quoting::Literal);
+                in_arg = true;
+            }
+            (State::Unquoted, '"') => {
+                state = State::Double(i);
+                open(&mut word, Quoting::Double);
+                in_arg = true;
+            }
+            (State::Single(_), '\'') | (State::Double(_), '"') => state = State::Unquoted,
+            (State::Double(start), '\\') => match chars.next() {
+                Some((_, c @ ('"' | '\\' | '\'' | '$'))) => push(&mut word, c, Quoting::Literal),
+                Some((_, c)) => {
+                    push(&mut word, '\\', Quoting::Double);
+                    push(&mut word, c, Quoting::Double);
+                }
+                None => return Err(ShellParseError::UnclosedQuote(start)),
+            },
+            (state, c) => {
+                let quoting = match state {
+                    State::Unquoted => Quoting::Unquoted,
+                    State::Single(_) => Quoting::Literal,
+                    State::Double(_) => Quoting::Double,
+                };
+                push(&mut word, c, quoting);
+                in_arg = true;
+            }
+        }
+    }
+
+    match state {
+        State::Unquoted => {}
+        State::Single(start) | State::Double(start) => {
+            return Err(ShellParseError::UnclosedQuote(start))
+        }
+    }
+    if in_arg {
+        result.push(RawToken::Word(word));
+    }
+
+    Ok(result)
+}
+
+// Push `c` to the word, merging it into the last piece if it has the same quoting
+fn push(word: &mut Vec<Piece>, c: char, quoting: Quoting) {
+    match word.last_mut() {
+        Some(piece) if piece.quoting == quoting => piece.text.push(c),
+        _ => word.push(Piece {
+            text: c.to_string(),
+            quoting,
+        }),
+    }
+}
+
+// Open a quote, which makes the word an argument even if the quote is empty
+fn open(word: &mut Vec<Piece>, quoting: Quoting) {
+    word.push(Piece {
+        text: String::new(),
+        quoting,
+    });
+}
+
+// A lone `&` is a regular character.
+fn is_operator_start(c: char, next: Option<&(usize, char)>) -> bool {
+    matches!(c, '|' | '<' | '>' | ';') || (c == '&' && matches!(next, Some((_, '&'))))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Op(Operator),
+}
+
+// Token before expansion
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RawToken {
+    Word(Vec<Piece>),
+    Op(Operator),
+}
+
+// Part of a word with the same quoting
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Piece {
+    text: String,
+    quoting: Quoting,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quoting {
+    Unquoted,
+    Double,
+    // Single-quoted or escaped
+    Literal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Pipe,
+    Input,
+    Output,
+    Append,
+    Semicolon,
+    And,
+    Or,
+}
+
+/// Command with its arguments and redirections, e.g. `sort -r < in > out`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SimpleCommand {
+    /// Arguments, including the command name. Never empty.
+    pub args: Vec<String>,
+    /// Redirections, in order of appearance.
+    pub redirects: Vec<Redirect>,
+}
+
+/// Redirection of a command's input or output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redirect {
+    /// Kind of the redirection.
+    pub kind: RedirectKind,
+    /// File name.
+    pub target: String,
+}
+
+/// Kind of a redirection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedirectKind {
+    /// `<`: reads the input from the file.
+    Input,
+    /// `>`: writes the output to the file.
+    Output,
+    /// `>>`: appends the output to the file.
+    Append,
+}
+
+/// Commands connected with `|`, e.g. `cat a | sort`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pipeline {
+    /// Commands, in order. Never empty.
+    pub commands: Vec<SimpleCommand>,
+}
+
+/// How a pipeline is connected to the previous one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connector {
+    /// `;`: runs it unconditionally.
+    Then,
+    /// `&&`: runs it if the previous one succeeded.
+    And,
+    /// `||`: runs it if the previous one failed.
+    Or,
+}
+
+/// Pipelines connected with `;`, `&&`, or `||`, e.g. `make && ./a.out; echo done`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequence {
+    /// The first pipeline.
+    pub first: Pipeline,
+    /// The following pipelines, with their connectors.
+    pub rest: Vec<(Connector, Pipeline)>,
+}
+
+// State of the tokenizer, with the byte position of the opening quote
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Unquoted,
+    Single(usize),
+    Double(usize),
+}
+
+/// Error of parsing a shell command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellParseError {
+    /// The command has no arguments.
+    Empty,
+
+    /// A quote is not closed. It contains the byte position of the opening quote.
+    UnclosedQuote(usize),
+
+    /// The command ends with a backslash outside quotes.
+    TrailingBackslash,
+
+    /// An operator is not preceded or followed by a command, as in `ls |` or `&& ls`.
+    MissingCommand,
+
+    /// A redirection has no file name, as in `ls >`.
+    MissingRedirectTarget,
+
+    /// A `${...}` expansion is not closed or has an invalid name.
+    BadSubstitution,
+}
+
+impl fmt::Display for ShellParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShellParseError::Empty => write!(f, "empty command"),
+            ShellParseError::UnclosedQuote(pos) => write!(f, "unclosed quote at byte {pos}"),
+            ShellParseError::TrailingBackslash => write!(f, "trailing backslash"),
+            ShellParseError::MissingCommand => write!(f, "missing command around an operator"),
+            ShellParseError::MissingRedirectTarget => write!(f, "missing redirection target"),
+            ShellParseError::BadSubstitution => write!(f, "bad substitution"),
+        }
+    }
+}
+
+impl std::error::Error for ShellParseError {}
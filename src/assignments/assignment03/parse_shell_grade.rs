@@ -1,23 +1,25 @@
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
+
     use crate::assignments::assignment03::parse_shell::*;
 
     #[test]
     fn test_shell() {
         assert_eq!(
-            parse_shell_command("cat file"),
+            parse_shell_command("cat file").unwrap(),
             vec!["cat".to_string(), "file".to_string()]
         );
         assert_eq!(
-            parse_shell_command("ls 'VirtualBox VMs'"),
+            parse_shell_command("ls 'VirtualBox VMs'").unwrap(),
             vec!["ls".to_string(), "VirtualBox VMs".to_string()]
         );
         assert_eq!(
-            parse_shell_command("ls VirtualBox' 'VMs"),
+            parse_shell_command("ls VirtualBox' 'VMs").unwrap(),
             vec!["ls".to_string(), "VirtualBox VMs".to_string()]
         );
         assert_eq!(
-            parse_shell_command("echo once upon a midnight dreary"),
+            parse_shell_command("echo once upon a midnight dreary").unwrap(),
             vec![
                 "echo".to_string(),
                 "once".to_string(),
@@ -28,11 +30,295 @@ mod test {
             ]
         );
         assert_eq!(
-            parse_shell_command("echo 'once upon a midnight dreary'"),
+            parse_shell_command("echo 'once upon a midnight dreary'").unwrap(),
             vec![
                 "echo".to_string(),
                 "once upon a midnight dreary".to_string(),
             ]
         );
     }
+
+    #[test]
+    fn test_shell_double_quotes() {
+        assert_eq!(
+            parse_shell_command(r#"ls "VirtualBox VMs""#).unwrap(),
+            vec!["ls".to_string(), "VirtualBox VMs".to_string()]
+        );
+        assert_eq!(
+            parse_shell_command(r#"echo "it's \"quoted\" \\ \'""#).unwrap(),
+            vec!["echo".to_string(), r#"it's "quoted" \ '"#.to_string()]
+        );
+        assert_eq!(
+            parse_shell_command(r#"echo 'a "b"'" 'c'"d"#).unwrap(),
+            vec!["echo".to_string(), r#"a "b" 'c'd"#.to_string()]
+        );
+        assert_eq!(
+            parse_shell_command(r#"echo 'back\slash' "back\slash" back\ slash"#).unwrap(),
+            vec![
+                "echo".to_string(),
+                r"back\slash".to_string(),
+                r"back\slash".to_string(),
+                "back slash".to_string(),
+            ]
+        );
+        assert_eq!(
+            parse_shell_command(r#"printf "" '' x"#).unwrap(),
+            vec![
+                "printf".to_string(),
+                String::new(),
+                String::new(),
+                "x".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shell_errors() {
+        assert_eq!(parse_shell_command("ls").unwrap(), vec!["ls".to_string()]);
+        assert_eq!(
+            parse_shell_command("  a  b\tc  'd'e \"\" ").unwrap(),
+            vec![
+                "a".to_string(),
+                "b".to_string(),
+                "c".to_string(),
+                "de".to_string(),
+                String::new(),
+            ]
+        );
+        assert_eq!(
+            parse_shell_command("cp 'a b' 'c d' \"e f\"").unwrap(),
+            vec![
+                "cp".to_string(),
+                "a b".to_string(),
+                "c d".to_string(),
+                "e f".to_string(),
+            ]
+        );
+
+        assert_eq!(parse_shell_command(""), Err(ShellParseError::Empty));
+        assert_eq!(parse_shell_command(" \t "), Err(ShellParseError::Empty));
+        assert_eq!(
+            parse_shell_command("ls 'VirtualBox VMs"),
+            Err(ShellParseError::UnclosedQuote(3))
+        );
+        assert_eq!(
+            parse_shell_command("echo \"a 'b' c"),
+            Err(ShellParseError::UnclosedQuote(5))
+        );
+        assert_eq!(
+            parse_shell_command("echo \"a\\\""),
+            Err(ShellParseError::UnclosedQuote(5))
+        );
+        assert_eq!(
+            parse_shell_command("echo a\\"),
+            Err(ShellParseError::TrailingBackslash)
+        );
+
+        let error: Box<dyn std::error::Error> = parse_shell_command("ls 'a").unwrap_err().into();
+        assert_eq!(error.to_string(), "unclosed quote at byte 3");
+        assert_eq!(ShellParseError::Empty.to_string(), "empty command");
+    }
+
+    fn command(args: &[&str], redirects: &[(RedirectKind, &str)]) -> SimpleCommand {
+        SimpleCommand {
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            redirects: redirects
+                .iter()
+                .map(|(kind, target)| Redirect {
+                    kind: *kind,
+                    target: target.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn pipeline(commands: Vec<SimpleCommand>) -> Pipeline {
+        Pipeline { commands }
+    }
+
+    #[test]
+    fn test_shell_pipeline() {
+        assert_eq!(
+            parse_shell_pipeline("ls -l").unwrap(),
+            Sequence {
+                first: pipeline(vec![command(&["ls", "-l"], &[])]),
+                rest: vec![],
+            }
+        );
+
+        assert_eq!(
+            parse_shell_pipeline("cat a|sort -r >> 'out file' ; ls && echo ok || echo fail;")
+                .unwrap(),
+            Sequence {
+                first: pipeline(vec![
+                    command(&["cat", "a"], &[]),
+                    command(&["sort", "-r"], &[(RedirectKind::Append, "out file")]),
+                ]),
+                rest: vec![
+                    (Connector::Then, pipeline(vec![command(&["ls"], &[])])),
+                    (
+                        Connector::And,
+                        pipeline(vec![command(&["echo", "ok"], &[])])
+                    ),
+                    (
+                        Connector::Or,
+                        pipeline(vec![command(&["echo", "fail"], &[])])
+                    ),
+                ],
+            }
+        );
+
+        assert_eq!(
+            parse_shell_pipeline("< in sort > out -u").unwrap().first,
+            pipeline(vec![command(
+                &["sort", "-u"],
+                &[(RedirectKind::Input, "in"), (RedirectKind::Output, "out")]
+            )])
+        );
+
+        // Quoted, escaped, and lone `&` are regular arguments.
+        assert_eq!(
+            parse_shell_pipeline("echo '|' \\; \"&&\" a&b &")
+                .unwrap()
+                .first,
+            pipeline(vec![command(&["echo", "|", ";", "&&", "a&b", "&"], &[])])
+        );
+        assert_eq!(
+            parse_shell_command("echo a|b > c").unwrap(),
+            vec![
+                "echo".to_string(),
+                "a|b".to_string(),
+                ">".to_string(),
+                "c".to_string()
+            ]
+        );
+
+        assert_eq!(parse_shell_pipeline("  "), Err(ShellParseError::Empty));
+        assert_eq!(
+            parse_shell_pipeline("ls |"),
+            Err(ShellParseError::MissingCommand)
+        );
+        assert_eq!(
+            parse_shell_pipeline("&& ls"),
+            Err(ShellParseError::MissingCommand)
+        );
+        assert_eq!(
+            parse_shell_pipeline("ls ;; ls"),
+            Err(ShellParseError::MissingCommand)
+        );
+        assert_eq!(
+            parse_shell_pipeline("ls > | wc"),
+            Err(ShellParseError::MissingRedirectTarget)
+        );
+        assert_eq!(
+            parse_shell_pipeline("> out"),
+            Err(ShellParseError::MissingCommand)
+        );
+        assert_eq!(
+            parse_shell_pipeline("echo 'a | b"),
+            Err(ShellParseError::UnclosedQuote(5))
+        );
+    }
+
+    #[test]
+    fn test_shell_vars() {
+        let vars = HashMap::from([
+            ("HOME".to_string(), "/home/me".to_string()),
+            ("NAME".to_string(), "a b".to_string()),
+            ("_x1".to_string(), "X".to_string()),
+        ]);
+        let parse =
+            |command: &str| parse_shell_command_with(command, Expansions::new().vars(&vars));
+
+        assert_eq!(
+            parse("cd $HOME/src ${HOME}x").unwrap(),
+            vec![
+                "cd".to_string(),
+                "/home/me/src".to_string(),
+                "/home/mex".to_string()
+            ]
+        );
+        assert_eq!(
+            parse(r#"echo "$NAME" '$NAME' \$NAME "\$NAME" $_x1$_x1"#).unwrap(),
+            vec![
+                "echo".to_string(),
+                "a b".to_string(),
+                "$NAME".to_string(),
+                "$NAME".to_string(),
+                "$NAME".to_string(),
+                "XX".to_string(),
+            ]
+        );
+        assert_eq!(
+            parse("echo $$ $ a$ $1 $UNDEFINED \"$UNDEFINED\"").unwrap(),
+            vec![
+                "echo".to_string(),
+                "$".to_string(),
+                "$".to_string(),
+                "a$".to_string(),
+                "$1".to_string(),
+                String::new(),
+            ]
+        );
+        assert_eq!(parse("echo ${HOME"), Err(ShellParseError::BadSubstitution));
+        assert_eq!(parse("echo ${}"), Err(ShellParseError::BadSubstitution));
+        assert_eq!(parse("echo ${A-B}"), Err(ShellParseError::BadSubstitution));
+        assert_eq!(parse("$UNDEFINED"), Err(ShellParseError::Empty));
+
+        // Without variables, nothing is expanded.
+        assert_eq!(
+            parse_shell_command("echo $HOME").unwrap(),
+            vec!["echo".to_string(), "$HOME".to_string()]
+        );
+
+        assert_eq!(
+            parse_shell_pipeline_with("cat $HOME/a > \"$NAME\"", Expansions::new().vars(&vars))
+                .unwrap()
+                .first,
+            pipeline(vec![command(
+                &["cat", "/home/me/a"],
+                &[(RedirectKind::Output, "a b")]
+            )])
+        );
+    }
+
+    #[test]
+    fn test_shell_glob() {
+        let files = ["main.rs", "Cargo.toml", "lib.rs", "a*b", "x.r"]
+            .map(String::from)
+            .to_vec();
+        let vars = HashMap::from([("EXT".to_string(), "rs".to_string())]);
+        let parse = |command: &str| {
+            parse_shell_command_with(command, Expansions::new().vars(&vars).files(&files)).unwrap()
+        };
+        let words = |words: &[&str]| {
+            words
+                .iter()
+                .map(|word| word.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(parse("ls *.rs"), words(&["ls", "lib.rs", "main.rs"]));
+        assert_eq!(parse("ls *.$EXT"), words(&["ls", "lib.rs", "main.rs"]));
+        assert_eq!(parse("ls ???.rs x.?"), words(&["ls", "lib.rs", "x.r"]));
+        assert_eq!(
+            parse("ls *"),
+            words(&["ls", "Cargo.toml", "a*b", "lib.rs", "main.rs", "x.r"])
+        );
+        assert_eq!(parse("ls *.c C*"), words(&["ls", "*.c", "Cargo.toml"]));
+
+        // Quoted or escaped wildcards are literal.
+        assert_eq!(
+            parse("ls '*.rs' \"*\".rs \\*.rs"),
+            words(&["ls", "*.rs", "*.rs", "*.rs"])
+        );
+        assert_eq!(parse("ls a'*'*"), words(&["ls", "a*b"]));
+        assert_eq!(parse("ls a'*'"), words(&["ls", "a*"]));
+
+        // Without files, nothing is expanded.
+        assert_eq!(
+            parse_shell_command("ls *.rs").unwrap(),
+            words(&["ls", "*.rs"])
+        );
+    }
 }
@@ -1,13 +1,18 @@
 //! Small problems.
 
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::format;
+use std::hash::Hash;
+use std::ops::Range;
+use std::str::FromStr;
 use std::{default, fmt};
 
 use itertools::Itertools;
 use rayon::collections::hash_set;
 
+use crate::assignments::assignment09::small_exercises::count_occurrences;
+
 /// Day of week.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DayOfWeek {
@@ -27,6 +32,85 @@ pub enum DayOfWeek {
     Sat,
 }
 
+impl DayOfWeek {
+    /// Every day of the week, starting from Sunday.
+    pub const ALL: [DayOfWeek; 7] = [
+        DayOfWeek::Sun,
+        DayOfWeek::Mon,
+        DayOfWeek::Tue,
+        DayOfWeek::Wed,
+        DayOfWeek::Thu,
+        DayOfWeek::Fri,
+        DayOfWeek::Sat,
+    ];
+
+    /// Returns an iterator over the week, starting from Sunday.
+    pub fn iter() -> impl Iterator<Item = DayOfWeek> {
+        Self::ALL.into_iter()
+    }
+
+    /// The day of week `n` days later, or earlier if `n` is negative.
+    ///
+    /// `Sat.add_days(2)` is `Mon`; and `Sun.add_days(-1)` is `Sat`.
+    pub fn add_days(self, n: i64) -> DayOfWeek {
+        Self::ALL[(self as i64 + n).rem_euclid(7) as usize]
+    }
+
+    /// Returns `true` for Saturday and Sunday.
+    pub fn is_weekend(self) -> bool {
+        matches!(self, DayOfWeek::Sat | DayOfWeek::Sun)
+    }
+
+    /// The full name, e.g. `"Monday"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            DayOfWeek::Sun => "Sunday",
+            DayOfWeek::Mon => "Monday",
+            DayOfWeek::Tue => "Tuesday",
+            DayOfWeek::Wed => "Wednesday",
+            DayOfWeek::Thu => "Thursday",
+            DayOfWeek::Fri => "Friday",
+            DayOfWeek::Sat => "Saturday",
+        }
+    }
+}
+
+impl fmt::Display for DayOfWeek {
+    /// Formats the abbreviated name like `Mon`, or the full name like `Monday` with `{:#}`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str(self.name())
+        } else {
+            f.write_str(&self.name()[..3])
+        }
+    }
+}
+
+/// Error for parsing a [`DayOfWeek`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDayOfWeekError(pub String);
+
+impl fmt::Display for ParseDayOfWeekError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid day of week: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseDayOfWeekError {}
+
+impl FromStr for DayOfWeek {
+    type Err = ParseDayOfWeekError;
+
+    /// Parses the abbreviated or full name, ignoring case, e.g. `Mon`, `monday` or `MONDAY`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::iter()
+            .find(|day| {
+                s.eq_ignore_ascii_case(day.name()) || s.eq_ignore_ascii_case(&day.name()[..3])
+            })
+            .ok_or_else(|| ParseDayOfWeekError(s.to_string()))
+    }
+}
+
 /// The next day of week.
 ///
 /// `next_weekday(Thu)` is `Fri`; and `next_weekday(Fri)` is `Mon`.
@@ -42,7 +126,17 @@ pub fn next_weekday(day: DayOfWeek) -> DayOfWeek {
     }
 }
 
-/// Given a list of integers, returns its median (when sorted, the value in the middle position).
+/// The previous weekday.
+///
+/// `prev_weekday(Tue)` is `Mon`; and `prev_weekday(Mon)` is `Fri`.
+pub fn prev_weekday(day: DayOfWeek) -> DayOfWeek {
+    match day {
+        DayOfWeek::Mon | DayOfWeek::Sat | DayOfWeek::Sun => DayOfWeek::Fri,
+        day => day.add_days(-1),
+    }
+}
+
+/// Given a list of values, returns its median (when sorted, the value in the middle position).
 ///
 /// For a data set `x` of `n` elements, the median can be defined as follows:
 ///
@@ -64,36 +158,46 @@ pub fn next_weekday(day: DayOfWeek) -> DayOfWeek {
 /// it has the median of 5, which is the fifth value.
 ///
 /// Returns `None` if the list is empty.
-pub fn median(values: Vec<isize>) -> Option<isize> {
-    let mut sort: Vec<isize> = values.clone();
-    sort.sort();
-    if sort.is_empty() {
-        None
-    } else if sort.len() % 2 == 0 {
-        let element = sort.len() / 2;
-        Some(sort[element])
-    } else {
-        let element = (sort.len() + 1) / 2 - 1;
-        Some(sort[element])
+pub fn median<T: Ord>(mut values: Vec<T>) -> Option<T> {
+    if values.is_empty() {
+        return None;
     }
+    values.sort();
+    let middle = values.len() / 2;
+    Some(values.swap_remove(middle))
 }
 
-/// Given a list of integers, returns its smallest mode (the value that occurs most often; a hash
-/// map will be helpful here).
+/// Like [`median`], but for floating-point numbers.
 ///
-/// Returns `None` if the list is empty.
-pub fn mode(values: Vec<isize>) -> Option<isize> {
-    let mut map: HashMap<isize, isize> = HashMap::new();
-    for element in values {
-        let count = map.entry(element).and_modify(|v| *v += 1).or_insert(1);
+/// Returns `None` if the list is empty or contains NaN.
+pub fn median_f64(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() || values.iter().any(|value| value.is_nan()) {
+        return None;
     }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    Some(values[values.len() / 2])
+}
+
+/// Given a list of values, returns its smallest mode (the value that occurs most often).
+///
+/// Returns `None` if the list is empty.
+pub fn mode<T: Hash + Ord>(values: Vec<T>) -> Option<T> {
+    modes(values).into_iter().next()
+}
 
-    map.iter()
-        .max_by(|(k1, v1), (k2, v2)| match v1.cmp(v2) {
-            Ordering::Equal => k2.cmp(k1), // reverse so smaller key wins in max_by
-            other => other,
-        })
-        .map(|(k, _)| *k)
+/// Given a list of values, returns all of its modes (the values tied for occurring most often) in
+/// increasing order.
+///
+/// Returns an empty list if the list is empty.
+pub fn modes<T: Hash + Ord>(values: Vec<T>) -> Vec<T> {
+    let counts = count_occurrences(values.into_iter());
+    let max = counts.values().copied().max().unwrap_or(0);
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count == max)
+        .map(|(value, _)| value)
+        .sorted()
+        .collect()
 }
 
 /// Converts the given string to Pig Latin. Use the rules below to translate normal English into Pig
@@ -143,118 +247,408 @@ pub fn piglatin(input: String) -> String {
 ///   - "Add {person} to {department}"
 ///   - "Remove {person} from {department}"
 ///   - "Move {person} from {department} to {department}"
+///   - "Rename {department} to {department}", if the latter does not exist
+///   - "Merge {department} into {department}"
+///   - "List {department}", which does not change the table
 /// - Ignore invalid / non-executable commands
 /// - No spaces in names or department identifiers
 pub fn organize(commands: Vec<String>) -> HashMap<String, HashSet<String>> {
-    let mut org: HashMap<String, HashSet<String>> = HashMap::new();
+    organize_with_output(commands).0
+}
 
+/// Like [`organize`], but also returns the output of the `List` commands.
+///
+/// `List {department}` outputs the department and its employees in sorted order, e.g.
+/// `"Sales: Amir, Sally"`.
+pub fn organize_with_output(
+    commands: Vec<String>,
+) -> (HashMap<String, HashSet<String>>, Vec<String>) {
+    let mut org = HashMap::new();
+    let mut output = Vec::new();
     for cmd in commands {
-        let parts: Vec<&str> = cmd.split_whitespace().collect();
-
-        match parts.as_slice() {
-            // Add Sally to Sales
-            ["Add", person, "to", dept] => {
-                // Get or create the set for the department, then insert person
-                let _unused = org
-                    .entry((*dept).to_string())
-                    .or_default()
-                    .insert((*person).to_string());
+        // Invalid commands are ignored
+        let _unused = execute(&mut org, &cmd, &mut output);
+    }
+    (org, output)
+}
+
+/// Like [`organize`], but also returns why each ignored command could not be executed.
+pub fn organize_checked(
+    commands: Vec<String>,
+) -> (HashMap<String, HashSet<String>>, Vec<CommandError>) {
+    let mut org = HashMap::new();
+    let mut errors = Vec::new();
+    for (index, command) in commands.into_iter().enumerate() {
+        if let Err(kind) = execute(&mut org, &command, &mut Vec::new()) {
+            errors.push(CommandError {
+                index,
+                command,
+                kind,
+            });
+        }
+    }
+    (org, errors)
+}
+
+/// HR command that could not be executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandError {
+    /// Index of the command.
+    pub index: usize,
+    /// The command.
+    pub command: String,
+    /// Why the command could not be executed.
+    pub kind: CommandErrorKind,
+}
+
+/// Reasons for [`CommandError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandErrorKind {
+    /// The command is not one of the known commands.
+    Malformed,
+    /// The department does not exist.
+    UnknownDepartment(String),
+    /// The person is not in the department.
+    UnknownPerson {
+        /// The person.
+        person: String,
+        /// The department.
+        department: String,
+    },
+    /// The department to rename to already exists.
+    DepartmentExists(String),
+    /// A department is merged into itself.
+    SameDepartment(String),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "command {} `{}`: ", self.index, self.command)?;
+        match &self.kind {
+            CommandErrorKind::Malformed => write!(f, "malformed command"),
+            CommandErrorKind::UnknownDepartment(department) => {
+                write!(f, "unknown department {}", department)
             }
+            CommandErrorKind::UnknownPerson { person, department } => {
+                write!(f, "{} is not in {}", person, department)
+            }
+            CommandErrorKind::DepartmentExists(department) => {
+                write!(f, "department {} already exists", department)
+            }
+            CommandErrorKind::SameDepartment(department) => {
+                write!(f, "cannot merge {} into itself", department)
+            }
+        }
+    }
+}
 
-            // Remove Jeehoon from Sales
-            ["Remove", person, "from", dept] => {
-                if let Some(set) = org.get_mut(*dept) {
-                    let _unused = set.remove(*person);
-                    if set.is_empty() {
-                        // Remove empty department
-                        let _unused = org.remove(*dept);
-                    }
-                }
-                // If dept doesn't exist, or person not in dept -> ignore
+impl std::error::Error for CommandError {}
+
+// Execute an HR command, appending the output of `List` to `output`
+fn execute(
+    org: &mut HashMap<String, HashSet<String>>,
+    cmd: &str,
+    output: &mut Vec<String>,
+) -> Result<(), CommandErrorKind> {
+    let unknown_department = |dept: &str| CommandErrorKind::UnknownDepartment(dept.to_string());
+    let unknown_person = |person: &str, dept: &str| CommandErrorKind::UnknownPerson {
+        person: person.to_string(),
+        department: dept.to_string(),
+    };
+
+    let parts: Vec<&str> = cmd.split_whitespace().collect();
+    match parts.as_slice() {
+        // Add Sally to Sales
+        ["Add", person, "to", dept] => {
+            // Get or create the set for the department, then insert person
+            let _unused = org
+                .entry((*dept).to_string())
+                .or_default()
+                .insert((*person).to_string());
+        }
+
+        // Remove Jeehoon from Sales
+        ["Remove", person, "from", dept] => {
+            let set = org.get_mut(*dept).ok_or_else(|| unknown_department(dept))?;
+            if !set.remove(*person) {
+                return Err(unknown_person(person, dept));
             }
+            if set.is_empty() {
+                // Remove empty department
+                let _unused = org.remove(*dept);
+            }
+        }
 
-            // Move Amir from Engineering to Sales
-            ["Move", person, "from", from_dept, "to", to_dept] => {
-                // Must exist in from_dept first; otherwise ignore
-                let mut moved = false;
-                if let Some(from_set) = org.get_mut(*from_dept) {
-                    if from_set.remove(*person) {
-                        moved = true;
-                        if from_set.is_empty() {
-                            // Removing from empty department after move
-                            // (we'll remove it below after borrowing ends)
-                        }
-                    }
-                }
+        // Move Amir from Engineering to Sales
+        ["Move", person, "from", from_dept, "to", to_dept] => {
+            // Must exist in from_dept first
+            let from_set = org
+                .get_mut(*from_dept)
+                .ok_or_else(|| unknown_department(from_dept))?;
+            if !from_set.remove(*person) {
+                return Err(unknown_person(person, from_dept));
+            }
+            if from_set.is_empty() {
+                let _unused = org.remove(*from_dept);
+            }
+            let _unused = org
+                .entry((*to_dept).to_string())
+                .or_default()
+                .insert((*person).to_string());
+        }
 
-                if moved {
-                    let _unused = org
-                        .entry((*to_dept).to_string())
-                        .or_default()
-                        .insert((*person).to_string());
-
-                    // Now clean up `from_dept` if it exists and became empty
-                    // (Need a second lookup because of previous mutable borrow scope)
-                    if let Some(from_set) = org.get_mut(*from_dept) {
-                        if from_set.is_empty() {
-                            let _unused = org.remove(*from_dept);
-                        }
-                    }
-                }
+        // Rename Sales to Marketing
+        ["Rename", from_dept, "to", to_dept] => {
+            if org.contains_key(*to_dept) {
+                return Err(CommandErrorKind::DepartmentExists((*to_dept).to_string()));
+            }
+            let set = org
+                .remove(*from_dept)
+                .ok_or_else(|| unknown_department(from_dept))?;
+            let _unused = org.insert((*to_dept).to_string(), set);
+        }
+
+        // Merge Sales into Engineering
+        ["Merge", from_dept, "into", to_dept] => {
+            if from_dept == to_dept {
+                return Err(CommandErrorKind::SameDepartment((*from_dept).to_string()));
             }
+            let set = org
+                .remove(*from_dept)
+                .ok_or_else(|| unknown_department(from_dept))?;
+            org.entry((*to_dept).to_string()).or_default().extend(set);
+        }
 
-            // Anything else is ignored
-            _ => { /* ignore non-matching or malformed commands */ }
+        // List Sales
+        ["List", dept] => {
+            let set = org.get(*dept).ok_or_else(|| unknown_department(dept))?;
+            output.push(format!("{}: {}", dept, set.iter().sorted().join(", ")));
         }
+
+        _ => return Err(CommandErrorKind::Malformed),
     }
 
-    org
+    Ok(())
 }
 
 /// Events in a text editor.
 #[derive(Debug)]
 pub enum TypeEvent {
-    /// A character is typed.
+    /// A character is typed at the cursor, replacing the selection.
     Type(char),
-    /// The last character is removed.
+    /// The selection, or the character before the cursor, is removed.
     Backspace,
-    /// The whole string is copied to the clipboard.
+    /// The selection, or the whole string, is copied to the clipboard.
     Copy,
-    /// The string in the clipboard is appended.
+    /// The most recently copied string is inserted at the cursor, replacing the selection.
     Paste,
+    /// The cursor moves one character to the left.
+    Left,
+    /// The cursor moves one character to the right.
+    Right,
+    /// The cursor moves to the start of the string.
+    Home,
+    /// The cursor moves to the end of the string.
+    End,
+    /// The last edit is reverted.
+    Undo,
+    /// The last undone edit is applied again.
+    Redo,
+    /// The whole string is selected, and the cursor moves to its end.
+    SelectAll,
+    /// The selection, or the whole string, is copied to the clipboard and removed.
+    Cut,
+    /// Like `Paste`, but inserts the `n`-th most recently copied string, starting from 0.
+    PasteNth(usize),
 }
 
-/// Starting from an empty string and an empty clipboard,
-/// processes the given `events` in order and returns the resulting string.
+/// State of a text editor: the string, the cursor, the selection, the clipboard and the edit
+/// history.
+///
+/// The cursor is between characters (not bytes), and stays after the inserted text when typing or
+/// pasting. Moving the cursor past either end of the string has no effect.
+///
+/// The selection is cleared by every event other than `SelectAll`.
+///
+/// The clipboard keeps the last [`Editor::CLIPBOARD_LIMIT`] copied strings. Pasting from an empty
+/// clipboard, or past its end, has no effect.
 ///
-/// See the test function `test_editor` for examples.
+/// Typing, backspacing, cutting and pasting are edits that can be undone, up to the last
+/// [`Editor::HISTORY_LIMIT`] ones. A new edit discards the edits that can be redone.
+#[derive(Debug, Default, Clone)]
+pub struct Editor {
+    text: String,
+    // Number of characters before the cursor
+    cursor: usize,
+    // Selected characters
+    selection: Option<Range<usize>>,
+    // Copied strings, the most recent at the front
+    clipboard: VecDeque<String>,
+    // Text and cursor before each edit, the most recent at the back
+    undo: VecDeque<(String, usize)>,
+    redo: Vec<(String, usize)>,
+}
 
-pub fn use_editor(events: Vec<TypeEvent>) -> String {
-    let mut text = String::new();
-    let mut clipboard = String::new();
+impl Editor {
+    /// The maximum number of edits that can be undone.
+    pub const HISTORY_LIMIT: usize = 100;
 
-    for event in events {
+    /// The maximum number of strings in the clipboard.
+    pub const CLIPBOARD_LIMIT: usize = 10;
+
+    /// Creates an editor with an empty string and an empty clipboard.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the string.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the cursor position in characters.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Returns the selected range in characters, if any.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        self.selection.clone()
+    }
+
+    /// Returns the copied strings, the most recent first.
+    pub fn clipboard(&self) -> impl Iterator<Item = &str> {
+        self.clipboard.iter().map(String::as_str)
+    }
+
+    /// Processes the event.
+    pub fn apply(&mut self, event: TypeEvent) {
+        let selection = self.selection.take().filter(|range| !range.is_empty());
         match event {
             TypeEvent::Type(ch) => {
-                text.push(ch);
+                self.record();
+                if let Some(range) = selection {
+                    self.delete(range);
+                }
+                self.insert(&ch.to_string());
             }
             TypeEvent::Backspace => {
-                // Remove last Unicode scalar if any
-                if let Some(last) = text.pop() {};
+                let range =
+                    selection.or_else(|| (self.cursor > 0).then(|| self.cursor - 1..self.cursor));
+                if let Some(range) = range {
+                    self.record();
+                    self.delete(range);
+                }
+            }
+            TypeEvent::Copy => self.copy(selection),
+            TypeEvent::Cut => {
+                let range = selection.unwrap_or(0..self.text.chars().count());
+                self.copy(Some(range.clone()));
+                if !range.is_empty() {
+                    self.record();
+                    self.delete(range);
+                }
+            }
+            TypeEvent::Paste => self.paste(0, selection),
+            TypeEvent::PasteNth(n) => self.paste(n, selection),
+            TypeEvent::Left => self.cursor = self.cursor.saturating_sub(1),
+            TypeEvent::Right => self.cursor = (self.cursor + 1).min(self.text.chars().count()),
+            TypeEvent::Home => self.cursor = 0,
+            TypeEvent::End => self.cursor = self.text.chars().count(),
+            TypeEvent::SelectAll => {
+                self.cursor = self.text.chars().count();
+                self.selection = Some(0..self.cursor);
             }
-            TypeEvent::Copy => {
-                // Copy entire current buffer to clipboard
-                // Avoid new allocation strategy if you prefer:
-                // clipboard.clear();
-                // clipboard.push_str(&text);
-                clipboard = text.clone();
+            TypeEvent::Undo => {
+                if let Some(state) = self.undo.pop_back() {
+                    let state = self.restore(state);
+                    self.redo.push(state);
+                }
             }
-            TypeEvent::Paste => {
-                // Append clipboard contents
-                text.push_str(&clipboard);
+            TypeEvent::Redo => {
+                if let Some(state) = self.redo.pop() {
+                    let state = self.restore(state);
+                    self.undo.push_back(state);
+                }
             }
         }
     }
 
-    text
+    // Byte offset of the character index
+    fn offset(&self, index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(index)
+            .map_or(self.text.len(), |(offset, _)| offset)
+    }
+
+    // Insert the string at the cursor, moving the cursor after it
+    fn insert(&mut self, s: &str) {
+        self.text.insert_str(self.offset(self.cursor), s);
+        self.cursor += s.chars().count();
+    }
+
+    // Remove the characters in the range, moving the cursor to its start
+    fn delete(&mut self, range: Range<usize>) {
+        let bytes = self.offset(range.start)..self.offset(range.end);
+        let _unused = self.text.drain(bytes);
+        self.cursor = range.start;
+    }
+
+    // Copy the characters in the range, or the whole string, to the clipboard
+    fn copy(&mut self, range: Option<Range<usize>>) {
+        let copied = match range {
+            Some(range) => self.text[self.offset(range.start)..self.offset(range.end)].to_string(),
+            None => self.text.clone(),
+        };
+        if self.clipboard.len() == Self::CLIPBOARD_LIMIT {
+            let _unused = self.clipboard.pop_back();
+        }
+        self.clipboard.push_front(copied);
+    }
+
+    // Replace the selection with the `n`-th most recently copied string
+    fn paste(&mut self, n: usize, selection: Option<Range<usize>>) {
+        let Some(copied) = self.clipboard.get(n).cloned() else {
+            return;
+        };
+        if copied.is_empty() && selection.is_none() {
+            return;
+        }
+        self.record();
+        if let Some(range) = selection {
+            self.delete(range);
+        }
+        self.insert(&copied);
+    }
+
+    // Save the state before an edit
+    fn record(&mut self) {
+        if self.undo.len() == Self::HISTORY_LIMIT {
+            let _unused = self.undo.pop_front();
+        }
+        self.undo.push_back((self.text.clone(), self.cursor));
+        self.redo.clear();
+    }
+
+    // Replace the text and cursor, returning the previous ones
+    fn restore(&mut self, (text, cursor): (String, usize)) -> (String, usize) {
+        (
+            std::mem::replace(&mut self.text, text),
+            std::mem::replace(&mut self.cursor, cursor),
+        )
+    }
+}
+
+/// Starting from an empty string and an empty clipboard, with the cursor at the end,
+/// processes the given `events` in order and returns the resulting string.
+///
+/// See [`Editor`] for the behavior of the events, and the test function `test_editor` for
+/// examples.
+pub fn use_editor(events: Vec<TypeEvent>) -> String {
+    let mut editor = Editor::new();
+    for event in events {
+        editor.apply(event);
+    }
+    editor.text
 }
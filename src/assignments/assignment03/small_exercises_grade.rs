@@ -13,9 +13,43 @@ mod test {
         assert_eq!(next_weekday(DayOfWeek::Sat), DayOfWeek::Mon);
     }
 
+    #[test]
+    fn test_day_of_week() {
+        assert_eq!(prev_weekday(DayOfWeek::Sun), DayOfWeek::Fri);
+        assert_eq!(prev_weekday(DayOfWeek::Mon), DayOfWeek::Fri);
+        assert_eq!(prev_weekday(DayOfWeek::Tue), DayOfWeek::Mon);
+        assert_eq!(prev_weekday(DayOfWeek::Sat), DayOfWeek::Fri);
+
+        assert_eq!(DayOfWeek::Sat.add_days(2), DayOfWeek::Mon);
+        assert_eq!(DayOfWeek::Sun.add_days(-1), DayOfWeek::Sat);
+        assert_eq!(DayOfWeek::Wed.add_days(7 * 100), DayOfWeek::Wed);
+        assert_eq!(DayOfWeek::Wed.add_days(-15), DayOfWeek::Tue);
+
+        assert_eq!(
+            DayOfWeek::iter()
+                .filter(|day| day.is_weekend())
+                .collect::<Vec<_>>(),
+            vec![DayOfWeek::Sun, DayOfWeek::Sat]
+        );
+        assert_eq!(DayOfWeek::iter().count(), 7);
+
+        assert_eq!(DayOfWeek::Mon.to_string(), "Mon");
+        assert_eq!(format!("{:#}", DayOfWeek::Wed), "Wednesday");
+        assert_eq!("Mon".parse(), Ok(DayOfWeek::Mon));
+        assert_eq!("thursday".parse(), Ok(DayOfWeek::Thu));
+        assert_eq!(
+            "Thurs".parse::<DayOfWeek>(),
+            Err(ParseDayOfWeekError("Thurs".to_string()))
+        );
+        for day in DayOfWeek::iter() {
+            assert_eq!(day.to_string().parse(), Ok(day));
+            assert_eq!(format!("{:#}", day).parse(), Ok(day));
+        }
+    }
+
     #[test]
     fn test_median() {
-        assert_eq!(median(vec![]), None);
+        assert_eq!(median(Vec::<isize>::new()), None);
         assert_eq!(median(vec![1]), Some(1));
         assert_eq!(median(vec![1, 2]), Some(2));
         assert_eq!(median(vec![2, 4, 5, 1, 3]), Some(3));
@@ -28,13 +62,34 @@ mod test {
 
     #[test]
     fn test_mode() {
-        assert_eq!(mode(vec![]), None);
+        assert_eq!(mode(Vec::<isize>::new()), None);
         assert_eq!(mode(vec![3]), Some(3));
         assert_eq!(mode(vec![2, 1, 2, 3]), Some(2));
         assert_eq!(mode(vec![2, 3, 1, 2, 2, 3, 3]), Some(2));
         assert_eq!(mode(vec![1, 1, 2, 2, 3, 3]), Some(1));
     }
 
+    #[test]
+    fn test_median_mode_generic() {
+        assert_eq!(median(vec!["b", "c", "a"]), Some("b"));
+        assert_eq!(median(vec!['x', 'y']), Some('y'));
+        assert_eq!(
+            mode(vec!["b".to_string(), "a".to_string(), "b".to_string()]),
+            Some("b".to_string())
+        );
+
+        assert_eq!(median_f64(vec![]), None);
+        assert_eq!(median_f64(vec![2.5, -1.0, 0.5]), Some(0.5));
+        assert_eq!(median_f64(vec![4.0, 1.0, 3.0, 2.0]), Some(3.0));
+        assert_eq!(median_f64(vec![1.0, f64::NAN]), None);
+
+        assert!(modes(Vec::<isize>::new()).is_empty());
+        assert_eq!(modes(vec![3]), vec![3]);
+        assert_eq!(modes(vec![2, 3, 1, 2, 2, 3, 3]), vec![2, 3]);
+        assert_eq!(modes(vec![3, 1, 2]), vec![1, 2, 3]);
+        assert_eq!(modes(vec!["a", "b", "b"]), vec!["b"]);
+    }
+
     #[test]
     fn test_piglatin() {
         assert_eq!(piglatin("pig".to_string()), "igpay".to_string());
@@ -120,6 +175,115 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_organize_more() {
+        assert_eq!(
+            organize_with_output(vec![
+                "Add Amir to Engineering".to_string(),
+                "Add Sally to Sales".to_string(),
+                "Add Jeehoon to Sales".to_string(),
+                "List Sales".to_string(),
+                "Rename Sales to Marketing".to_string(),
+                "Rename Engineering to Marketing".to_string(),
+                "List Sales".to_string(),
+                "Merge Engineering into Marketing".to_string(),
+                "List Marketing".to_string(),
+                "Merge Marketing into Marketing".to_string(),
+                "Merge Sales into Marketing".to_string(),
+                "List".to_string(),
+            ]),
+            (
+                [(
+                    "Marketing".to_string(),
+                    [
+                        "Amir".to_string(),
+                        "Sally".to_string(),
+                        "Jeehoon".to_string()
+                    ]
+                    .into()
+                )]
+                .into(),
+                vec![
+                    "Sales: Jeehoon, Sally".to_string(),
+                    "Marketing: Amir, Jeehoon, Sally".to_string(),
+                ]
+            )
+        );
+
+        assert_eq!(
+            organize(vec![
+                "Add P1 to D1".to_string(),
+                "Rename D1 to D2".to_string(),
+                "Merge D2 into D3".to_string(),
+                "Rename D4 to D5".to_string(),
+            ]),
+            [("D3".to_string(), ["P1".to_string()].into())].into()
+        );
+    }
+
+    #[test]
+    fn test_organize_checked() {
+        let (org, errors) = organize_checked(vec![
+            "Add Amir to Engineering".to_string(),
+            "Remove Amir from Sales".to_string(),
+            "Remove Sally from Engineering".to_string(),
+            "Move Sally from Engineering to Sales".to_string(),
+            "Hire Sally".to_string(),
+            "Add Sally to Sales".to_string(),
+            "Rename Sales to Engineering".to_string(),
+            "Merge Sales into Sales".to_string(),
+            "List Marketing".to_string(),
+            "Move Amir from Engineering to Sales".to_string(),
+        ]);
+
+        assert_eq!(
+            org,
+            [(
+                "Sales".to_string(),
+                ["Amir".to_string(), "Sally".to_string()].into()
+            )]
+            .into()
+        );
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| (error.index, error.kind.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, CommandErrorKind::UnknownDepartment("Sales".to_string())),
+                (
+                    2,
+                    CommandErrorKind::UnknownPerson {
+                        person: "Sally".to_string(),
+                        department: "Engineering".to_string()
+                    }
+                ),
+                (
+                    3,
+                    CommandErrorKind::UnknownPerson {
+                        person: "Sally".to_string(),
+                        department: "Engineering".to_string()
+                    }
+                ),
+                (4, CommandErrorKind::Malformed),
+                (
+                    6,
+                    CommandErrorKind::DepartmentExists("Engineering".to_string())
+                ),
+                (7, CommandErrorKind::SameDepartment("Sales".to_string())),
+                (
+                    8,
+                    CommandErrorKind::UnknownDepartment("Marketing".to_string())
+                ),
+            ]
+        );
+        assert_eq!(errors[3].command, "Hire Sally");
+        assert_eq!(
+            errors[0].to_string(),
+            "command 1 `Remove Amir from Sales`: unknown department Sales"
+        );
+    }
+
     #[test]
     fn test_editor() {
         assert_eq!(
@@ -176,4 +340,191 @@ mod test {
             ""
         );
     }
+
+    #[test]
+    fn test_editor_cursor() {
+        assert_eq!(
+            use_editor(vec![
+                TypeEvent::Type('a'),
+                TypeEvent::Type('c'),
+                TypeEvent::Left,
+                TypeEvent::Type('b'),
+                TypeEvent::End,
+                TypeEvent::Type('d'),
+                TypeEvent::Home,
+                TypeEvent::Backspace,
+                TypeEvent::Left,
+                TypeEvent::Type('_'),
+            ]),
+            "_abcd"
+        );
+
+        // The cursor counts characters, not bytes.
+        assert_eq!(
+            use_editor(vec![
+                TypeEvent::Type('가'),
+                TypeEvent::Type('나'),
+                TypeEvent::Type('다'),
+                TypeEvent::Left,
+                TypeEvent::Backspace,
+                TypeEvent::Type('é'),
+                TypeEvent::Right,
+                TypeEvent::Right,
+                TypeEvent::Type('!'),
+            ]),
+            "가é다!"
+        );
+
+        assert_eq!(
+            use_editor(vec![
+                TypeEvent::Type('a'),
+                TypeEvent::Type('b'),
+                TypeEvent::Copy,
+                TypeEvent::Left,
+                TypeEvent::Paste,
+                TypeEvent::Type('-'),
+                TypeEvent::Home,
+                TypeEvent::Left,
+                TypeEvent::Paste,
+            ]),
+            "abaab-b"
+        );
+    }
+
+    #[test]
+    fn test_editor_undo() {
+        assert_eq!(
+            use_editor(vec![
+                TypeEvent::Type('a'),
+                TypeEvent::Type('b'),
+                TypeEvent::Copy,
+                TypeEvent::Undo,
+                TypeEvent::Paste,
+            ]),
+            "aab"
+        );
+
+        assert_eq!(
+            use_editor(vec![
+                TypeEvent::Type('a'),
+                TypeEvent::Type('b'),
+                TypeEvent::Backspace,
+                TypeEvent::Undo,
+                TypeEvent::Undo,
+                TypeEvent::Redo,
+                TypeEvent::Redo,
+                TypeEvent::Redo,
+            ]),
+            "a"
+        );
+
+        // A new edit discards the redo history.
+        assert_eq!(
+            use_editor(vec![
+                TypeEvent::Type('a'),
+                TypeEvent::Type('b'),
+                TypeEvent::Undo,
+                TypeEvent::Type('c'),
+                TypeEvent::Redo,
+                TypeEvent::Undo,
+                TypeEvent::Undo,
+                TypeEvent::Undo,
+            ]),
+            ""
+        );
+
+        // Undo restores the cursor, and cursor movement is not an edit.
+        let mut editor = Editor::new();
+        for event in [
+            TypeEvent::Type('a'),
+            TypeEvent::Type('c'),
+            TypeEvent::Left,
+            TypeEvent::Type('b'),
+            TypeEvent::End,
+            TypeEvent::Undo,
+        ] {
+            editor.apply(event);
+        }
+        assert_eq!(editor.text(), "ac");
+        assert_eq!(editor.cursor(), 1);
+
+        // The history is bounded.
+        let mut editor = Editor::new();
+        for _ in 0..Editor::HISTORY_LIMIT + 10 {
+            editor.apply(TypeEvent::Type('x'));
+        }
+        for _ in 0..Editor::HISTORY_LIMIT + 10 {
+            editor.apply(TypeEvent::Undo);
+        }
+        assert_eq!(editor.text(), "x".repeat(10));
+    }
+
+    #[test]
+    fn test_editor_selection() {
+        assert_eq!(
+            use_editor(vec![
+                TypeEvent::Type('a'),
+                TypeEvent::Type('b'),
+                TypeEvent::SelectAll,
+                TypeEvent::Cut,
+                TypeEvent::Type('c'),
+                TypeEvent::Paste,
+                TypeEvent::SelectAll,
+                TypeEvent::Type('d'),
+            ]),
+            "d"
+        );
+
+        let mut editor = Editor::new();
+        for event in [
+            TypeEvent::Type('a'),
+            TypeEvent::Copy,
+            TypeEvent::Type('b'),
+            TypeEvent::SelectAll,
+        ] {
+            editor.apply(event);
+        }
+        assert_eq!(editor.selection(), Some(0..2));
+        assert_eq!(editor.cursor(), 2);
+
+        for event in [
+            TypeEvent::Copy,
+            TypeEvent::Backspace,
+            TypeEvent::PasteNth(1),
+        ] {
+            editor.apply(event);
+        }
+        assert_eq!(editor.text(), "aa");
+        assert_eq!(editor.selection(), None);
+        assert_eq!(editor.clipboard().collect::<Vec<_>>(), vec!["ab", "a"]);
+
+        for event in [
+            TypeEvent::PasteNth(0),
+            TypeEvent::PasteNth(2),
+            TypeEvent::SelectAll,
+            TypeEvent::Paste,
+            TypeEvent::Undo,
+        ] {
+            editor.apply(event);
+        }
+        assert_eq!(editor.text(), "aaab");
+
+        // Cutting without a selection cuts the whole string.
+        editor.apply(TypeEvent::Cut);
+        editor.apply(TypeEvent::Undo);
+        editor.apply(TypeEvent::Redo);
+        assert_eq!(editor.text(), "");
+        assert_eq!(editor.clipboard().next(), Some("aaab"));
+
+        // The clipboard is bounded.
+        let mut editor = Editor::new();
+        for i in 0..Editor::CLIPBOARD_LIMIT + 5 {
+            editor.apply(TypeEvent::Type(char::from(b'a' + i as u8)));
+            editor.apply(TypeEvent::Cut);
+        }
+        assert_eq!(editor.clipboard().count(), Editor::CLIPBOARD_LIMIT);
+        editor.apply(TypeEvent::PasteNth(Editor::CLIPBOARD_LIMIT - 1));
+        editor.apply(TypeEvent::PasteNth(Editor::CLIPBOARD_LIMIT));
+        assert_eq!(editor.text(), "f");
+    }
 }
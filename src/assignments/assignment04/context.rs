@@ -1,17 +1,197 @@
 //! Calculator.
 
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
 use std::{collections::HashMap, f64::consts::PI};
 
-use anyhow::*;
 use etrace::*;
 
+use super::error::CalcError;
 use super::syntax::{BinOp, Command, Expression};
 
+/// Complex number, the value of calculator expressions.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Complex {
+    /// Real part.
+    pub re: f64,
+    /// Imaginary part.
+    pub im: f64,
+}
+
+impl Complex {
+    /// The imaginary unit.
+    pub const I: Complex = Complex { re: 0.0, im: 1.0 };
+
+    /// Creates a new complex number.
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    /// Returns `true` if the imaginary part is zero.
+    pub fn is_real(&self) -> bool {
+        self.im == 0.0
+    }
+
+    /// Returns the modulus.
+    pub fn abs(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /// Returns the argument, in `(-pi, pi]`.
+    pub fn arg(self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    /// Returns the exponential.
+    pub fn exp(self) -> Self {
+        let r = self.re.exp();
+        Self::new(r * self.im.cos(), r * self.im.sin())
+    }
+
+    /// Returns the principal natural logarithm.
+    pub fn ln(self) -> Self {
+        Self::new(self.abs().ln(), self.arg())
+    }
+
+    /// Returns the principal square root.
+    pub fn sqrt(self) -> Self {
+        let r = self.abs();
+        let re = ((r + self.re) / 2.0).sqrt();
+        let im = ((r - self.re) / 2.0).sqrt();
+        Self::new(re, if self.im < 0.0 { -im } else { im })
+    }
+
+    /// Returns the sine.
+    pub fn sin(self) -> Self {
+        Self::new(
+            self.re.sin() * self.im.cosh(),
+            self.re.cos() * self.im.sinh(),
+        )
+    }
+
+    /// Returns the cosine.
+    pub fn cos(self) -> Self {
+        Self::new(
+            self.re.cos() * self.im.cosh(),
+            -self.re.sin() * self.im.sinh(),
+        )
+    }
+
+    /// Returns `self` raised to the power of `exp`, using the principal branch.
+    ///
+    /// Real results and integer exponents are computed exactly where possible, e.g. `(1+i)^4` is
+    /// exactly `-4`.
+    pub fn pow(self, exp: Self) -> Self {
+        if self.is_real() && exp.is_real() && (self.re >= 0.0 || exp.re.fract() == 0.0) {
+            return self.re.powf(exp.re).into();
+        }
+        if exp.is_real() && exp.re.fract() == 0.0 && exp.re.abs() <= f64::from(u16::MAX) {
+            // exponentiation by squaring
+            let mut n = exp.re.abs() as u32;
+            let (mut base, mut result) = (self, Self::from(1.0));
+            while n > 0 {
+                if n % 2 == 1 {
+                    result = result * base;
+                }
+                base = base * base;
+                n /= 2;
+            }
+            return if exp.re < 0.0 {
+                Self::from(1.0) / result
+            } else {
+                result
+            };
+        }
+        if self == Self::default() {
+            return Self::default();
+        }
+        (exp * self.ln()).exp()
+    }
+}
+
+impl From<f64> for Complex {
+    fn from(re: f64) -> Self {
+        Self::new(re, 0.0)
+    }
+}
+
+impl Add for Complex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.re + rhs.re, self.im + rhs.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.re - rhs.re, self.im - rhs.im)
+    }
+}
+
+impl Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.re * rhs.re - self.im * rhs.im,
+            self.re * rhs.im + self.im * rhs.re,
+        )
+    }
+}
+
+impl Div for Complex {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        if rhs.is_real() {
+            return Self::new(self.re / rhs.re, self.im / rhs.re);
+        }
+        let denom = rhs.re * rhs.re + rhs.im * rhs.im;
+        Self::new(
+            (self.re * rhs.re + self.im * rhs.im) / denom,
+            (self.im * rhs.re - self.re * rhs.im) / denom,
+        )
+    }
+}
+
+impl Neg for Complex {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.re, -self.im)
+    }
+}
+
+impl fmt::Display for Complex {
+    /// Formats like `3`, `2i`, `3+2i` or `1-i`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.im == 0.0 {
+            return write!(f, "{}", self.re);
+        }
+        if self.re != 0.0 {
+            write!(f, "{}{}", self.re, if self.im < 0.0 { "-" } else { "+" })?;
+        } else if self.im < 0.0 {
+            write!(f, "-")?;
+        }
+        match self.im.abs() {
+            1.0 => write!(f, "i"),
+            im => write!(f, "{}i", im),
+        }
+    }
+}
+
+// Real and complex versions of a built-in function
+type Builtin = (fn(f64) -> f64, fn(Complex) -> Complex);
+
 /// Calculator's context.
 #[derive(Debug, Default, Clone)]
 pub struct Context {
     anonymous_counter: usize,
-    variables: HashMap<String, f64>,
+    variables: HashMap<String, Complex>,
+    ans: Option<Complex>,
 }
 
 impl Context {
@@ -25,23 +205,77 @@ impl Context {
         self.anonymous_counter
     }
 
+    /// Returns an iterator over the variables and their values, in arbitrary order.
+    pub fn variables(&self) -> impl Iterator<Item = (&str, Complex)> {
+        self.variables
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+    }
+
+    /// Removes the variable, returning its value if it was defined.
+    pub fn remove_variable(&mut self, name: &str) -> Option<Complex> {
+        self.variables.remove(name)
+    }
+
+    /// Returns the most recently computed value, which `ans` refers to.
+    pub fn ans(&self) -> Option<Complex> {
+        self.ans
+    }
+
+    /// Removes every variable and resets the anonymous variable counter and `ans`.
+    pub fn reset(&mut self) {
+        self.variables.clear();
+        self.anonymous_counter = 0;
+        self.ans = None;
+    }
+
     /// Calculates the given expression. (We assume the absence of overflow.)
-    pub fn calc_expression(&self, expression: &Expression) -> Result<f64> {
+    ///
+    /// Returns `CalcError::NotReal` if the result is not real. Use
+    /// [`Context::calc_complex_expression`] for complex results.
+    pub fn calc_expression(&self, expression: &Expression) -> Result<f64, CalcError> {
+        to_real(self.calc_complex_expression(expression)?)
+    }
+
+    /// Calculates the given expression over complex numbers. (We assume the absence of overflow.)
+    pub fn calc_complex_expression(&self, expression: &Expression) -> Result<Complex, CalcError> {
+        self.eval(expression, &HashMap::new())
+    }
+
+    /// Calculates the given expression, where the variables in `overrides` take precedence over
+    /// the context's variables. The context is not modified.
+    pub fn calc_expression_with(
+        &self,
+        expression: &Expression,
+        overrides: &HashMap<String, f64>,
+    ) -> Result<f64, CalcError> {
+        to_real(self.eval(expression, overrides)?)
+    }
+
+    fn eval(
+        &self,
+        expression: &Expression,
+        overrides: &HashMap<String, f64>,
+    ) -> Result<Complex, CalcError> {
         match expression {
-            Expression::Num(n) => Ok(*n),
-
-            Expression::Variable(name) => {
-                // example variable lookup (you can adjust error message)
-                self.variables
-                    .get(name)
-                    .copied()
-                    .ok_or_else(|| anyhow!("undefined variable: {}", name))
-            }
+            Expression::Num(n) => Ok(Complex::from(*n)),
+
+            Expression::Imaginary(n) => Ok(Complex::new(0.0, *n)),
+
+            Expression::Variable(name) => overrides
+                .get(name)
+                .map(|value| Complex::from(*value))
+                .or_else(|| self.variables.get(name).copied())
+                .ok_or_else(|| CalcError::UndefinedVariable(name.clone())),
+
+            Expression::Ans => self
+                .ans
+                .ok_or_else(|| CalcError::UndefinedVariable("ans".into())),
 
             Expression::BinOp { op, lhs, rhs } => {
                 // 1) recursively evaluate both sides
-                let left_val = self.calc_expression(lhs)?;
-                let right_val = self.calc_expression(rhs)?;
+                let left_val = self.eval(lhs, overrides)?;
+                let right_val = self.eval(rhs, overrides)?;
 
                 // 2) apply the operator
                 let result = match op {
@@ -49,17 +283,54 @@ impl Context {
                     BinOp::Subtract => left_val - right_val,
                     BinOp::Multiply => left_val * right_val,
                     BinOp::Divide => {
-                        if right_val == 0.0 {
-                            bail!("Error")
+                        if right_val == Complex::default() {
+                            return Err(CalcError::DivisionByZero);
                         } else {
                             left_val / right_val
                         }
-                    } // optionally check /0
-                    BinOp::Power => left_val.powf(right_val), // exponent
+                    }
+                    BinOp::Power => left_val.pow(right_val), // exponent
                 };
 
                 Ok(result)
             }
+
+            Expression::Call { name, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.eval(arg, overrides))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Self::call_builtin(name, &args)
+            }
+        }
+    }
+
+    /// Calls the built-in function `name` on the evaluated arguments.
+    ///
+    /// Supported functions are `sin`, `cos`, `tan`, `sqrt`, `exp`, `ln`, `log` (base 10) and
+    /// `abs`, each taking exactly one argument. Real arguments in the real domain of the function
+    /// are computed over real numbers, e.g. `sqrt(4)` is `2` but `sqrt(-4)` is `2i`.
+    fn call_builtin(name: &str, args: &[Complex]) -> Result<Complex, CalcError> {
+        let (real, complex): Builtin = match name {
+            "sin" => (f64::sin, Complex::sin),
+            "cos" => (f64::cos, Complex::cos),
+            "tan" => (f64::tan, |z| z.sin() / z.cos()),
+            "sqrt" => (f64::sqrt, Complex::sqrt),
+            "exp" => (f64::exp, Complex::exp),
+            "ln" => (f64::ln, Complex::ln),
+            "log" => (f64::log10, |z| z.ln() / Complex::from(10f64.ln())),
+            "abs" => (f64::abs, |z| z.abs().into()),
+            _ => return Err(CalcError::UnknownFunction(name.to_string())),
+        };
+        let real_domain = |x: f64| !matches!(name, "sqrt" | "ln" | "log") || x >= 0.0;
+        match args {
+            [x] if x.is_real() && real_domain(x.re) => Ok(real(x.re).into()),
+            [z] => Ok(complex(*z)),
+            _ => Err(CalcError::Arity {
+                name: name.to_string(),
+                expected: 1,
+                found: args.len(),
+            }),
         }
     }
 
@@ -75,7 +346,27 @@ impl Context {
     /// After calculating commad `v = 3 - 2` => Context's variables = `{($0,8),(v,1))}`
     ///
     /// After calculating commad `3 ^ 2` => Context's variables = `{($0,8),(v,1),($1,9)}`
-    pub fn calc_command(&mut self, command: &Command) -> Result<(String, f64)> {
+    pub fn calc_command(&mut self, command: &Command) -> Result<(String, f64), CalcError> {
+        let value = self.calc_complex_expression(&command.expression);
+        let (name, value) = self.assign(command, value.and_then(to_real).map(Complex::from))?;
+        Ok((name, value.re))
+    }
+
+    /// Calculates the given command over complex numbers, like [`Context::calc_command`].
+    pub fn calc_complex_command(
+        &mut self,
+        command: &Command,
+    ) -> Result<(String, Complex), CalcError> {
+        let value = self.calc_complex_expression(&command.expression);
+        self.assign(command, value)
+    }
+
+    // Stores the value at the command's variable, or at the next anonymous variable
+    fn assign(
+        &mut self,
+        command: &Command,
+        value: Result<Complex, CalcError>,
+    ) -> Result<(String, Complex), CalcError> {
         let mut result_str = String::new();
         match &command.variable {
             Some(x) => result_str.push_str(x),
@@ -85,9 +376,18 @@ impl Context {
             }
         }
 
-        let result_exp = Self::calc_expression(self, &command.expression)?;
+        let result_exp = value?;
         let _unused = self.variables.insert(result_str.clone(), result_exp);
+        self.ans = Some(result_exp);
 
         Ok((result_str, result_exp))
     }
 }
+
+fn to_real(value: Complex) -> Result<f64, CalcError> {
+    if value.is_real() {
+        Ok(value.re)
+    } else {
+        Err(CalcError::NotReal(value))
+    }
+}
@@ -0,0 +1,273 @@
+//! Symbolic differentiation of calculator expressions.
+//!
+//! Expressions in `x` are converted into the functions of assignment 6, differentiated, and
+//! simplified.
+
+use crate::assignments::assignment06::symbolic_differentiation::{
+    BaseFuncs, ComplexFuncs, Differentiable, Evaluate, Exp, Rational, SingletonPolynomial,
+    Trignometric,
+};
+
+use super::error::CalcError;
+use super::syntax::{BinOp, Expression};
+
+/// Function of `x`.
+pub type Func = ComplexFuncs<BaseFuncs>;
+
+/// Returns the simplified derivative of the expression with respect to `x`.
+pub fn differentiate(expression: &Expression) -> Result<Func, CalcError> {
+    Ok(simplify(to_func(expression)?.diff()))
+}
+
+/// Converts the expression into a function of `x`.
+///
+/// Numbers must be exact decimals, powers must have constant exponents, and only `sin`, `cos` and
+/// `exp` are supported among the built-in functions.
+pub fn to_func(expression: &Expression) -> Result<Func, CalcError> {
+    match expression {
+        Expression::Num(n) => Ok(constant(rational(*n)?)),
+        Expression::Imaginary(_) => Err(unsupported("complex numbers")),
+        Expression::Ans => Err(unsupported("ans")),
+        Expression::Variable(name) if name == "x" => Ok(identity()),
+        Expression::Variable(name) => Err(CalcError::UndefinedVariable(name.clone())),
+        Expression::BinOp { op, lhs, rhs } => {
+            let lhs = Box::new(to_func(lhs)?);
+            let rhs = Box::new(to_func(rhs)?);
+            Ok(match op {
+                BinOp::Add => ComplexFuncs::Add(lhs, rhs),
+                BinOp::Subtract => ComplexFuncs::Sub(lhs, rhs),
+                BinOp::Multiply => ComplexFuncs::Mul(lhs, rhs),
+                BinOp::Divide => ComplexFuncs::Div(lhs, rhs),
+                BinOp::Power => power(*lhs, *rhs)?,
+            })
+        }
+        Expression::Call { name, args } => {
+            let arg = match args.as_slice() {
+                [arg] => to_func(arg)?,
+                _ => {
+                    return Err(CalcError::Arity {
+                        name: name.clone(),
+                        expected: 1,
+                        found: args.len(),
+                    })
+                }
+            };
+            let outer = match name.as_str() {
+                "sin" => BaseFuncs::Trig(Trignometric::new_sine(one())),
+                "cos" => BaseFuncs::Trig(Trignometric::new_cosine(one())),
+                "exp" => BaseFuncs::Exp(Exp::new()),
+                _ => return Err(unsupported(&format!("function {}", name))),
+            };
+            Ok(simplify(ComplexFuncs::Comp(
+                Box::new(ComplexFuncs::Func(outer)),
+                Box::new(arg),
+            )))
+        }
+    }
+}
+
+/// Simplifies the function by folding constants and removing zeros, ones and identities.
+pub fn simplify(func: Func) -> Func {
+    match func {
+        ComplexFuncs::Func(_) => as_const(&func).map(constant).unwrap_or(func),
+
+        ComplexFuncs::Add(l, r) => {
+            let (l, r) = (simplify(*l), simplify(*r));
+            match (as_const(&l), as_const(&r)) {
+                (Some(a), Some(b)) => constant(a + b),
+                (Some(a), _) if is_zero(a) => r,
+                (_, Some(b)) if is_zero(b) => l,
+                _ => ComplexFuncs::Add(Box::new(l), Box::new(r)),
+            }
+        }
+
+        ComplexFuncs::Sub(l, r) => {
+            let (l, r) = (simplify(*l), simplify(*r));
+            match (as_const(&l), as_const(&r)) {
+                (Some(a), Some(b)) => constant(a - b),
+                (_, Some(b)) if is_zero(b) => l,
+                (Some(a), _) if is_zero(a) => scale(minus_one(), r),
+                _ if l == r => constant(zero()),
+                _ => ComplexFuncs::Sub(Box::new(l), Box::new(r)),
+            }
+        }
+
+        ComplexFuncs::Mul(l, r) => {
+            let (l, r) = (simplify(*l), simplify(*r));
+            match (as_const(&l), as_const(&r)) {
+                (Some(a), Some(b)) => constant(a * b),
+                (Some(k), _) => scale(k, r),
+                (_, Some(k)) => scale(k, l),
+                _ => match (&l, &r) {
+                    (
+                        ComplexFuncs::Func(BaseFuncs::Poly(SingletonPolynomial::Polynomial {
+                            coeff: c1,
+                            power: p1,
+                        })),
+                        ComplexFuncs::Func(BaseFuncs::Poly(SingletonPolynomial::Polynomial {
+                            coeff: c2,
+                            power: p2,
+                        })),
+                    ) => simplify(poly(*c1 * *c2, *p1 + *p2)),
+                    _ => ComplexFuncs::Mul(Box::new(l), Box::new(r)),
+                },
+            }
+        }
+
+        ComplexFuncs::Div(l, r) => {
+            let (l, r) = (simplify(*l), simplify(*r));
+            match (as_const(&l), as_const(&r)) {
+                // Keeps the division by zero as is.
+                (_, Some(b)) if is_zero(b) => ComplexFuncs::Div(Box::new(l), Box::new(r)),
+                (Some(a), _) if is_zero(a) => constant(zero()),
+                (_, Some(b)) => scale(one() / b, l),
+                _ => ComplexFuncs::Div(Box::new(l), Box::new(r)),
+            }
+        }
+
+        ComplexFuncs::Comp(f, g) => {
+            let (f, g) = (simplify(*f), simplify(*g));
+            if g == identity() || as_const(&f).is_some() {
+                f
+            } else if f == identity() {
+                g
+            } else {
+                ComplexFuncs::Comp(Box::new(f), Box::new(g))
+            }
+        }
+    }
+}
+
+// `lhs ^ rhs`, where `rhs` must be constant
+fn power(lhs: Func, rhs: Func) -> Result<Func, CalcError> {
+    let exponent = as_const(&simplify(rhs)).ok_or_else(|| unsupported("non-constant exponents"))?;
+    if is_zero(exponent) {
+        return Ok(constant(one()));
+    }
+    let base = simplify(lhs);
+    if let Some(base) = as_const(&base) {
+        let n = exponent.evaluate(0.0);
+        if n.fract() != 0.0 || n.abs() > 64.0 {
+            return Err(unsupported("non-integer powers of constants"));
+        }
+        let mut result = one();
+        for _ in 0..n.abs() as usize {
+            result = result * base;
+        }
+        if n < 0.0 {
+            if is_zero(result) {
+                return Err(CalcError::DivisionByZero);
+            }
+            result = one() / result;
+        }
+        return Ok(constant(result));
+    }
+    Ok(simplify(ComplexFuncs::Comp(
+        Box::new(poly(one(), exponent)),
+        Box::new(base),
+    )))
+}
+
+// Returns the constant value of the function, if it is constant
+fn as_const(func: &Func) -> Option<Rational> {
+    match func {
+        ComplexFuncs::Func(BaseFuncs::Const(r))
+        | ComplexFuncs::Func(BaseFuncs::Poly(SingletonPolynomial::Const(r))) => Some(normalize(*r)),
+        ComplexFuncs::Func(BaseFuncs::Poly(SingletonPolynomial::Polynomial { coeff, power })) => {
+            if is_zero(*coeff) {
+                Some(zero())
+            } else if is_zero(*power) {
+                Some(*coeff)
+            } else {
+                None
+            }
+        }
+        ComplexFuncs::Func(BaseFuncs::Trig(
+            Trignometric::Sine { coeff } | Trignometric::Cosine { coeff },
+        )) if is_zero(*coeff) => Some(zero()),
+        _ => None,
+    }
+}
+
+// `k * func`, folding `k` into the coefficient if possible
+fn scale(k: Rational, func: Func) -> Func {
+    if is_zero(k) {
+        return constant(zero());
+    }
+    if k == one() {
+        return func;
+    }
+    match func {
+        ComplexFuncs::Func(BaseFuncs::Poly(SingletonPolynomial::Polynomial { coeff, power })) => {
+            poly(k * coeff, power)
+        }
+        ComplexFuncs::Func(BaseFuncs::Trig(Trignometric::Sine { coeff })) => {
+            ComplexFuncs::Func(BaseFuncs::Trig(Trignometric::new_sine(k * coeff)))
+        }
+        ComplexFuncs::Func(BaseFuncs::Trig(Trignometric::Cosine { coeff })) => {
+            ComplexFuncs::Func(BaseFuncs::Trig(Trignometric::new_cosine(k * coeff)))
+        }
+        _ => match as_const(&func) {
+            Some(c) => constant(k * c),
+            None => ComplexFuncs::Mul(Box::new(constant(k)), Box::new(func)),
+        },
+    }
+}
+
+// Converts an exact decimal into a rational number, reading its digits from the shortest decimal
+// that rounds to `n`, since scaling `n` by 10 accumulates binary rounding errors
+fn rational(n: f64) -> Result<Rational, CalcError> {
+    let decimal = format!("{}", n);
+    let (int, frac) = decimal.split_once('.').unwrap_or((&decimal, ""));
+    let numerator = format!("{}{}", int, frac)
+        .parse::<isize>()
+        .ok()
+        .filter(|numerator| numerator.unsigned_abs() <= 1_000_000_000_000);
+    match numerator {
+        Some(numerator) if frac.len() <= 6 => {
+            Ok(Rational::new(numerator, 1) / Rational::new(10_isize.pow(frac.len() as u32), 1))
+        }
+        _ => Err(unsupported(&format!("{} as a rational number", n))),
+    }
+}
+
+// `Rational`'s zero is `0/0`, which cannot be used in arithmetic; this uses `0/1` instead.
+fn normalize(r: Rational) -> Rational {
+    if is_zero(r) {
+        zero()
+    } else {
+        r
+    }
+}
+
+fn is_zero(r: Rational) -> bool {
+    r.evaluate(0.0) == 0.0
+}
+
+fn zero() -> Rational {
+    Rational::new(0, 1)
+}
+
+fn one() -> Rational {
+    Rational::new(1, 1)
+}
+
+fn minus_one() -> Rational {
+    Rational::new(-1, 1)
+}
+
+fn constant(r: Rational) -> Func {
+    ComplexFuncs::Func(BaseFuncs::Const(r))
+}
+
+fn poly(coeff: Rational, power: Rational) -> Func {
+    ComplexFuncs::Func(BaseFuncs::Poly(SingletonPolynomial::new_poly(coeff, power)))
+}
+
+fn identity() -> Func {
+    poly(one(), one())
+}
+
+fn unsupported(what: &str) -> CalcError {
+    CalcError::Unsupported(format!("cannot differentiate {}", what))
+}
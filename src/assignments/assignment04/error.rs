@@ -0,0 +1,99 @@
+//! Errors.
+
+use std::fmt;
+use std::ops::Range;
+
+use super::context::Complex;
+
+/// Error of parsing or calculating a command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalcError {
+    /// The input is not well-formed. It contains the byte range of the offending input.
+    Syntax {
+        /// Byte range in the input.
+        span: Range<usize>,
+        /// Description of the error.
+        message: String,
+    },
+
+    /// The variable is not defined. It contains the variable name.
+    UndefinedVariable(String),
+
+    /// The function is not a built-in function. It contains the function name.
+    UnknownFunction(String),
+
+    /// The function is called with a wrong number of arguments.
+    Arity {
+        /// Function name.
+        name: String,
+        /// Expected number of arguments.
+        expected: usize,
+        /// Given number of arguments.
+        found: usize,
+    },
+
+    /// Division by zero.
+    DivisionByZero,
+
+    /// A real number is expected, but the result is complex. It contains the result.
+    NotReal(Complex),
+
+    /// The operation is not supported. It contains the description.
+    Unsupported(String),
+}
+
+impl CalcError {
+    /// Returns the byte range of `line` that caused the error, if known.
+    ///
+    /// For undefined variables and unknown functions, this is the first occurrence of the name in
+    /// `line` as a whole word.
+    pub fn span(&self, line: &str) -> Option<Range<usize>> {
+        match self {
+            CalcError::Syntax { span, .. } => Some(span.clone()),
+            CalcError::UndefinedVariable(name)
+            | CalcError::UnknownFunction(name)
+            | CalcError::Arity { name, .. } => find_word(line, name),
+            CalcError::DivisionByZero | CalcError::NotReal(_) | CalcError::Unsupported(_) => None,
+        }
+    }
+}
+
+fn find_word(line: &str, word: &str) -> Option<Range<usize>> {
+    let is_word_char = |c: char| c.is_ascii_alphanumeric() || c == '$';
+    line.match_indices(word)
+        .map(|(start, _)| start..start + word.len())
+        .find(|span| {
+            !line[..span.start].ends_with(is_word_char)
+                && !line[span.end..].starts_with(is_word_char)
+        })
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalcError::Syntax { span, message } => {
+                write!(
+                    f,
+                    "syntax error at {}..{}: {}",
+                    span.start, span.end, message
+                )
+            }
+            CalcError::UndefinedVariable(name) => write!(f, "undefined variable: {}", name),
+            CalcError::UnknownFunction(name) => write!(f, "unknown function: {}", name),
+            CalcError::Arity {
+                name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} expects {} argument(s), got {}",
+                name, expected, found
+            ),
+            CalcError::DivisionByZero => write!(f, "division by zero"),
+            CalcError::NotReal(value) => write!(f, "result is not real: {}", value),
+            CalcError::Unsupported(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
@@ -1,5 +1,7 @@
 #[cfg(test)]
 mod test {
+    use crate::assignments::assignment04::context::Complex;
+    use crate::assignments::assignment04::error::CalcError;
     use crate::assignments::assignment04::syntax::*;
     use crate::assignments::assignment04::*;
 
@@ -311,4 +313,467 @@ mod test {
             })
             .is_err());
     }
+
+    #[test]
+    fn test_parse_call() {
+        assert_eq!(
+            parser::parse_command("y = sqrt(x) + 1").unwrap(),
+            Command {
+                variable: Some("y".into()),
+                expression: Expression::BinOp {
+                    op: BinOp::Add,
+                    lhs: Expression::Call {
+                        name: "sqrt".into(),
+                        args: vec![Expression::Variable("x".into())],
+                    }
+                    .into(),
+                    rhs: Expression::Num(1.0).into(),
+                }
+            }
+        );
+
+        assert_eq!(
+            parser::parse_command("sin(cos(0) * 2)").unwrap(),
+            Command {
+                variable: None,
+                expression: Expression::Call {
+                    name: "sin".into(),
+                    args: vec![Expression::BinOp {
+                        op: BinOp::Multiply,
+                        lhs: Expression::Call {
+                            name: "cos".into(),
+                            args: vec![Expression::Num(0.0)],
+                        }
+                        .into(),
+                        rhs: Expression::Num(2.0).into(),
+                    }],
+                }
+            }
+        );
+
+        assert!(parser::parse_command("sqrt(").is_err());
+        assert!(parser::parse_command("sqrt(1,)").is_err());
+    }
+
+    #[test]
+    fn test_context_calc_call() {
+        let mut ctx = context::Context::new();
+        let mut calc = |line: &str| {
+            ctx.calc_command(&parser::parse_command(line).unwrap())
+                .map(|(_, value)| value)
+        };
+
+        assert_eq!(calc("sqrt(16) + abs(0 - 3)").unwrap(), 7.0);
+        assert_eq!(calc("x = ln(1) + sin(0) + cos(0)").unwrap(), 1.0);
+        assert_eq!(calc("log(1000) * exp(x - 1)").unwrap(), 3.0);
+        assert!((calc("tan(1)").unwrap() - 1f64.tan()).abs() < 1e-12);
+
+        assert!(calc("foo(1)").is_err());
+        assert!(calc("sqrt(1, 2)").is_err());
+        assert!(calc("abs()").is_err());
+        assert!(calc("abs(undefined)").is_err());
+    }
+
+    #[test]
+    fn test_parse_associativity() {
+        // "1+2+3" is "(1+2)+3".
+        assert_eq!(
+            parser::parse_command("1+2+3").unwrap().expression,
+            Expression::BinOp {
+                op: BinOp::Add,
+                lhs: Expression::BinOp {
+                    op: BinOp::Add,
+                    lhs: Expression::Num(1.0).into(),
+                    rhs: Expression::Num(2.0).into(),
+                }
+                .into(),
+                rhs: Expression::Num(3.0).into(),
+            }
+        );
+
+        // "2^3^2" is "2^(3^2)".
+        assert_eq!(
+            parser::parse_command("2^3^2").unwrap().expression,
+            Expression::BinOp {
+                op: BinOp::Power,
+                lhs: Expression::Num(2.0).into(),
+                rhs: Expression::BinOp {
+                    op: BinOp::Power,
+                    lhs: Expression::Num(3.0).into(),
+                    rhs: Expression::Num(2.0).into(),
+                }
+                .into(),
+            }
+        );
+
+        // "8/4/2" is "(8/4)/2" and "1-2*3^2" is "1-(2*(3^2))".
+        let ctx = context::Context::new();
+        let calc = |line: &str| {
+            ctx.calc_expression(&parser::parse_command(line).unwrap().expression)
+                .unwrap()
+        };
+        assert_eq!(calc("8/4/2"), 1.0);
+        assert_eq!(calc("10-4-3"), 3.0);
+        assert_eq!(calc("2^3^2"), 512.0);
+        assert_eq!(calc("1-2*3^2"), -17.0);
+    }
+
+    #[test]
+    fn test_parse_statement() {
+        assert_eq!(parser::parse_statement("vars").unwrap(), Statement::Vars);
+        assert_eq!(
+            parser::parse_statement(" clear ").unwrap(),
+            Statement::Clear
+        );
+        assert_eq!(
+            parser::parse_statement("del x1").unwrap(),
+            Statement::Delete("x1".into())
+        );
+        assert_eq!(
+            parser::parse_statement("vars = 1").unwrap(),
+            Statement::Command(Command {
+                variable: Some("vars".into()),
+                expression: Expression::Num(1.0),
+            })
+        );
+        assert_eq!(
+            parser::parse_statement("clear + 1").unwrap(),
+            Statement::Command(parser::parse_command("clear + 1").unwrap())
+        );
+        assert_eq!(
+            parser::parse_statement("delx").unwrap(),
+            Statement::Command(parser::parse_command("delx").unwrap())
+        );
+        assert!(parser::parse_statement("del x y").is_err());
+        assert!(parser::parse_statement("del 1").is_err());
+    }
+
+    #[test]
+    fn test_context_management() {
+        let mut ctx = context::Context::new();
+        for line in ["x = 1", "y = 2", "x + y"] {
+            let _unused = ctx
+                .calc_command(&parser::parse_command(line).unwrap())
+                .unwrap();
+        }
+
+        let mut variables = ctx.variables().collect::<Vec<_>>();
+        variables.sort_by_key(|&(variable, _)| variable);
+        assert_eq!(
+            variables,
+            [("$0", 3.0.into()), ("x", 1.0.into()), ("y", 2.0.into())]
+        );
+
+        assert_eq!(ctx.remove_variable("x"), Some(1.0.into()));
+        assert_eq!(ctx.remove_variable("x"), None);
+        assert!(ctx
+            .calc_expression(&parser::parse_command("x").unwrap().expression)
+            .is_err());
+        assert_eq!(ctx.variables().count(), 2);
+
+        ctx.reset();
+        assert_eq!(ctx.variables().count(), 0);
+        assert_eq!(ctx.current_counter(), 0);
+    }
+
+    #[test]
+    fn test_calc_error() {
+        let err = parser::parse_command("1 + * 2").unwrap_err();
+        assert!(matches!(err, CalcError::Syntax { .. }));
+        assert_eq!(err.span("1 + * 2"), Some(4..4));
+
+        let mut ctx = context::Context::new();
+        let mut calc = |line: &str| {
+            ctx.calc_command(&parser::parse_command(line).unwrap())
+                .map(|(_, value)| value)
+        };
+        assert_eq!(calc("x = 1 / 0"), Err(CalcError::DivisionByZero));
+        assert_eq!(
+            calc("xy + x"),
+            Err(CalcError::UndefinedVariable("xy".into()))
+        );
+        assert_eq!(
+            calc("foo(1)"),
+            Err(CalcError::UnknownFunction("foo".into()))
+        );
+        assert_eq!(
+            calc("abs(1, 2)"),
+            Err(CalcError::Arity {
+                name: "abs".into(),
+                expected: 1,
+                found: 2
+            })
+        );
+
+        assert_eq!(
+            CalcError::UndefinedVariable("x".into()).span("xy + x * 2"),
+            Some(5..6)
+        );
+        assert_eq!(
+            CalcError::UndefinedVariable("$1".into()).span("$10 + $1"),
+            Some(6..8)
+        );
+        assert_eq!(CalcError::DivisionByZero.span("1 / 0"), None);
+        assert_eq!(
+            CalcError::UnknownFunction("foo".into()).to_string(),
+            "unknown function: foo"
+        );
+    }
+
+    #[test]
+    fn test_numeric_literals() {
+        let parse = |line: &str| parser::parse_command(line).map(|command| command.expression);
+        assert_eq!(parse("1e-3").unwrap(), Expression::Num(1e-3));
+        assert_eq!(parse("2.5E6").unwrap(), Expression::Num(2.5e6));
+        assert_eq!(parse("1_000_000").unwrap(), Expression::Num(1_000_000.0));
+        assert_eq!(
+            parse("1_000.000_5e+1_0").unwrap(),
+            Expression::Num(1000.0005e10)
+        );
+        assert_eq!(parse("3.").unwrap(), Expression::Num(3.0));
+
+        assert!(parse("1__000").is_err());
+        assert!(parse("1_").is_err());
+        assert!(parse("_1").is_err());
+        assert!(parse("1e").is_err());
+        assert!(parse("1._5").is_err());
+
+        let mut ctx = context::Context::new();
+        let mut calc = |line: &str| {
+            ctx.calc_command(&parser::parse_command(line).unwrap())
+                .map(|(_, value)| value)
+                .unwrap()
+        };
+        assert_eq!(calc("x = 1_000 * 1e-3"), 1.0);
+        assert_eq!(calc("2.5E6 / 2_500_000 + x"), 2.0);
+        assert_eq!(calc("1e3-1E3"), 0.0);
+        assert_eq!(calc("2e-1 * 5"), 1.0);
+    }
+
+    #[test]
+    fn test_complex() {
+        assert_eq!(
+            parser::parse_command("3+2i").unwrap().expression,
+            Expression::BinOp {
+                op: BinOp::Add,
+                lhs: Expression::Num(3.0).into(),
+                rhs: Expression::Imaginary(2.0).into(),
+            }
+        );
+        assert_eq!(
+            parser::parse_command("i").unwrap().expression,
+            Expression::Imaginary(1.0)
+        );
+        assert_eq!(
+            parser::parse_command("i2").unwrap().expression,
+            Expression::Variable("i2".into())
+        );
+
+        let mut ctx = context::Context::new();
+        let mut calc = |line: &str| {
+            ctx.calc_complex_command(&parser::parse_command(line).unwrap())
+                .map(|(_, value)| value)
+                .unwrap()
+        };
+        assert_eq!(calc("(1+i)^4"), Complex::from(-4.0));
+        assert_eq!(calc("z = (1+2i)*(3-i)"), Complex::new(5.0, 5.0));
+        assert_eq!(calc("z / (1+2i)"), Complex::new(3.0, -1.0));
+        assert_eq!(calc("i^2"), Complex::from(-1.0));
+        assert_eq!(calc("sqrt(0-4)"), Complex::new(0.0, 2.0));
+        assert_eq!(calc("abs(3+4i)"), Complex::from(5.0));
+        assert_eq!(calc("2^-1"), Complex::from(0.5));
+
+        let euler = calc("exp(i * 3.141592653589793) + 1");
+        assert!(euler.abs() < 1e-15);
+        let root = calc("(0-8)^(1/3)");
+        assert!((root - Complex::new(1.0, 3f64.sqrt())).abs() < 1e-12);
+
+        assert_eq!(Complex::new(3.0, 2.0).to_string(), "3+2i");
+        assert_eq!(Complex::new(1.0, -1.0).to_string(), "1-i");
+        assert_eq!(Complex::new(0.0, -2.5).to_string(), "-2.5i");
+        assert_eq!(Complex::I.to_string(), "i");
+        assert_eq!(Complex::from(-4.0).to_string(), "-4");
+
+        let mut ctx = context::Context::new();
+        let command = parser::parse_command("1 + i").unwrap();
+        assert_eq!(
+            ctx.calc_command(&command),
+            Err(CalcError::NotReal(Complex::new(1.0, 1.0)))
+        );
+        assert_eq!(
+            ctx.calc_command(&parser::parse_command("i * i").unwrap()),
+            Ok(("$1".into(), -1.0))
+        );
+        assert!(ctx
+            .calc_expression(&parser::parse_command("$0").unwrap().expression)
+            .is_err());
+        assert_eq!(
+            ctx.calc_command(&parser::parse_command("1 / (0i)").unwrap()),
+            Err(CalcError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        use crate::assignments::assignment06::symbolic_differentiation::Evaluate;
+
+        let parse_diff = |line: &str| match parser::parse_statement(line).unwrap() {
+            Statement::Diff(expression) => expression,
+            statement => panic!("not a diff command: {:?}", statement),
+        };
+        assert_eq!(
+            parse_diff("diff x ^ 2"),
+            parser::parse_command("x ^ 2").unwrap().expression
+        );
+        assert!(matches!(
+            parser::parse_statement("diff = 1").unwrap(),
+            Statement::Command(_)
+        ));
+        assert!(matches!(
+            parser::parse_statement("diffx").unwrap(),
+            Statement::Command(_)
+        ));
+
+        let derive = |line: &str| diff::differentiate(&parse_diff(line));
+        assert_eq!(derive("diff x^3").unwrap().to_string(), "(3)x^(2)");
+        assert_eq!(derive("diff 2*x + 1").unwrap().to_string(), "2");
+        assert_eq!(derive("diff 5").unwrap().to_string(), "0");
+        assert_eq!(derive("diff sin(x)").unwrap().to_string(), "cos(x)");
+        assert_eq!(
+            derive("diff cos(x) / 2").unwrap().to_string(),
+            "(-1/2)sin(x)"
+        );
+        assert_eq!(derive("diff x^2 - x^2").unwrap().to_string(), "0");
+        assert_eq!(derive("diff 0.5 * x^2").unwrap().to_string(), "x");
+        assert_eq!(derive("diff 0.07 * x").unwrap().to_string(), "7/100");
+        assert_eq!(
+            derive("diff 0.123456 * x").unwrap().to_string(),
+            "1929/15625"
+        );
+
+        // Compares with the derivatives at some points.
+        type Derivative = fn(f64) -> f64;
+        let cases: [(&str, Derivative); 4] = [
+            ("diff sin(x^2)", |x| 2.0 * x * (x * x).cos()),
+            ("diff exp(2*x) * x", |x| (2.0 * x).exp() * (2.0 * x + 1.0)),
+            ("diff 1 / (x^2 + 1)", |x| -2.0 * x / (x * x + 1.0).powi(2)),
+            ("diff (3*x + 1)^3", |x| 9.0 * (3.0 * x + 1.0).powi(2)),
+        ];
+        for (line, expected) in cases {
+            let derivative = derive(line).unwrap();
+            for x in [0.5, 1.0, 2.0] {
+                assert!(
+                    (derivative.evaluate(x) - expected(x)).abs() < 1e-9,
+                    "{line} at {x}"
+                );
+            }
+        }
+
+        assert_eq!(
+            derive("diff y * x"),
+            Err(CalcError::UndefinedVariable("y".into()))
+        );
+        assert!(matches!(
+            derive("diff x ^ x"),
+            Err(CalcError::Unsupported(_))
+        ));
+        assert!(matches!(
+            derive("diff sqrt(x)"),
+            Err(CalcError::Unsupported(_))
+        ));
+        assert!(matches!(
+            derive("diff i * x"),
+            Err(CalcError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_ans() {
+        assert_eq!(
+            parser::parse_command("ans * 2").unwrap().expression,
+            Expression::BinOp {
+                op: BinOp::Multiply,
+                lhs: Expression::Ans.into(),
+                rhs: Expression::Num(2.0).into(),
+            }
+        );
+        assert_eq!(
+            parser::parse_command("answer").unwrap().expression,
+            Expression::Variable("answer".into())
+        );
+        assert!(parser::parse_command("ans = 1").is_err());
+
+        let mut ctx = context::Context::new();
+        let mut calc = |line: &str| {
+            ctx.calc_command(&parser::parse_command(line).unwrap())
+                .map(|(_, value)| value)
+        };
+        assert_eq!(calc("ans"), Err(CalcError::UndefinedVariable("ans".into())));
+        assert_eq!(calc("1 + 2"), Ok(3.0));
+        assert_eq!(calc("ans * ans"), Ok(9.0));
+        assert_eq!(calc("x = ans - 1"), Ok(8.0));
+        assert_eq!(calc("ans / 0"), Err(CalcError::DivisionByZero));
+        assert_eq!(calc("ans + x"), Ok(16.0));
+
+        // `$n` refers to the n-th anonymous command, where failed ones are skipped.
+        assert_eq!(calc("$1 + $2 + $4"), Ok(28.0));
+        assert_eq!(ctx.ans(), Some(28.0.into()));
+
+        ctx.reset();
+        assert_eq!(ctx.ans(), None);
+    }
+
+    #[test]
+    fn test_expression_display() {
+        let cases = [
+            ("1+2+3", "1 + 2 + 3"),
+            ("1+(2+3)", "1 + (2 + 3)"),
+            ("1-(2-3)", "1 - (2 - 3)"),
+            ("(1*2)+3", "1 * 2 + 3"),
+            ("(1+2)*3", "(1 + 2) * 3"),
+            ("2^3^2", "2 ^ 3 ^ 2"),
+            ("(2^3)^2", "(2 ^ 3) ^ 2"),
+            ("-2^2", "-2 ^ 2"),
+            ("1 - -2", "1 - -2"),
+            ("x / (y * z)", "x / (y * z)"),
+            ("sin(x^2, (1+2)) * ans", "sin(x ^ 2, 1 + 2) * ans"),
+            ("3+2i - i", "3 + 2i - i"),
+            ("1e-3 * 1_000", "0.001 * 1000"),
+        ];
+        for (input, expected) in cases {
+            let expression = parser::parse_command(input).unwrap().expression;
+            assert_eq!(expression.to_string(), expected);
+            assert_eq!(
+                parser::parse_command(expected).unwrap().expression,
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn test_calc_expression_with() {
+        use std::collections::HashMap;
+
+        let mut ctx = context::Context::new();
+        let _unused = ctx
+            .calc_command(&parser::parse_command("x = 2").unwrap())
+            .unwrap();
+        let expression = parser::parse_command("x * y + 1").unwrap().expression;
+
+        let overrides = HashMap::from([("y".to_string(), 10.0)]);
+        assert_eq!(ctx.calc_expression_with(&expression, &overrides), Ok(21.0));
+
+        let overrides = HashMap::from([("x".to_string(), 3.0), ("y".to_string(), 1.0)]);
+        assert_eq!(ctx.calc_expression_with(&expression, &overrides), Ok(4.0));
+
+        assert_eq!(
+            ctx.calc_expression_with(&expression, &HashMap::new()),
+            Err(CalcError::UndefinedVariable("y".into()))
+        );
+        assert_eq!(
+            ctx.calc_expression(&parser::parse_command("x").unwrap().expression),
+            Ok(2.0)
+        );
+        assert_eq!(ctx.variables().count(), 1);
+    }
 }
@@ -25,6 +25,8 @@
 //! and submit the generated `assignment04.zip` file in `target` directory.
 
 pub mod context;
+pub mod diff;
+pub mod error;
 mod grade;
 pub mod parser;
 pub mod syntax;
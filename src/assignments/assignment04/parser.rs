@@ -1,16 +1,14 @@
-#![allow(deprecated)]
-
 //! Parser.
 
 use std::default;
 
-use anyhow::{bail, Result};
 use etrace::*;
 use lazy_static::*;
 use pest::iterators::{Pair, Pairs};
-use pest::prec_climber::*;
+use pest::pratt_parser::{Assoc, Op, PrattParser};
 use pest::Parser;
 
+use super::error::CalcError;
 use super::syntax::*;
 
 #[allow(missing_docs)]
@@ -25,67 +23,119 @@ mod inner {
 
 use inner::*;
 
-// Helper: build a precedence climber once
-fn climber() -> PrecClimber<Rule> {
-    PrecClimber::new(vec![
+type Result<T> = std::result::Result<T, CalcError>;
+
+lazy_static! {
+    static ref PRATT_PARSER: PrattParser<Rule> = PrattParser::new()
         // lowest precedence
-        Operator::new(Rule::add, Assoc::Left) | Operator::new(Rule::subtract, Assoc::Left),
-        Operator::new(Rule::multiply, Assoc::Left) | Operator::new(Rule::divide, Assoc::Left),
+        .op(Op::infix(Rule::add, Assoc::Left) | Op::infix(Rule::subtract, Assoc::Left))
+        .op(Op::infix(Rule::multiply, Assoc::Left) | Op::infix(Rule::divide, Assoc::Left))
         // highest precedence
-        Operator::new(Rule::power, Assoc::Right),
-    ])
+        .op(Op::infix(Rule::power, Assoc::Right));
 }
 
 // Map pest rule -> BinOp
-fn rule_to_binop(rule: Rule) -> Result<BinOp> {
-    Ok(match rule {
+fn rule_to_binop(pair: &Pair<'_, Rule>) -> Result<BinOp> {
+    Ok(match pair.as_rule() {
         Rule::add => BinOp::Add,
         Rule::subtract => BinOp::Subtract,
         Rule::multiply => BinOp::Multiply,
         Rule::divide => BinOp::Divide,
         Rule::power => BinOp::Power,
-        _ => bail!("unexpected operator rule: {:?}", rule),
+        rule => {
+            return Err(unexpected(
+                pair,
+                format!("unexpected operator rule: {:?}", rule),
+            ))
+        }
     })
 }
 
+// Syntax error spanning `pair`
+fn unexpected(pair: &Pair<'_, Rule>, message: String) -> CalcError {
+    let span = pair.as_span();
+    CalcError::Syntax {
+        span: span.start()..span.end(),
+        message,
+    }
+}
+
+// Convert a pest error into a syntax error
+fn from_pest(error: pest::error::Error<Rule>) -> CalcError {
+    let span = match error.location {
+        pest::error::InputLocation::Pos(pos) => pos..pos,
+        pest::error::InputLocation::Span((start, end)) => start..end,
+    };
+    CalcError::Syntax {
+        span,
+        message: error.variant.message().into_owned(),
+    }
+}
+
 // Parse an expr Pair into Expression AST
 fn parse_expr_pair(pair: Pair<'_, Rule>) -> Result<Expression> {
     if pair.as_rule() != Rule::expr {
-        bail!("expected expr, got {:?}", pair.as_rule());
+        return Err(unexpected(
+            &pair,
+            format!("expected expr, got {:?}", pair.as_rule()),
+        ));
     }
 
-    let climber = climber();
-
-    // climb over expr's inner pairs: atom op atom op atom ...
-    let expr = climber.climb(
-        pair.into_inner(),
-        |p: Pair<'_, Rule>| parse_primary(p),
-        |lhs: Result<Expression>, op: Pair<'_, Rule>, rhs: Result<Expression>| {
+    // fold expr's inner pairs by precedence: atom op atom op atom ...
+    let expr = PRATT_PARSER
+        .map_primary(parse_primary)
+        .map_infix(|lhs, op, rhs| {
             let lhs = lhs?;
             let rhs = rhs?;
-            let op = rule_to_binop(op.as_rule())?;
+            let op = rule_to_binop(&op)?;
 
             Ok(Expression::BinOp {
                 op,
                 lhs: Box::new(lhs),
                 rhs: Box::new(rhs),
             })
-        },
-    )?;
+        })
+        .parse(pair.into_inner())?;
 
     Ok(expr)
 }
 
-// Parse "primary"/atom: num | var | parenthesized expr (which appears as Rule::expr)
+// Parse "primary"/atom: imag | num | call | ans | var | parenthesized expr (which appears as Rule::expr)
 fn parse_primary(pair: Pair<'_, Rule>) -> Result<Expression> {
     match pair.as_rule() {
         Rule::num => {
-            let n: f64 = pair.as_str().parse()?;
+            // digit separators are only for readability
+            let n: f64 = pair
+                .as_str()
+                .replace('_', "")
+                .parse()
+                .map_err(|e| unexpected(&pair, format!("{}", e)))?;
             Ok(Expression::Num(n))
         }
+        Rule::imag => match pair.clone().into_inner().next() {
+            Some(num) => match parse_primary(num)? {
+                Expression::Num(n) => Ok(Expression::Imaginary(n)),
+                _ => Err(unexpected(&pair, "expected num".into())),
+            },
+            None => Ok(Expression::Imaginary(1.0)),
+        },
+        Rule::ans => Ok(Expression::Ans),
         Rule::var => Ok(Expression::Variable(pair.as_str().to_string())),
+        Rule::call => {
+            let mut inner = pair.clone().into_inner();
+            let name = inner
+                .next()
+                .ok_or_else(|| unexpected(&pair, "missing function name".into()))?
+                .as_str()
+                .to_string();
+            let args = inner.map(parse_expr_pair).collect::<Result<Vec<_>>>()?;
+            Ok(Expression::Call { name, args })
+        }
         Rule::expr => parse_expr_pair(pair),
-        other => bail!("unexpected primary: {:?}", other),
+        other => Err(unexpected(
+            &pair,
+            format!("unexpected primary: {:?}", other),
+        )),
     }
 }
 
@@ -93,21 +143,55 @@ fn parse_primary(pair: Pair<'_, Rule>) -> Result<Expression> {
 ///
 /// ## Operator Associativty
 ///
-/// For associativity of each operator, please follow [here](https://docs.rs/pest/latest/pest/prec_climber/struct.PrecClimber.html#examples).
+/// For associativity of each operator, please follow [here](https://docs.rs/pest/latest/pest/pratt_parser/struct.PrattParser.html#examples).
 ///
 /// e.g. `1+2+3` should be parsed into `(1+2)+3`, not `1+(2+3)` because the associativity of
 /// plus("add" in our hw) operator is `Left`.
 
-pub fn parse_command<'i>(line: &'i str) -> Result<Command> {
-    let mut parsed = SyntaxParser::parse(Rule::command, line)?;
-    println!("pared {:?}", parsed);
+pub fn parse_command(line: &str) -> std::result::Result<Command, CalcError> {
+    let top_pairs = parse_top_level(Rule::command, line)?;
+    command_from_pairs(&top_pairs)
+}
+
+/// Parses statement, i.e. a command, one of the context management commands `vars`, `del {var}`
+/// and `clear`, or `diff {expression}`.
+pub fn parse_statement(line: &str) -> std::result::Result<Statement, CalcError> {
+    let top_pairs = parse_top_level(Rule::statement, line)?;
 
-    let first = parsed
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("empty parse result"))?;
+    match top_pairs.as_slice() {
+        [cmd] if cmd.as_rule() == Rule::vars_cmd => Ok(Statement::Vars),
+        [cmd] if cmd.as_rule() == Rule::clear_cmd => Ok(Statement::Clear),
+        [cmd] if cmd.as_rule() == Rule::del_cmd => {
+            let var = cmd
+                .clone()
+                .into_inner()
+                .next()
+                .ok_or_else(|| unexpected(cmd, "missing variable".into()))?;
+            Ok(Statement::Delete(var.as_str().to_string()))
+        }
+        [cmd] if cmd.as_rule() == Rule::diff_cmd => {
+            let expr = cmd
+                .clone()
+                .into_inner()
+                .find(|p| p.as_rule() == Rule::expr)
+                .ok_or_else(|| unexpected(cmd, "missing expression".into()))?;
+            Ok(Statement::Diff(parse_expr_pair(expr)?))
+        }
+        _ => command_from_pairs(&top_pairs).map(Statement::Command),
+    }
+}
+
+// Parse `line` from the silent top-level `rule`, returning its pairs without EOI
+fn parse_top_level(rule: Rule, line: &str) -> Result<Vec<Pair<'_, Rule>>> {
+    let mut parsed = SyntaxParser::parse(rule, line).map_err(from_pest)?;
+
+    let first = parsed.next().ok_or_else(|| CalcError::Syntax {
+        span: 0..line.len(),
+        message: "empty parse result".into(),
+    })?;
 
     // Collect pairs whether `command` is silent or not
-    let mut top_pairs: Vec<Pair<'i, Rule>> = if first.as_rule() == Rule::command {
+    let mut top_pairs: Vec<Pair<'_, Rule>> = if first.as_rule() == rule {
         first.into_inner().collect()
     } else {
         let mut v = vec![first];
@@ -118,11 +202,19 @@ pub fn parse_command<'i>(line: &'i str) -> Result<Command> {
     // Filter out SOI/EOI if they appear
     top_pairs.retain(|p| !matches!(p.as_rule(), Rule::EOI));
 
-    match top_pairs.as_slice() {
+    Ok(top_pairs)
+}
+
+// Build a Command from the top-level pairs: `var expr` or `expr`
+fn command_from_pairs(top_pairs: &[Pair<'_, Rule>]) -> Result<Command> {
+    match top_pairs {
         // assignment: var expr
         [var_pair, expr_pair]
             if var_pair.as_rule() == Rule::var && expr_pair.as_rule() == Rule::expr =>
         {
+            if var_pair.as_str() == "ans" {
+                return Err(unexpected(var_pair, "cannot assign to `ans`".into()));
+            }
             Ok(Command {
                 variable: Some(var_pair.as_str().to_string()),
                 expression: parse_expr_pair(expr_pair.clone())?,
@@ -135,9 +227,15 @@ pub fn parse_command<'i>(line: &'i str) -> Result<Command> {
             expression: parse_expr_pair(expr_pair.clone())?,
         }),
 
-        other => bail!(
-            "unexpected command shape: {:?}",
-            other.iter().map(|p| p.as_rule()).collect::<Vec<_>>()
-        ),
+        other => Err(CalcError::Syntax {
+            span: match (other.first(), other.last()) {
+                (Some(first), Some(last)) => first.as_span().start()..last.as_span().end(),
+                _ => 0..0,
+            },
+            message: format!(
+                "unexpected command shape: {:?}",
+                other.iter().map(|p| p.as_rule()).collect::<Vec<_>>()
+            ),
+        }),
     }
 }
@@ -1,5 +1,7 @@
 //! Syntax.
 
+use std::fmt;
+
 /// Command of the form "{expression}" or "{var} = {expression}".
 #[derive(Debug, Clone, PartialEq)]
 pub struct Command {
@@ -9,6 +11,21 @@ pub struct Command {
     pub expression: Expression,
 }
 
+/// Line of calculator input: either a command or a context management command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    /// Command of the form "{expression}" or "{var} = {expression}".
+    Command(Command),
+    /// "vars": lists the variables.
+    Vars,
+    /// "del {var}": deletes the variable.
+    Delete(String),
+    /// "clear": deletes every variable.
+    Clear,
+    /// "diff {expression}": differentiates the expression with respect to `x`.
+    Diff(Expression),
+}
+
 /// Binary operators.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BinOp {
@@ -29,8 +46,12 @@ pub enum BinOp {
 pub enum Expression {
     /// Number.
     Num(f64),
+    /// Imaginary number, e.g. `2i` is `Imaginary(2.0)` and `i` is `Imaginary(1.0)`.
+    Imaginary(f64),
     /// Variable.
     Variable(String),
+    /// `ans`, the most recently computed value.
+    Ans,
     /// Binary operation.
     BinOp {
         /// Operator.
@@ -40,4 +61,85 @@ pub enum Expression {
         /// Rhs.
         rhs: Box<Expression>,
     },
+    /// Function call.
+    Call {
+        /// Function name.
+        name: String,
+        /// Arguments.
+        args: Vec<Expression>,
+    },
+}
+
+impl BinOp {
+    // Precedence, matching the parser
+    fn precedence(self) -> u8 {
+        match self {
+            BinOp::Add | BinOp::Subtract => 1,
+            BinOp::Multiply | BinOp::Divide => 2,
+            BinOp::Power => 3,
+        }
+    }
+
+    fn is_right_assoc(self) -> bool {
+        self == BinOp::Power
+    }
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            BinOp::Add => "+",
+            BinOp::Subtract => "-",
+            BinOp::Multiply => "*",
+            BinOp::Divide => "/",
+            BinOp::Power => "^",
+        };
+        write!(f, "{}", op)
+    }
+}
+
+impl fmt::Display for Expression {
+    /// Formats with the minimal parentheses, so that parsing the result gives back the same
+    /// expression.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expression::Num(n) => write!(f, "{}", n),
+            Expression::Imaginary(n) if *n == 1.0 => write!(f, "i"),
+            Expression::Imaginary(n) => write!(f, "{}i", n),
+            Expression::Variable(name) => write!(f, "{}", name),
+            Expression::Ans => write!(f, "ans"),
+            Expression::BinOp { op, lhs, rhs } => {
+                // Operands of lower precedence, or of the same precedence on the side against the
+                // associativity, need parentheses.
+                let needs_parens = |operand: &Expression, is_rhs: bool| match operand {
+                    Expression::BinOp { op: inner, .. } => {
+                        inner.precedence() < op.precedence()
+                            || (inner.precedence() == op.precedence()
+                                && is_rhs != op.is_right_assoc())
+                    }
+                    _ => false,
+                };
+                let write_operand = |f: &mut fmt::Formatter<'_>, operand: &Expression, is_rhs| {
+                    if needs_parens(operand, is_rhs) {
+                        write!(f, "({})", operand)
+                    } else {
+                        write!(f, "{}", operand)
+                    }
+                };
+                write_operand(f, lhs, false)?;
+                write!(f, " {} ", op)?;
+                write_operand(f, rhs, true)
+            }
+            Expression::Call { name, args } => {
+                write!(f, "{}(", name)?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
 }
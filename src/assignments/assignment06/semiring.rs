@@ -51,6 +51,24 @@ impl Semiring for u64 {
     }
 }
 
+impl Semiring for i32 {
+    fn zero() -> Self {
+        0_i32
+    }
+
+    fn one() -> Self {
+        1_i32
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        *self + *rhs
+    }
+
+    fn mul(&self, rhs: &Self) -> Self {
+        *self * *rhs
+    }
+}
+
 impl Semiring for i64 {
     fn zero() -> Self {
         0_i64
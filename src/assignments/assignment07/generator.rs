@@ -2,9 +2,21 @@
 //!
 //! HINT: Look at the `generator_grade.rs` file to see how the generator is used.
 
+use std::marker::PhantomData;
+#[cfg(feature = "stream")]
+use std::pin::Pin;
+#[cfg(feature = "stream")]
+use std::task::{Context, Poll};
+
+#[cfg(feature = "stream")]
+use futures_core::Stream;
+
 /// Yielded value. It can be either a value or a stop signal.
-enum Yielded<T> {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Yielded<T> {
+    /// A value.
     Value(T),
+    /// The stop signal.
     Stop,
 }
 
@@ -12,15 +24,167 @@ enum Yielded<T> {
 /// - You can call `next()` method to get the next value.
 /// - The generator should stop when it yields `Yielded::Stop`.
 ///
+/// The step function `f` is a plain function pointer by default, but it can be any closure, e.g.,
+/// one that captures a limit from its environment.
+///
 /// Reference:
 /// - [Python generator](https://python-reference.readthedocs.io/en/latest/docs/generator/)
 #[derive(Debug)]
-pub struct Generator<T, S> {
+pub struct Generator<T, S, F = fn(&mut S) -> Yielded<T>> {
     state: S,
-    f: fn(&mut S) -> Yielded<T>,
+    f: F,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// Generator returned by [`Generator::chain`], whose state is the pair of the two generators.
+pub type ChainGenerator<A, B> = Generator<<A as Iterator>::Item, (A, B)>;
+
+/// Generator returned by [`Generator::zip`], whose state is the pair of the two generators.
+pub type ZipGenerator<A, B> = Generator<(<A as Iterator>::Item, <B as Iterator>::Item), (A, B)>;
+
+/// Generator returned by [`Generator::flatten`], whose state is the pair of the outer generator and
+/// the current inner generator.
+pub type FlattenGenerator<A, B> = Generator<<B as Iterator>::Item, (A, Option<B>)>;
+
+impl<T, S, F: FnMut(&mut S) -> Yielded<T>> Generator<T, S, F> {
+    /// Creates a generator that starts from `state` and steps with `f`.
+    pub fn new(state: S, f: F) -> Self {
+        Self {
+            state,
+            f,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    /// Returns a generator that yields the values of `self` and then the values of `other`.
+    pub fn chain<S2, F2>(
+        self,
+        other: Generator<T, S2, F2>,
+    ) -> ChainGenerator<Self, Generator<T, S2, F2>>
+    where
+        F2: FnMut(&mut S2) -> Yielded<T>,
+    {
+        fn step<T, A, B>(st: &mut (A, B)) -> Yielded<T>
+        where
+            A: Iterator<Item = T>,
+            B: Iterator<Item = T>,
+        {
+            st.0.next()
+                .or_else(|| st.1.next())
+                .map_or(Yielded::Stop, Yielded::Value)
+        }
+
+        Generator::new((self, other), step::<T, Self, Generator<T, S2, F2>>)
+    }
+
+    /// Returns a generator that yields the pairs of the values of `self` and `other`, until one of
+    /// them stops.
+    pub fn zip<U, S2, F2>(
+        self,
+        other: Generator<U, S2, F2>,
+    ) -> ZipGenerator<Self, Generator<U, S2, F2>>
+    where
+        F2: FnMut(&mut S2) -> Yielded<U>,
+    {
+        fn step<T, U, A, B>(st: &mut (A, B)) -> Yielded<(T, U)>
+        where
+            A: Iterator<Item = T>,
+            B: Iterator<Item = U>,
+        {
+            match (st.0.next(), st.1.next()) {
+                (Some(t), Some(u)) => Yielded::Value((t, u)),
+                _ => Yielded::Stop,
+            }
+        }
+
+        Generator::new((self, other), step::<T, U, Self, Generator<U, S2, F2>>)
+    }
+}
+
+impl<T, S, F, S2, F2> Generator<Generator<T, S2, F2>, S, F>
+where
+    F: FnMut(&mut S) -> Yielded<Generator<T, S2, F2>>,
+    F2: FnMut(&mut S2) -> Yielded<T>,
+{
+    /// Returns a generator that yields the values of each generator yielded by `self` in turn.
+    pub fn flatten(self) -> FlattenGenerator<Self, Generator<T, S2, F2>> {
+        fn step<T, A, B>(st: &mut (A, Option<B>)) -> Yielded<T>
+        where
+            A: Iterator<Item = B>,
+            B: Iterator<Item = T>,
+        {
+            loop {
+                if let Some(t) = st.1.as_mut().and_then(Iterator::next) {
+                    return Yielded::Value(t);
+                }
+                match st.0.next() {
+                    Some(inner) => st.1 = Some(inner),
+                    None => return Yielded::Stop,
+                }
+            }
+        }
+
+        Generator::new((self, None), step::<T, Self, Generator<T, S2, F2>>)
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<T, S, F: FnMut(&mut S) -> Yielded<T>> Generator<T, S, F> {
+    /// Wraps the generator into a [`Stream`], which steps the generator whenever it is polled.
+    pub fn into_stream(self) -> GeneratorStream<T, S, F> {
+        GeneratorStream { generator: self }
+    }
+}
+
+/// Stream returned by [`Generator::into_stream`].
+///
+/// The step function never blocks, so the stream is always ready.
+#[cfg(feature = "stream")]
+#[derive(Debug)]
+pub struct GeneratorStream<T, S, F> {
+    generator: Generator<T, S, F>,
+}
+
+#[cfg(feature = "stream")]
+impl<T, S: Unpin, F: FnMut(&mut S) -> Yielded<T> + Unpin> Stream for GeneratorStream<T, S, F> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.get_mut().generator.next())
+    }
+}
+
+impl<T, G: FnMut() -> Option<T>> Generator<T, G> {
+    /// Creates a generator that yields the values returned by `g` until it returns `None`.
+    pub fn from_fn(g: G) -> Self {
+        fn step<T, G: FnMut() -> Option<T>>(g: &mut G) -> Yielded<T> {
+            g().map_or(Yielded::Stop, Yielded::Value)
+        }
+
+        Generator::new(g, step::<T, G>)
+    }
+}
+
+impl<T, I: Iterator<Item = T>> Generator<T, I> {
+    /// Creates a generator that yields the elements of `iter`.
+    ///
+    /// Unlike `FromIterator::from_iter`, this does not collect the elements.
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_iter<J: IntoIterator<IntoIter = I>>(iter: J) -> Self {
+        fn step<T, I: Iterator<Item = T>>(iter: &mut I) -> Yielded<T> {
+            iter.next().map_or(Yielded::Stop, Yielded::Value)
+        }
+
+        Generator::new(iter.into_iter(), step::<T, I>)
+    }
 }
 
-impl<T, S> Iterator for Generator<T, S> {
+impl<T, S, F: FnMut(&mut S) -> Yielded<T>> Iterator for Generator<T, S, F> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -31,6 +195,94 @@ impl<T, S> Iterator for Generator<T, S> {
     }
 }
 
+/// Fallible generator
+/// - The step function returns `Err` to report a failure instead of panicking.
+/// - The generator yields `Ok` values until it stops, or `Err` once and then stops.
+#[derive(Debug)]
+pub struct TryGenerator<T, E, S, F = fn(&mut S) -> Result<Yielded<T>, E>> {
+    state: S,
+    f: F,
+    failed: bool,
+    _marker: PhantomData<fn() -> (T, E)>,
+}
+
+impl<T, E, S, F: FnMut(&mut S) -> Result<Yielded<T>, E>> TryGenerator<T, E, S, F> {
+    /// Creates a fallible generator that starts from `state` and steps with `f`.
+    pub fn new(state: S, f: F) -> Self {
+        Self {
+            state,
+            f,
+            failed: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+}
+
+impl<T, E, S, F: FnMut(&mut S) -> Result<Yielded<T>, E>> Iterator for TryGenerator<T, E, S, F> {
+    type Item = Result<T, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        match (self.f)(&mut self.state) {
+            Ok(Yielded::Value(t)) => Some(Ok(t)),
+            Ok(Yielded::Stop) => None,
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Two-way generator
+/// - You can call `send(value)` to resume the coroutine with `value` and get the next value.
+/// - Calling `next()` resumes the coroutine with the default value of `R`, like `next()` in Python
+///   resumes a generator with `None`.
+/// - The coroutine should stop when it yields `Yielded::Stop`.
+///
+/// Reference:
+/// - [Python generator.send](https://docs.python.org/3/reference/expressions.html#generator.send)
+#[derive(Debug)]
+pub struct Coroutine<T, R, S, F = fn(&mut S, R) -> Yielded<T>> {
+    state: S,
+    f: F,
+    _marker: PhantomData<fn(R) -> T>,
+}
+
+impl<T, R, S, F: FnMut(&mut S, R) -> Yielded<T>> Coroutine<T, R, S, F> {
+    /// Creates a coroutine that starts from `state` and steps with `f`.
+    pub fn new(state: S, f: F) -> Self {
+        Self {
+            state,
+            f,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Resumes the coroutine with `value`, and returns the next value or `None` if it stops.
+    pub fn send(&mut self, value: R) -> Option<T> {
+        match (self.f)(&mut self.state, value) {
+            Yielded::Value(t) => Some(t),
+            Yielded::Stop => None,
+        }
+    }
+}
+
+impl<T, R: Default, S, F: FnMut(&mut S, R) -> Yielded<T>> Iterator for Coroutine<T, R, S, F> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.send(R::default())
+    }
+}
+
 /// Returns a generator that yields fibonacci numbers.
 ///
 /// HINT: Consult <https://en.wikipedia.org/wiki/Fibonacci_sequence>
@@ -43,10 +295,7 @@ pub fn fib_generator(first: usize, second: usize) -> Generator<usize, (usize, us
         Yielded::Value(out)
     }
 
-    Generator {
-        state: (first, second),
-        f: fib_step,
-    }
+    Generator::new((first, second), fib_step)
 }
 
 /// Returns a generator that yields collatz numbers.
@@ -87,8 +336,5 @@ pub fn collatz_conjecture(start: usize) -> Generator<usize, usize> {
     }
 
     // If you want to reject start == 0 instead, you could set state to 0 to stop immediately.
-    Generator {
-        state: start,
-        f: collatz_step,
-    }
+    Generator::new(start, collatz_step)
 }
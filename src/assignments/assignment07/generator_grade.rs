@@ -35,4 +35,198 @@ mod test {
             ]
         );
     }
+
+    #[test]
+    fn test_closure_generator() {
+        let limit = 5;
+        let counter = Generator::new(0, move |n: &mut usize| {
+            if *n == limit {
+                return Yielded::Stop;
+            }
+            *n += 1;
+            Yielded::Value(*n)
+        });
+        assert_eq!(counter.collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+
+        let mut log = Vec::new();
+        let squares = Generator::new(1, |n: &mut u64| {
+            log.push(*n);
+            let square = *n * *n;
+            *n += 1;
+            Yielded::Value(square)
+        });
+        assert_eq!(squares.take(4).collect::<Vec<_>>(), vec![1, 4, 9, 16]);
+        assert_eq!(log, vec![1, 2, 3, 4]);
+
+        // Plain functions keep working.
+        fn stop(_: &mut ()) -> Yielded<i32> {
+            Yielded::Stop
+        }
+        let empty: Generator<i32, ()> = Generator::new((), stop);
+        assert_eq!(empty.count(), 0);
+    }
+
+    #[test]
+    fn test_coroutine() {
+        // Running average of the sent values.
+        let mut average = Coroutine::new((0.0, 0), |(sum, count): &mut (f64, i32), x: f64| {
+            *sum += x;
+            *count += 1;
+            Yielded::Value(*sum / *count as f64)
+        });
+        assert_about_eq!(average.send(10.0).unwrap(), 10.0);
+        assert_about_eq!(average.send(20.0).unwrap(), 15.0);
+        assert_about_eq!(average.send(60.0).unwrap(), 30.0);
+
+        // `next()` sends the default value, and the coroutine stops on `Yielded::Stop`.
+        let mut counter = Coroutine::new(0, |n: &mut i32, reset: Option<i32>| {
+            if let Some(reset) = reset {
+                *n = reset;
+            }
+            if *n >= 5 {
+                return Yielded::Stop;
+            }
+            *n += 1;
+            Yielded::Value(*n)
+        });
+        assert_eq!(counter.next(), Some(1));
+        assert_eq!(counter.next(), Some(2));
+        assert_eq!(counter.send(Some(-2)), Some(-1));
+        assert_eq!(counter.by_ref().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(counter.send(None), None);
+        assert_eq!(counter.send(Some(3)), Some(4));
+    }
+
+    #[test]
+    fn test_generator_constructors() {
+        let mut n = 0;
+        let powers = Generator::from_fn(|| {
+            n += 1;
+            (n <= 5).then(|| 1 << n)
+        });
+        assert_eq!(powers.collect::<Vec<_>>(), vec![2, 4, 8, 16, 32]);
+
+        let mut words = Generator::from_iter(["a", "b", "c"]);
+        assert_eq!(words.next(), Some("a"));
+        assert_eq!(words.collect::<String>(), "bc");
+
+        assert_eq!(
+            Generator::from_iter(collatz_conjecture(6)).collect::<Vec<_>>(),
+            collatz_conjecture(6).collect::<Vec<_>>()
+        );
+        assert_eq!(Generator::from_iter(Vec::<i32>::new()).count(), 0);
+    }
+
+    #[test]
+    fn test_generator_composition() {
+        assert_eq!(
+            collatz_conjecture(4)
+                .chain(collatz_conjecture(3))
+                .collect::<Vec<_>>(),
+            vec![4, 2, 1, 3, 10, 5, 16, 8, 4, 2, 1]
+        );
+
+        let mut zipped = fib_generator(0, 1).zip(collatz_conjecture(6));
+        assert_eq!(
+            zipped.by_ref().collect::<Vec<_>>(),
+            vec![
+                (0, 6),
+                (1, 3),
+                (1, 10),
+                (2, 5),
+                (3, 16),
+                (5, 8),
+                (8, 4),
+                (13, 2),
+                (21, 1)
+            ]
+        );
+        // The fibonacci generator has also been stepped for the final, unpaired value.
+        assert_eq!(zipped.state().0.state(), &(55, 89));
+        assert_eq!(zipped.state().1.state(), &0);
+
+        let nested = Generator::from_iter(vec![
+            Generator::from_iter(vec![1, 2]),
+            Generator::from_iter(vec![]),
+            Generator::from_iter(vec![3]),
+        ]);
+        let mut flat = nested.flatten();
+        assert_eq!(flat.next(), Some(1));
+        assert_eq!(flat.state().1.as_ref().unwrap().state().len(), 1);
+        assert_eq!(flat.collect::<Vec<_>>(), vec![2, 3]);
+
+        let collatzes = Generator::from_iter([1, 2, 3].map(collatz_conjecture));
+        assert_eq!(
+            collatzes.flatten().collect::<Vec<_>>(),
+            vec![1, 2, 1, 3, 10, 5, 16, 8, 4, 2, 1]
+        );
+    }
+
+    #[test]
+    fn test_try_generator() {
+        fn parse_step(st: &mut std::vec::IntoIter<&'static str>) -> Result<Yielded<i32>, String> {
+            match st.next() {
+                Some(s) => s
+                    .parse()
+                    .map(Yielded::Value)
+                    .map_err(|_| format!("invalid number: {s}")),
+                None => Ok(Yielded::Stop),
+            }
+        }
+
+        let numbers: TryGenerator<i32, String, _> =
+            TryGenerator::new(vec!["1", "2", "3"].into_iter(), parse_step);
+        assert_eq!(numbers.collect::<Result<Vec<_>, _>>(), Ok(vec![1, 2, 3]));
+
+        // The generator fuses after the first error.
+        let mut numbers: TryGenerator<i32, String, _> =
+            TryGenerator::new(vec!["1", "x", "3"].into_iter(), parse_step);
+        assert_eq!(numbers.next(), Some(Ok(1)));
+        assert_eq!(numbers.next(), Some(Err("invalid number: x".to_string())));
+        assert_eq!(numbers.next(), None);
+        assert_eq!(numbers.state().len(), 1);
+
+        // Checked collatz that fails instead of overflowing.
+        let mut collatz = TryGenerator::new(usize::MAX / 2, |n: &mut usize| {
+            let current = *n;
+            *n = if current % 2 == 0 {
+                current / 2
+            } else {
+                current
+                    .checked_mul(3)
+                    .and_then(|v| v.checked_add(1))
+                    .ok_or(current)?
+            };
+            Ok(Yielded::Value(current))
+        });
+        assert_eq!(collatz.next(), Some(Err(usize::MAX / 2)));
+        assert_eq!(collatz.next(), None);
+    }
+
+    #[cfg(feature = "stream")]
+    #[test]
+    fn test_generator_stream() {
+        use std::pin::Pin;
+        use std::sync::Arc;
+        use std::task::{Context, Poll, Wake, Waker};
+
+        use futures_core::Stream;
+
+        struct NoopWaker;
+
+        impl Wake for NoopWaker {
+            fn wake(self: Arc<Self>) {}
+        }
+
+        let waker = Waker::from(Arc::new(NoopWaker));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut stream = collatz_conjecture(4).into_stream();
+        let mut values = Vec::new();
+        while let Poll::Ready(Some(value)) = Pin::new(&mut stream).poll_next(&mut cx) {
+            values.push(value);
+        }
+        assert_eq!(values, vec![4, 2, 1]);
+        assert_eq!(Pin::new(&mut stream).poll_next(&mut cx), Poll::Ready(None));
+    }
 }
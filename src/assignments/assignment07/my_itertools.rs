@@ -1,7 +1,11 @@
 //! Implement your own minimal `itertools` crate.
 
-use std::collections::HashSet;
+use std::cell::RefCell;
+use std::collections::{BinaryHeap, HashSet, VecDeque};
+use std::fmt;
 use std::hash::Hash;
+use std::iter::{FusedIterator, Map, Peekable};
+use std::rc::Rc;
 
 /// Iterator that iterates over the given iterator and returns only unique elements.
 #[derive(Debug)]
@@ -23,8 +27,21 @@ where
             .by_ref()
             .find(|item| self.used.insert(item.clone()))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Before any element is seen, the first element of `iter` is always unique.
+        let (lower, upper) = self.iter.size_hint();
+        let lower = if self.used.is_empty() {
+            lower.min(1)
+        } else {
+            0
+        };
+        (lower, upper)
+    }
 }
 
+impl<I: FusedIterator> FusedIterator for Unique<I> where I::Item: Eq + Hash + Clone {}
+
 /// Iterator that chains two iterators together.
 #[derive(Debug)]
 pub struct Chain<I1: Iterator, I2: Iterator> {
@@ -45,6 +62,36 @@ impl<T: Eq + Hash + Clone, I1: Iterator<Item = T>, I2: Iterator<Item = T>> Itera
             self.iter2.next()
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower1, upper1) = self.iter1.size_hint();
+        let (lower2, upper2) = self.iter2.size_hint();
+        let upper = match (upper1, upper2) {
+            (Some(upper1), Some(upper2)) => upper1.checked_add(upper2),
+            _ => None,
+        };
+        (lower1.saturating_add(lower2), upper)
+    }
+}
+
+impl<
+        T: Eq + Hash + Clone,
+        I1: DoubleEndedIterator<Item = T>,
+        I2: DoubleEndedIterator<Item = T>,
+    > DoubleEndedIterator for Chain<I1, I2>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if let Some(x) = self.iter2.next_back() {
+            Some(x)
+        } else {
+            self.iter1.next_back()
+        }
+    }
+}
+
+impl<T: Eq + Hash + Clone, I1: FusedIterator<Item = T>, I2: FusedIterator<Item = T>> FusedIterator
+    for Chain<I1, I2>
+{
 }
 
 /// Iterator that iterates over given iterator and enumerates each element.
@@ -64,8 +111,23 @@ impl<I: Iterator> Iterator for Enumerate<I> {
         self.count += 1;
         Some((i, a))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
 }
 
+impl<I: ExactSizeIterator> ExactSizeIterator for Enumerate<I> {}
+
+impl<I: DoubleEndedIterator + ExactSizeIterator> DoubleEndedIterator for Enumerate<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let a = self.iter.next_back()?;
+        Some((self.count + self.iter.len(), a))
+    }
+}
+
+impl<I: FusedIterator> FusedIterator for Enumerate<I> {}
+
 /// Iterator that zips two iterators together.
 ///
 /// If one iterator is longer than the other one, the remaining elements for the longer element
@@ -86,6 +148,576 @@ impl<I1: Iterator, I2: Iterator> Iterator for Zip<I1, I2> {
 
         Some((a, b))
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower1, upper1) = self.iter1.size_hint();
+        let (lower2, upper2) = self.iter2.size_hint();
+        let upper = match (upper1, upper2) {
+            (Some(upper1), Some(upper2)) => Some(upper1.min(upper2)),
+            (upper1, upper2) => upper1.or(upper2),
+        };
+        (lower1.min(lower2), upper)
+    }
+}
+
+impl<I1: ExactSizeIterator, I2: ExactSizeIterator> ExactSizeIterator for Zip<I1, I2> {}
+
+impl<I1, I2> DoubleEndedIterator for Zip<I1, I2>
+where
+    I1: DoubleEndedIterator + ExactSizeIterator,
+    I2: DoubleEndedIterator + ExactSizeIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // Drop the trailing elements of the longer iterator, which have no pair.
+        let len = self.len();
+        for _ in len..self.iter1.len() {
+            let _unused = self.iter1.next_back();
+        }
+        for _ in len..self.iter2.len() {
+            let _unused = self.iter2.next_back();
+        }
+        Some((self.iter1.next_back()?, self.iter2.next_back()?))
+    }
+}
+
+impl<I1: FusedIterator, I2: FusedIterator> FusedIterator for Zip<I1, I2> {}
+
+/// Iterator that iterates over the overlapping windows of the given size.
+///
+/// If the given iterator has fewer elements than the window size, no window is returned.
+#[derive(Debug)]
+pub struct Windows<I: Iterator> {
+    iter: I,
+    size: usize,
+    window: VecDeque<I::Item>,
+}
+
+impl<I: Iterator> Iterator for Windows<I>
+where
+    I::Item: Clone,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.window.len() == self.size {
+            let _unused = self.window.pop_front();
+        }
+        while self.window.len() < self.size {
+            self.window.push_back(self.iter.next()?);
+        }
+        Some(self.window.iter().cloned().collect())
+    }
+}
+
+/// Iterator that iterates over the non-overlapping chunks of the given size.
+///
+/// The last chunk is shorter than the given size if there are not enough elements left.
+#[derive(Debug)]
+pub struct Chunks<I: Iterator> {
+    iter: I,
+    size: usize,
+}
+
+impl<I: Iterator> Iterator for Chunks<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.iter.by_ref().take(self.size).collect::<Vec<_>>();
+        if chunk.is_empty() {
+            None
+        } else {
+            Some(chunk)
+        }
+    }
+}
+
+/// Iterator that iterates over the elements of the given iterator while the predicate holds.
+///
+/// Once the predicate fails, the iterator returns `None` forever, and the failing element is lost.
+#[derive(Debug)]
+pub struct TakeWhile<I: Iterator, P> {
+    iter: I,
+    predicate: P,
+    done: bool,
+}
+
+impl<I: Iterator, P: FnMut(&I::Item) -> bool> Iterator for TakeWhile<I, P> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.iter.next()?;
+        if (self.predicate)(&item) {
+            Some(item)
+        } else {
+            self.done = true;
+            None
+        }
+    }
+}
+
+impl<I: FusedIterator, P: FnMut(&I::Item) -> bool> FusedIterator for TakeWhile<I, P> {}
+
+/// Iterator that skips the elements of the given iterator while the predicate holds, and then
+/// iterates over the rest.
+///
+/// Once the predicate fails, it is not called anymore.
+#[derive(Debug)]
+pub struct SkipWhile<I: Iterator, P> {
+    iter: I,
+    predicate: P,
+    done: bool,
+}
+
+impl<I: Iterator, P: FnMut(&I::Item) -> bool> Iterator for SkipWhile<I, P> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return self.iter.next();
+        }
+        let predicate = &mut self.predicate;
+        let item = self.iter.find(|item| !predicate(item))?;
+        self.done = true;
+        Some(item)
+    }
+}
+
+impl<I: FusedIterator, P: FnMut(&I::Item) -> bool> FusedIterator for SkipWhile<I, P> {}
+
+/// Iterator that groups the consecutive elements of the given iterator with the same key.
+///
+/// Each group is returned with its key.
+#[derive(Debug)]
+pub struct ChunkBy<I: Iterator, F> {
+    iter: I,
+    key: F,
+    pending: Option<I::Item>,
+}
+
+impl<I: Iterator, K: PartialEq, F: FnMut(&I::Item) -> K> Iterator for ChunkBy<I, F> {
+    type Item = (K, Vec<I::Item>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.pending.take().or_else(|| self.iter.next())?;
+        let key = (self.key)(&first);
+        let mut chunk = vec![first];
+        for item in self.iter.by_ref() {
+            if (self.key)(&item) == key {
+                chunk.push(item);
+            } else {
+                self.pending = Some(item);
+                break;
+            }
+        }
+        Some((key, chunk))
+    }
+}
+
+/// Iterator that removes the consecutive duplicates of the given iterator, using the given closure
+/// to decide whether two elements are duplicates.
+///
+/// Of each run of duplicates, only the first element is returned.
+#[derive(Debug)]
+pub struct DedupBy<I: Iterator, F> {
+    iter: I,
+    same: F,
+    pending: Option<I::Item>,
+}
+
+impl<I: Iterator, F: FnMut(&I::Item, &I::Item) -> bool> Iterator for DedupBy<I, F> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.pending.take().or_else(|| self.iter.next())?;
+        for item in self.iter.by_ref() {
+            if !(self.same)(&current, &item) {
+                self.pending = Some(item);
+                break;
+            }
+        }
+        Some(current)
+    }
+}
+
+/// Iterator that removes the consecutive duplicates of the given iterator.
+pub type Dedup<I> = DedupBy<I, fn(&<I as Iterator>::Item, &<I as Iterator>::Item) -> bool>;
+
+/// Iterator that iterates over the given iterator with the separator between each two elements.
+#[derive(Debug)]
+pub struct Intersperse<I: Iterator> {
+    iter: Peekable<I>,
+    sep: I::Item,
+    sep_next: bool,
+}
+
+impl<I: Iterator> Iterator for Intersperse<I>
+where
+    I::Item: Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.sep_next && self.iter.peek().is_some() {
+            self.sep_next = false;
+            return Some(self.sep.clone());
+        }
+        let item = self.iter.next()?;
+        self.sep_next = true;
+        Some(item)
+    }
+}
+
+/// Iterator that iterates over all pairs of the elements of two iterators.
+///
+/// The second iterator is cloned for each element of the first one. If it is empty, the first one is
+/// not consumed at all, so that the product of an infinite iterator and an empty one is empty.
+#[derive(Debug)]
+pub struct CartesianProduct<I1: Iterator, I2: Iterator> {
+    iter1: I1,
+    current: Option<I1::Item>,
+    orig2: I2,
+    iter2: I2,
+    /// Whether the second iterator is empty, in which case there are no pairs.
+    empty: bool,
+}
+
+impl<I1: Iterator, I2: Iterator + Clone> Iterator for CartesianProduct<I1, I2>
+where
+    I1::Item: Clone,
+{
+    type Item = (I1::Item, I2::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.empty {
+            return None;
+        }
+        loop {
+            if let Some(a) = &self.current {
+                if let Some(b) = self.iter2.next() {
+                    return Some((a.clone(), b));
+                }
+            }
+            self.current = Some(self.iter1.next()?);
+            self.iter2 = self.orig2.clone();
+        }
+    }
+}
+
+/// Iterator that iterates over every `n`-th element of the given iterator, starting from the first
+/// one.
+#[derive(Debug)]
+pub struct StepBy<I: Iterator> {
+    iter: I,
+    skip: usize,
+    first: bool,
+}
+
+impl<I: Iterator> Iterator for StepBy<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.first {
+            self.first = false;
+            self.iter.next()
+        } else {
+            self.iter.nth(self.skip)
+        }
+    }
+}
+
+/// Iterator that iterates over the elements of each element of the given iterator in turn.
+pub struct Flatten<I: Iterator>
+where
+    I::Item: IntoIterator,
+{
+    iter: I,
+    inner: Option<<I::Item as IntoIterator>::IntoIter>,
+}
+
+impl<I: Iterator + fmt::Debug> fmt::Debug for Flatten<I>
+where
+    I::Item: IntoIterator,
+    <I::Item as IntoIterator>::IntoIter: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Flatten")
+            .field("iter", &self.iter)
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<I: Iterator> Iterator for Flatten<I>
+where
+    I::Item: IntoIterator,
+{
+    type Item = <I::Item as IntoIterator>::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.inner.as_mut().and_then(Iterator::next) {
+                return Some(item);
+            }
+            self.inner = Some(self.iter.next()?.into_iter());
+        }
+    }
+}
+
+/// Iterator that maps each element of the given iterator to an iterator and flattens the results.
+pub type FlatMap<I, F> = Flatten<Map<I, F>>;
+
+/// Element of [`ZipLongest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZipItem<A, B> {
+    /// Both iterators have an element.
+    Both(A, B),
+    /// Only the first iterator has an element.
+    Left(A),
+    /// Only the second iterator has an element.
+    Right(B),
+}
+
+/// Iterator that zips two iterators together until both of them are exhausted.
+#[derive(Debug)]
+pub struct ZipLongest<I1: Iterator, I2: Iterator> {
+    iter1: I1,
+    iter2: I2,
+}
+
+impl<I1: Iterator, I2: Iterator> Iterator for ZipLongest<I1, I2> {
+    type Item = ZipItem<I1::Item, I2::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.iter1.next(), self.iter2.next()) {
+            (Some(a), Some(b)) => Some(ZipItem::Both(a, b)),
+            (Some(a), None) => Some(ZipItem::Left(a)),
+            (None, Some(b)) => Some(ZipItem::Right(b)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Result of [`MyIterTools::my_minmax`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinMaxResult<T> {
+    /// The iterator is empty.
+    NoElements,
+    /// The iterator has exactly one element.
+    OneElement(T),
+    /// The minimum and the maximum elements.
+    MinMax(T, T),
+}
+
+/// Iterator that iterates over the `k`-element subsets of the elements of the given iterator.
+///
+/// The subsets are returned in lexicographic order of the positions of their elements. The
+/// elements are collected when the first subset is requested.
+#[derive(Debug)]
+pub struct Combinations<I: Iterator> {
+    iter: I,
+    k: usize,
+    pool: Option<Vec<I::Item>>,
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl<I: Iterator> Iterator for Combinations<I>
+where
+    I::Item: Clone,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let k = self.k;
+        let Some(pool) = &self.pool else {
+            let pool = self.pool.insert(self.iter.by_ref().collect());
+            if k > pool.len() {
+                self.done = true;
+                return None;
+            }
+            self.indices = (0..k).collect();
+            return Some(self.indices.iter().map(|&i| pool[i].clone()).collect());
+        };
+
+        // Find the rightmost index that can be incremented.
+        let n = pool.len();
+        let Some(i) = (0..k).rev().find(|&i| self.indices[i] != i + n - k) else {
+            self.done = true;
+            return None;
+        };
+        self.indices[i] += 1;
+        for j in i + 1..k {
+            self.indices[j] = self.indices[j - 1] + 1;
+        }
+        Some(self.indices.iter().map(|&i| pool[i].clone()).collect())
+    }
+}
+
+/// Iterator that iterates over the `k`-element permutations of the elements of the given iterator.
+///
+/// The permutations are returned in lexicographic order of the positions of their elements. The
+/// elements are collected when the first permutation is requested.
+#[derive(Debug)]
+pub struct Permutations<I: Iterator> {
+    iter: I,
+    k: usize,
+    pool: Option<Vec<I::Item>>,
+    indices: Vec<usize>,
+    cycles: Vec<usize>,
+    done: bool,
+}
+
+impl<I: Iterator> Iterator for Permutations<I>
+where
+    I::Item: Clone,
+{
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let k = self.k;
+        let Some(pool) = &self.pool else {
+            let pool = self.pool.insert(self.iter.by_ref().collect());
+            let n = pool.len();
+            if k > n {
+                self.done = true;
+                return None;
+            }
+            self.indices = (0..n).collect();
+            self.cycles = (n - k + 1..=n).rev().collect();
+            return Some(self.indices[..k].iter().map(|&i| pool[i].clone()).collect());
+        };
+
+        // `cycles[i]` counts the choices left for position `i`; once they run out, the position is
+        // reset and the previous position advances.
+        let n = pool.len();
+        for i in (0..k).rev() {
+            self.cycles[i] -= 1;
+            if self.cycles[i] == 0 {
+                self.indices[i..].rotate_left(1);
+                self.cycles[i] = n - i;
+            } else {
+                self.indices.swap(i, n - self.cycles[i]);
+                return Some(self.indices[..k].iter().map(|&i| pool[i].clone()).collect());
+            }
+        }
+        self.done = true;
+        None
+    }
+}
+
+/// State shared by the two halves of [`MyIterTools::my_tee`].
+#[derive(Debug)]
+struct TeeBuffer<I: Iterator> {
+    iter: I,
+    /// Elements taken from `iter` by the half that is ahead, but not yet by the other half.
+    buffer: VecDeque<I::Item>,
+    /// Whether the elements in `buffer` were taken by the second half.
+    ahead: bool,
+}
+
+/// One of the two iterators returned by [`MyIterTools::my_tee`].
+///
+/// Both halves iterate over the same elements, and can be consumed at different rates. The
+/// elements that only one half has consumed so far are buffered.
+pub struct TeeHalf<I: Iterator> {
+    shared: Rc<RefCell<TeeBuffer<I>>>,
+    second: bool,
+}
+
+impl<I: Iterator> fmt::Debug for TeeHalf<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TeeHalf")
+            .field("second", &self.second)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I: Iterator> Iterator for TeeHalf<I>
+where
+    I::Item: Clone,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut shared = self.shared.borrow_mut();
+        if shared.ahead != self.second {
+            if let Some(item) = shared.buffer.pop_front() {
+                return Some(item);
+            }
+        }
+
+        let item = shared.iter.next()?;
+        shared.buffer.push_back(item.clone());
+        shared.ahead = self.second;
+        Some(item)
+    }
+}
+
+/// Iterator that alternates between the elements of two iterators, starting from the first one.
+///
+/// Once one iterator is exhausted, the remaining elements of the other one are returned.
+#[derive(Debug)]
+pub struct Interleave<I1: Iterator, I2: Iterator> {
+    iter1: I1,
+    iter2: I2,
+    second: bool,
+}
+
+impl<T, I1: Iterator<Item = T>, I2: Iterator<Item = T>> Iterator for Interleave<I1, I2> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.second = !self.second;
+        if self.second {
+            self.iter1.next().or_else(|| self.iter2.next())
+        } else {
+            self.iter2.next().or_else(|| self.iter1.next())
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower1, upper1) = self.iter1.size_hint();
+        let (lower2, upper2) = self.iter2.size_hint();
+        let upper = match (upper1, upper2) {
+            (Some(upper1), Some(upper2)) => upper1.checked_add(upper2),
+            _ => None,
+        };
+        (lower1.saturating_add(lower2), upper)
+    }
+}
+
+/// Iterator that threads a mutable state through the elements of the given iterator.
+///
+/// Once the closure returns `None`, the iterator returns `None` forever.
+#[derive(Debug)]
+pub struct Scan<I, S, F> {
+    iter: I,
+    state: S,
+    f: F,
+    done: bool,
+}
+
+impl<I: Iterator, S, B, F: FnMut(&mut S, I::Item) -> Option<B>> Iterator for Scan<I, S, F> {
+    type Item = B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.iter.next()?;
+        let result = (self.f)(&mut self.state, item);
+        self.done = result.is_none();
+        result
+    }
 }
 
 /// My Itertools trait.
@@ -134,20 +766,389 @@ pub trait MyIterTools: Iterator {
         }
     }
 
+    /// Returns an iterator over the overlapping windows of `size` elements of `self`.
+    ///
+    /// Panics if `size` is 0.
+    fn my_windows(self, size: usize) -> Windows<Self>
+    where
+        Self: Sized,
+    {
+        assert!(size != 0, "window size must be non-zero");
+        Windows {
+            iter: self,
+            size,
+            window: VecDeque::with_capacity(size),
+        }
+    }
+
+    /// Returns an iterator over the non-overlapping chunks of `size` elements of `self`.
+    ///
+    /// Panics if `size` is 0.
+    fn my_chunks(self, size: usize) -> Chunks<Self>
+    where
+        Self: Sized,
+    {
+        assert!(size != 0, "chunk size must be non-zero");
+        Chunks { iter: self, size }
+    }
+
+    /// Returns an iterator over the longest prefix of `self` whose elements satisfy `predicate`.
+    fn my_take_while<P>(self, predicate: P) -> TakeWhile<Self, P>
+    where
+        Self: Sized,
+        P: FnMut(&Self::Item) -> bool,
+    {
+        TakeWhile {
+            iter: self,
+            predicate,
+            done: false,
+        }
+    }
+
+    /// Returns an iterator over `self` without the longest prefix whose elements satisfy
+    /// `predicate`.
+    fn my_skip_while<P>(self, predicate: P) -> SkipWhile<Self, P>
+    where
+        Self: Sized,
+        P: FnMut(&Self::Item) -> bool,
+    {
+        SkipWhile {
+            iter: self,
+            predicate,
+            done: false,
+        }
+    }
+
+    /// Returns an iterator over the groups of consecutive elements of `self` with the same key.
+    fn my_chunk_by<K, F>(self, key: F) -> ChunkBy<Self, F>
+    where
+        Self: Sized,
+        K: PartialEq,
+        F: FnMut(&Self::Item) -> K,
+    {
+        ChunkBy {
+            iter: self,
+            key,
+            pending: None,
+        }
+    }
+
+    /// Returns an iterator over `self` without consecutive duplicates.
+    fn my_dedup(self) -> Dedup<Self>
+    where
+        Self: Sized,
+        Self::Item: PartialEq,
+    {
+        self.my_dedup_by(PartialEq::eq)
+    }
+
+    /// Returns an iterator over `self` without consecutive duplicates, where `same` decides whether
+    /// two elements are duplicates.
+    fn my_dedup_by<F>(self, same: F) -> DedupBy<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&Self::Item, &Self::Item) -> bool,
+    {
+        DedupBy {
+            iter: self,
+            same,
+            pending: None,
+        }
+    }
+
+    /// Returns an iterator over `self` with a clone of `sep` between each two elements.
+    fn my_intersperse(self, sep: Self::Item) -> Intersperse<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        Intersperse {
+            iter: self.peekable(),
+            sep,
+            sep_next: false,
+        }
+    }
+
+    /// Returns an iterator over all pairs of the elements of `self` and `other`, in the order of
+    /// `self`.
+    fn my_cartesian_product<I>(self, other: I) -> CartesianProduct<Self, I>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+        I: Iterator + Clone,
+    {
+        CartesianProduct {
+            iter1: self,
+            current: None,
+            orig2: other.clone(),
+            empty: other.clone().next().is_none(),
+            iter2: other,
+        }
+    }
+
+    /// Returns an iterator over every `n`-th element of `self`, starting from the first one.
+    ///
+    /// Panics if `n` is 0.
+    fn my_step_by(self, n: usize) -> StepBy<Self>
+    where
+        Self: Sized,
+    {
+        assert!(n != 0, "step must be non-zero");
+        StepBy {
+            iter: self,
+            skip: n - 1,
+            first: true,
+        }
+    }
+
+    /// Returns an iterator over the elements of each element of `self` in turn.
+    fn my_flatten(self) -> Flatten<Self>
+    where
+        Self: Sized,
+        Self::Item: IntoIterator,
+    {
+        Flatten {
+            iter: self,
+            inner: None,
+        }
+    }
+
+    /// Returns an iterator that maps each element of `self` to an iterator with `f` and iterates over
+    /// the results in turn.
+    fn my_flat_map<U, F>(self, f: F) -> FlatMap<Self, F>
+    where
+        Self: Sized,
+        U: IntoIterator,
+        F: FnMut(Self::Item) -> U,
+    {
+        self.map(f).my_flatten()
+    }
+
+    /// Returns an iterator that zips `self` and `other` together until both of them are exhausted.
+    fn my_zip_longest<I: Iterator>(self, other: I) -> ZipLongest<Self, I>
+    where
+        Self: Sized,
+    {
+        ZipLongest {
+            iter1: self,
+            iter2: other,
+        }
+    }
+
+    /// Returns an iterator over the elements of `self` in ascending order.
+    ///
+    /// All the elements are collected and sorted before the first one is returned.
+    fn my_sorted(self) -> std::vec::IntoIter<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Ord,
+    {
+        let mut v = self.collect::<Vec<_>>();
+        v.sort();
+        v.into_iter()
+    }
+
+    /// Returns an iterator over the elements of `self` in ascending order of the keys given by `f`.
+    ///
+    /// The sort is stable. All the elements are collected and sorted before the first one is
+    /// returned.
+    fn my_sorted_by_key<K, F>(self, f: F) -> std::vec::IntoIter<Self::Item>
+    where
+        Self: Sized,
+        K: Ord,
+        F: FnMut(&Self::Item) -> K,
+    {
+        let mut v = self.collect::<Vec<_>>();
+        v.sort_by_key(f);
+        v.into_iter()
+    }
+
+    /// Returns an iterator over the `k` smallest elements of `self` in ascending order.
+    ///
+    /// Only `k` elements are kept in a max-heap at a time, instead of sorting all of them.
+    fn my_k_smallest(self, k: usize) -> std::vec::IntoIter<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Ord,
+    {
+        if k == 0 {
+            return Vec::new().into_iter();
+        }
+
+        let mut heap = BinaryHeap::with_capacity(k);
+        for item in self {
+            if heap.len() < k {
+                heap.push(item);
+            } else if let Some(mut largest) = heap.peek_mut() {
+                if item < *largest {
+                    *largest = item;
+                }
+            }
+        }
+        heap.into_sorted_vec().into_iter()
+    }
+
+    /// Returns the minimum and the maximum elements of `self` in a single pass.
+    ///
+    /// If there are several equal minimum elements, the first one is returned; if there are several
+    /// equal maximum elements, the last one is returned.
+    fn my_minmax(mut self) -> MinMaxResult<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Ord,
+    {
+        let Some(first) = self.next() else {
+            return MinMaxResult::NoElements;
+        };
+        let Some(second) = self.next() else {
+            return MinMaxResult::OneElement(first);
+        };
+
+        let (mut min, mut max) = if second < first {
+            (second, first)
+        } else {
+            (first, second)
+        };
+        for item in self {
+            if item < min {
+                min = item;
+            } else if item >= max {
+                max = item;
+            }
+        }
+        MinMaxResult::MinMax(min, max)
+    }
+
+    /// Returns an iterator over the `k`-element subsets of the elements of `self`.
+    fn my_combinations(self, k: usize) -> Combinations<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        Combinations {
+            iter: self,
+            k,
+            pool: None,
+            indices: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Returns an iterator over the `k`-element permutations of the elements of `self`.
+    fn my_permutations(self, k: usize) -> Permutations<Self>
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        Permutations {
+            iter: self,
+            k,
+            pool: None,
+            indices: Vec::new(),
+            cycles: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Returns two iterators over the elements of `self`, which can be consumed at different
+    /// rates.
+    fn my_tee(self) -> (TeeHalf<Self>, TeeHalf<Self>)
+    where
+        Self: Sized,
+        Self::Item: Clone,
+    {
+        let shared = Rc::new(RefCell::new(TeeBuffer {
+            iter: self,
+            buffer: VecDeque::new(),
+            ahead: false,
+        }));
+        (
+            TeeHalf {
+                shared: Rc::clone(&shared),
+                second: false,
+            },
+            TeeHalf {
+                shared,
+                second: true,
+            },
+        )
+    }
+
+    /// Returns an iterator that alternates between the elements of `self` and `other`.
+    fn my_interleave<I>(self, other: I) -> Interleave<Self, I>
+    where
+        Self: Sized,
+        I: Iterator<Item = Self::Item>,
+    {
+        Interleave {
+            iter1: self,
+            iter2: other,
+            second: false,
+        }
+    }
+
     /// Foldleft for `MyIterTools`
-    fn my_fold<T, F>(mut self, init: T, mut f: F) -> T
+    ///
+    /// `f` takes the accumulator first and the element second, like [`Iterator::fold`].
+    fn my_fold<T, F>(self, init: T, mut f: F) -> T
     where
         Self: Sized,
-        F: FnMut(Self::Item, T) -> T,
+        F: FnMut(T, Self::Item) -> T,
     {
         let mut acc = init;
 
         for item in self {
-            acc = f(item, acc);
+            acc = f(acc, item);
         }
 
         acc
     }
+
+    /// Foldleft for `MyIterTools`, where `f` takes the element first and the accumulator second.
+    #[deprecated(note = "use `my_fold`, whose closure takes the accumulator first")]
+    fn my_fold_item_first<T, F>(self, init: T, mut f: F) -> T
+    where
+        Self: Sized,
+        F: FnMut(Self::Item, T) -> T,
+    {
+        self.my_fold(init, |acc, item| f(item, acc))
+    }
+
+    /// Folds `self` with its first element as the initial accumulator.
+    ///
+    /// Returns `None` if `self` is empty.
+    fn my_reduce<F>(mut self, f: F) -> Option<Self::Item>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item, Self::Item) -> Self::Item,
+    {
+        let first = self.next()?;
+        Some(self.my_fold(first, f))
+    }
+
+    /// Same as [`MyIterTools::my_reduce`].
+    fn my_fold1<F>(self, f: F) -> Option<Self::Item>
+    where
+        Self: Sized,
+        F: FnMut(Self::Item, Self::Item) -> Self::Item,
+    {
+        self.my_reduce(f)
+    }
+
+    /// Returns an iterator that passes a mutable state and each element of `self` to `f`, and
+    /// returns the results of `f` until it returns `None`.
+    fn my_scan<S, B, F>(self, state: S, f: F) -> Scan<Self, S, F>
+    where
+        Self: Sized,
+        F: FnMut(&mut S, Self::Item) -> Option<B>,
+    {
+        Scan {
+            iter: self,
+            state,
+            f,
+            done: false,
+        }
+    }
 }
 
 impl<T: ?Sized> MyIterTools for T where T: Iterator {}
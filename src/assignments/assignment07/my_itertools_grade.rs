@@ -58,8 +58,690 @@ mod test {
         );
 
         assert_eq!(
-            it().take(15).my_fold(0, |elt, acc| elt + acc),
-            take15.iter().sum()
+            it().take(15).my_fold(0, |acc, elt| acc + elt),
+            take15.iter().sum::<i32>()
         );
     }
+
+    #[test]
+    fn test_windows() {
+        assert_eq!(
+            (1..=5).my_windows(2).collect::<Vec<_>>(),
+            vec![vec![1, 2], vec![2, 3], vec![3, 4], vec![4, 5]]
+        );
+        assert_eq!(
+            (1..=5).my_windows(5).collect::<Vec<_>>(),
+            vec![vec![1, 2, 3, 4, 5]]
+        );
+        assert_eq!((1..=5).my_windows(6).count(), 0);
+        assert_eq!(
+            (1..=5)
+                .my_windows(2)
+                .map(|w| w[1] - w[0])
+                .collect::<Vec<_>>(),
+            vec![1, 1, 1, 1]
+        );
+        assert_eq!(
+            "abc"
+                .chars()
+                .my_windows(1)
+                .map(String::from_iter)
+                .collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(
+            (1..=5).my_windows(2).collect::<Vec<_>>(),
+            (1..=5).collect::<Vec<_>>().windows(2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_chunks() {
+        assert_eq!(
+            (1..=7).my_chunks(3).collect::<Vec<_>>(),
+            vec![vec![1, 2, 3], vec![4, 5, 6], vec![7]]
+        );
+        assert_eq!(
+            (1..=6).my_chunks(3).collect::<Vec<_>>(),
+            vec![vec![1, 2, 3], vec![4, 5, 6]]
+        );
+        assert_eq!((1..1).my_chunks(3).count(), 0);
+        assert_eq!(
+            std::iter::repeat(1)
+                .my_chunks(4)
+                .map(|c| c.into_iter().sum::<i32>())
+                .take(3)
+                .collect::<Vec<_>>(),
+            vec![4, 4, 4]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_windows_zero_size() {
+        let _unused = (1..=5).my_windows(0);
+    }
+
+    #[test]
+    fn test_take_while() {
+        assert_eq!(
+            [1, 2, 3, 10, 1, 2]
+                .into_iter()
+                .my_take_while(|x| *x < 5)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!((1..).my_take_while(|x| x * x < 50).count(), 7);
+        assert_eq!((1..5).my_take_while(|_| false).count(), 0);
+
+        // The iterator stays exhausted even if later elements satisfy the predicate.
+        let mut it = [1, 10, 1].into_iter().my_take_while(|x| *x < 5);
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next(), None);
+
+        let v = [1, 2, 3, 10, 1, 2, 20];
+        assert_eq!(
+            v.iter().my_take_while(|x| **x < 5).collect::<Vec<_>>(),
+            v.iter().take_while(|x| **x < 5).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_skip_while() {
+        assert_eq!(
+            [1, 2, 3, 10, 1, 2]
+                .into_iter()
+                .my_skip_while(|x| *x < 5)
+                .collect::<Vec<_>>(),
+            vec![10, 1, 2]
+        );
+        assert_eq!((1..5).my_skip_while(|_| true).count(), 0);
+        assert_eq!((1..5).my_skip_while(|_| false).count(), 4);
+
+        // The predicate is not called after it fails.
+        let mut calls = 0;
+        assert_eq!(
+            (1..10)
+                .my_skip_while(|x| {
+                    calls += 1;
+                    *x < 3
+                })
+                .collect::<Vec<_>>(),
+            (3..10).collect::<Vec<_>>()
+        );
+        assert_eq!(calls, 3);
+
+        let v = [1, 2, 3, 10, 1, 2, 20];
+        assert_eq!(
+            v.iter().my_skip_while(|x| **x < 5).collect::<Vec<_>>(),
+            v.iter().skip_while(|x| **x < 5).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_chunk_by() {
+        assert_eq!(
+            [1, 3, 2, 4, 6, 5, 7]
+                .into_iter()
+                .my_chunk_by(|x| x % 2)
+                .collect::<Vec<_>>(),
+            vec![(1, vec![1, 3]), (0, vec![2, 4, 6]), (1, vec![5, 7])]
+        );
+        assert_eq!((1..1).my_chunk_by(|x| *x).count(), 0);
+
+        let words = [
+            "apple",
+            "avocado",
+            "banana",
+            "blueberry",
+            "cherry",
+            "apricot",
+        ];
+        assert_eq!(
+            words
+                .iter()
+                .my_chunk_by(|w| w.chars().next())
+                .map(|(c, ws)| (c.unwrap(), ws.len()))
+                .collect::<Vec<_>>(),
+            vec![('a', 2), ('b', 2), ('c', 1), ('a', 1)]
+        );
+
+        let v = [1, 1, 2, 3, 3, 3, 1];
+        assert_eq!(
+            v.iter().my_chunk_by(|x| **x).collect::<Vec<_>>(),
+            v.iter()
+                .chunk_by(|x| **x)
+                .into_iter()
+                .map(|(k, g)| (k, g.collect::<Vec<_>>()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_dedup() {
+        assert_eq!(
+            [1, 1, 2, 3, 3, 3, 1, 2, 2]
+                .into_iter()
+                .my_dedup()
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3, 1, 2]
+        );
+        assert_eq!((1..1).my_dedup().count(), 0);
+        assert_eq!("aaabbbcca".chars().my_dedup().collect::<String>(), "abca");
+
+        let v = [1, 1, 2, 3, 3, 1];
+        assert_eq!(
+            v.iter().my_dedup().collect::<Vec<_>>(),
+            v.iter().dedup().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_dedup_by() {
+        assert_eq!(
+            [1, 3, 5, 2, 4, 7, 9, 6]
+                .into_iter()
+                .my_dedup_by(|a, b| a % 2 == b % 2)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 7, 6]
+        );
+        assert_eq!(
+            ["Apple", "apple", "APPLE", "pear", "Pear"]
+                .into_iter()
+                .my_dedup_by(|a, b| a.eq_ignore_ascii_case(b))
+                .collect::<Vec<_>>(),
+            vec!["Apple", "pear"]
+        );
+    }
+
+    #[test]
+    fn test_intersperse() {
+        assert_eq!(
+            [1, 2, 3].into_iter().my_intersperse(0).collect::<Vec<_>>(),
+            vec![1, 0, 2, 0, 3]
+        );
+        assert_eq!(
+            [1].into_iter().my_intersperse(0).collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert_eq!((1..1).my_intersperse(0).count(), 0);
+        assert_eq!(
+            ["a", "b", "c"]
+                .into_iter()
+                .my_intersperse(", ")
+                .collect::<String>(),
+            "a, b, c"
+        );
+        assert_eq!(
+            (1..)
+                .map(|x| x.to_string())
+                .my_intersperse("-".to_string())
+                .take(5)
+                .collect::<String>(),
+            "1-2-3"
+        );
+    }
+
+    #[test]
+    fn test_cartesian_product() {
+        assert_eq!(
+            (1..=2)
+                .my_cartesian_product("ab".chars())
+                .collect::<Vec<_>>(),
+            vec![(1, 'a'), (1, 'b'), (2, 'a'), (2, 'b')]
+        );
+        assert_eq!((1..=3).my_cartesian_product(1..1).count(), 0);
+        assert_eq!((1..1).my_cartesian_product(1..=3).count(), 0);
+        assert_eq!(
+            (0..).my_cartesian_product(std::iter::empty::<u8>()).next(),
+            None
+        );
+        let mut consumed = 0..;
+        assert_eq!(consumed.by_ref().my_cartesian_product(1..1).count(), 0);
+        assert_eq!(consumed.next(), Some(0));
+        assert_eq!(
+            (0..).my_cartesian_product(0..3).take(4).collect::<Vec<_>>(),
+            vec![(0, 0), (0, 1), (0, 2), (1, 0)]
+        );
+        assert_eq!(
+            (1..=4)
+                .my_cartesian_product(1..=4)
+                .filter(|(x, y)| x + y == 5)
+                .count(),
+            4
+        );
+        assert_eq!(
+            (1..=3).my_cartesian_product(4..=5).collect::<Vec<_>>(),
+            (1..=3).cartesian_product(4..=5).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_step_by() {
+        assert_eq!((0..10).my_step_by(3).collect::<Vec<_>>(), vec![0, 3, 6, 9]);
+        assert_eq!(
+            (0..10).my_step_by(1).collect::<Vec<_>>(),
+            (0..10).collect::<Vec<_>>()
+        );
+        assert_eq!((0..10).my_step_by(20).collect::<Vec<_>>(), vec![0]);
+        assert_eq!((0..0).my_step_by(2).count(), 0);
+        assert_eq!(
+            (1..).my_step_by(5).take(3).collect::<Vec<_>>(),
+            vec![1, 6, 11]
+        );
+        assert_eq!(
+            "abcdefg".chars().my_step_by(2).collect::<String>(),
+            "abcdefg".chars().step_by(2).collect::<String>()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_step_by_zero() {
+        let _unused = (0..10).my_step_by(0);
+    }
+
+    #[test]
+    fn test_flatten() {
+        assert_eq!(
+            vec![vec![1, 2], vec![], vec![3], vec![], vec![]]
+                .into_iter()
+                .my_flatten()
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(Vec::<Vec<i32>>::new().into_iter().my_flatten().count(), 0);
+        assert_eq!(
+            [Some(1), None, Some(3)]
+                .into_iter()
+                .my_flatten()
+                .collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(
+            (0..).map(|x| 0..x).my_flatten().take(6).collect::<Vec<_>>(),
+            vec![0, 0, 1, 0, 1, 2]
+        );
+        assert_eq!([[1, 2], [3, 4]].iter().my_flatten().sum::<i32>(), 10);
+    }
+
+    #[test]
+    fn test_flat_map() {
+        assert_eq!(
+            (1..=3).my_flat_map(|x| 0..x).collect::<Vec<_>>(),
+            vec![0, 0, 1, 0, 1, 2]
+        );
+        assert_eq!(
+            ["ab", "", "cd"]
+                .into_iter()
+                .my_flat_map(str::chars)
+                .collect::<String>(),
+            "abcd"
+        );
+
+        let words = ["alpha", "beta"];
+        assert_eq!(
+            words.iter().my_flat_map(|w| w.chars()).collect::<Vec<_>>(),
+            words.iter().flat_map(|w| w.chars()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_zip_longest() {
+        assert_eq!(
+            (1..=3).my_zip_longest("ab".chars()).collect::<Vec<_>>(),
+            vec![
+                ZipItem::Both(1, 'a'),
+                ZipItem::Both(2, 'b'),
+                ZipItem::Left(3)
+            ]
+        );
+        assert_eq!(
+            (1..=1).my_zip_longest("abc".chars()).collect::<Vec<_>>(),
+            vec![
+                ZipItem::Both(1, 'a'),
+                ZipItem::Right('b'),
+                ZipItem::Right('c')
+            ]
+        );
+        assert_eq!((1..1).my_zip_longest(1..1).count(), 0);
+        assert_eq!(
+            [1, 2, 3]
+                .into_iter()
+                .my_zip_longest([10, 20].into_iter())
+                .map(|item| match item {
+                    ZipItem::Both(a, b) => a + b,
+                    ZipItem::Left(a) => a,
+                    ZipItem::Right(b) => b,
+                })
+                .collect::<Vec<_>>(),
+            vec![11, 22, 3]
+        );
+    }
+
+    #[test]
+    fn test_sorted() {
+        assert_eq!(
+            [3, 1, 4, 1, 5, 9, 2, 6]
+                .into_iter()
+                .my_sorted()
+                .collect::<Vec<_>>(),
+            vec![1, 1, 2, 3, 4, 5, 6, 9]
+        );
+        assert_eq!((1..1).my_sorted().count(), 0);
+
+        assert_eq!(
+            ["ccc", "a", "bb", "d"]
+                .into_iter()
+                .my_sorted_by_key(|s| s.len())
+                .collect::<Vec<_>>(),
+            vec!["a", "d", "bb", "ccc"]
+        );
+        assert_eq!(
+            (1..=6).my_sorted_by_key(|x| x % 3).collect::<Vec<_>>(),
+            vec![3, 6, 1, 4, 2, 5]
+        );
+    }
+
+    #[test]
+    fn test_k_smallest() {
+        let v = [3, 1, 4, 1, 5, 9, 2, 6];
+        assert_eq!(
+            v.into_iter().my_k_smallest(3).collect::<Vec<_>>(),
+            vec![1, 1, 2]
+        );
+        assert_eq!(v.into_iter().my_k_smallest(0).count(), 0);
+        assert_eq!(
+            v.into_iter().my_k_smallest(100).collect::<Vec<_>>(),
+            v.into_iter().my_sorted().collect::<Vec<_>>()
+        );
+        for k in 0..=v.len() {
+            assert_eq!(
+                v.into_iter().my_k_smallest(k).collect::<Vec<_>>(),
+                v.into_iter().k_smallest(k).collect::<Vec<_>>()
+            );
+        }
+        assert_eq!(
+            (0..1_000_000).rev().my_k_smallest(2).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+    }
+
+    #[test]
+    fn test_minmax() {
+        assert_eq!((1..1).my_minmax(), MinMaxResult::NoElements);
+        assert_eq!([7].into_iter().my_minmax(), MinMaxResult::OneElement(7));
+        assert_eq!(
+            [3, 1, 4, 1, 5, 9, 2, 6].into_iter().my_minmax(),
+            MinMaxResult::MinMax(1, 9)
+        );
+        assert_eq!([2, 2].into_iter().my_minmax(), MinMaxResult::MinMax(2, 2));
+
+        // The first minimum and the last maximum are returned.
+        let v = [(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd'), (1, 'e')];
+        let chars = |result| match result {
+            MinMaxResult::MinMax(Keyed(_, min), Keyed(_, max)) => Some((min, max)),
+            _ => None,
+        };
+        assert_eq!(
+            chars(v.iter().map(|&(k, c)| Keyed(k, c)).my_minmax()),
+            Some(('b', 'e'))
+        );
+        assert_eq!(
+            chars(v.iter().map(|&(k, c)| Keyed(k, c)).my_minmax()),
+            match v.iter().map(|&(k, c)| Keyed(k, c)).minmax() {
+                itertools::MinMaxResult::MinMax(Keyed(_, min), Keyed(_, max)) => Some((min, max)),
+                _ => None,
+            }
+        );
+    }
+
+    /// Value compared only by its key.
+    #[derive(Debug, Clone, Copy)]
+    struct Keyed(i32, char);
+
+    impl PartialEq for Keyed {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl Eq for Keyed {}
+
+    impl PartialOrd for Keyed {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Keyed {
+        fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+
+    #[test]
+    fn test_combinations() {
+        assert_eq!(
+            (1..=4).my_combinations(2).collect::<Vec<_>>(),
+            vec![
+                vec![1, 2],
+                vec![1, 3],
+                vec![1, 4],
+                vec![2, 3],
+                vec![2, 4],
+                vec![3, 4]
+            ]
+        );
+        assert_eq!(
+            (1..=3).my_combinations(0).collect::<Vec<_>>(),
+            vec![Vec::<i32>::new()]
+        );
+        assert_eq!((1..=3).my_combinations(4).count(), 0);
+        assert_eq!((1..=3).my_combinations(3).count(), 1);
+        assert_eq!((1..=10).my_combinations(4).count(), 210);
+
+        for k in 0..=5 {
+            assert_eq!(
+                (1..=5).my_combinations(k).collect::<Vec<_>>(),
+                (1..=5).combinations(k).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_permutations() {
+        assert_eq!(
+            (1..=3).my_permutations(2).collect::<Vec<_>>(),
+            vec![
+                vec![1, 2],
+                vec![1, 3],
+                vec![2, 1],
+                vec![2, 3],
+                vec![3, 1],
+                vec![3, 2]
+            ]
+        );
+        assert_eq!(
+            (1..=3).my_permutations(0).collect::<Vec<_>>(),
+            vec![Vec::<i32>::new()]
+        );
+        assert_eq!((1..=3).my_permutations(4).count(), 0);
+        assert_eq!((1..=5).my_permutations(5).count(), 120);
+
+        for k in 0..=4 {
+            assert_eq!(
+                (1..=4).my_permutations(k).collect::<Vec<_>>(),
+                (1..=4).permutations(k).collect::<Vec<_>>()
+            );
+        }
+
+        let perms = "abc"
+            .chars()
+            .my_permutations(3)
+            .map(String::from_iter)
+            .collect::<Vec<_>>();
+        assert!(perms.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_size_hint() {
+        let v = [1, 2, 2, 3];
+
+        let it = v.iter().my_unique();
+        assert_eq!(it.size_hint(), (1, Some(4)));
+        let mut it = v.iter().my_unique();
+        let _unused = it.next();
+        assert_eq!(it.size_hint(), (0, Some(3)));
+        assert_eq!((1..1).my_unique().size_hint(), (0, Some(0)));
+
+        let it = v.iter().my_chain(v.iter());
+        assert_eq!(it.size_hint(), (8, Some(8)));
+        assert_eq!((0..3).my_chain(0..).size_hint(), (usize::MAX, None));
+        assert_eq!(
+            (0..3).my_chain(0..4).rev().collect::<Vec<_>>(),
+            vec![3, 2, 1, 0, 2, 1, 0]
+        );
+
+        let mut it = v.iter().my_enumerate();
+        assert_eq!(it.len(), 4);
+        assert_eq!(it.next(), Some((0, &1)));
+        assert_eq!(it.next_back(), Some((3, &3)));
+        assert_eq!(it.len(), 2);
+        assert_eq!(it.collect::<Vec<_>>(), vec![(1, &2), (2, &2)]);
+
+        let it = v.iter().my_zip(0..10);
+        assert_eq!(it.len(), 4);
+        assert_eq!((0..).my_zip(0..3).size_hint(), (3, Some(3)));
+        assert_eq!(
+            v.iter().my_zip(0..10).rev().collect::<Vec<_>>(),
+            vec![(&3, 3), (&2, 2), (&2, 1), (&1, 0)]
+        );
+        assert_eq!(
+            (0..10).my_zip(v.iter()).rev().collect::<Vec<_>>(),
+            (0..10).zip(v.iter()).rev().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_tee() {
+        let (a, b) = (1..=5).my_tee();
+        assert_eq!(a.collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(b.collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+
+        // The halves can be consumed alternately and at different rates.
+        let (mut a, mut b) = (1..=5).my_tee();
+        assert_eq!(a.next(), Some(1));
+        assert_eq!(a.next(), Some(2));
+        assert_eq!(b.next(), Some(1));
+        assert_eq!(b.next(), Some(2));
+        assert_eq!(b.next(), Some(3));
+        assert_eq!(a.next(), Some(3));
+        assert_eq!(b.next(), Some(4));
+        assert_eq!(a.collect::<Vec<_>>(), vec![4, 5]);
+        assert_eq!(b.collect::<Vec<_>>(), vec![5]);
+
+        // Pairwise differences of the same stream.
+        let (a, b) = [1, 4, 9, 16].into_iter().my_tee();
+        assert_eq!(
+            a.my_zip(b.skip(1)).map(|(x, y)| y - x).collect::<Vec<_>>(),
+            vec![3, 5, 7]
+        );
+
+        let (a, b) = (0..).my_tee();
+        assert_eq!(
+            a.step_by(2).my_zip(b).take(3).collect::<Vec<_>>(),
+            vec![(0, 0), (2, 1), (4, 2)]
+        );
+    }
+
+    #[test]
+    fn test_interleave() {
+        assert_eq!(
+            (1..=3).my_interleave(4..=6).collect::<Vec<_>>(),
+            vec![1, 4, 2, 5, 3, 6]
+        );
+        assert_eq!(
+            (1..=5).my_interleave(10..=11).collect::<Vec<_>>(),
+            vec![1, 10, 2, 11, 3, 4, 5]
+        );
+        assert_eq!(
+            (1..=1).my_interleave(10..=13).collect::<Vec<_>>(),
+            vec![1, 10, 11, 12, 13]
+        );
+        assert_eq!((1..1).my_interleave(1..1).count(), 0);
+        assert_eq!((1..=5).my_interleave(10..=11).size_hint(), (7, Some(7)));
+        assert_eq!(
+            (0..)
+                .step_by(2)
+                .my_interleave((1..).step_by(2))
+                .take(6)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            (1..=5).my_interleave(10..=11).collect::<Vec<_>>(),
+            (1..=5).interleave(10..=11).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_fold() {
+        assert_eq!(
+            (1..=4).my_fold(String::new(), |acc, x| acc + &x.to_string()),
+            "1234"
+        );
+        assert_eq!((1..=4).my_fold(0, |acc, x| acc * 10 - x), -1234);
+        assert_eq!((1..1).my_fold(7, |acc, x| acc + x), 7);
+
+        #[allow(deprecated)]
+        let s = (1..=4).my_fold_item_first(String::new(), |x, acc| acc + &x.to_string());
+        assert_eq!(s, "1234");
+    }
+
+    #[test]
+    fn test_reduce() {
+        assert_eq!((1..=4).my_reduce(|acc, x| acc * x), Some(24));
+        assert_eq!((1..1).my_reduce(|acc, x| acc + x), None);
+        assert_eq!([5].into_iter().my_reduce(|acc, x| acc - x), Some(5));
+        assert_eq!((1..=4).my_reduce(|acc, x| acc - x), Some(-8));
+        assert_eq!((1..=4).my_fold1(i32::max), Some(4));
+        assert_eq!(
+            (1..=4).my_fold1(|acc, x| acc - x),
+            (1..=4).reduce(|acc, x| acc - x)
+        );
+    }
+
+    #[test]
+    fn test_scan() {
+        assert_eq!(
+            (1..=5)
+                .my_scan(0, |sum, x| {
+                    *sum += x;
+                    Some(*sum)
+                })
+                .collect::<Vec<_>>(),
+            vec![1, 3, 6, 10, 15]
+        );
+
+        // The iterator stops at the first `None`.
+        let mut it = [1, 2, -1, 3].into_iter().my_scan(1, |product, x| {
+            *product *= x;
+            (*product > 0).then_some(*product)
+        });
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(it.next(), Some(2));
+        assert_eq!(it.next(), None);
+        assert_eq!(it.next(), None);
+
+        let fib = (0..10)
+            .my_scan((0, 1), |(a, b), _| {
+                let next = *a;
+                (*a, *b) = (*b, *a + *b);
+                Some(next)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(fib, vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+    }
 }
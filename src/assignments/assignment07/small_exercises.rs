@@ -1,9 +1,68 @@
 //! Implement functions using `Iterator` trait
 
+use std::fmt;
+
+use itertools::Itertools;
+
+// Knuth-Morris-Pratt search for the query in the base
 struct FindIter<'s, T: Eq> {
     query: &'s [T],
     base: &'s [T],
+    // Whether matches may overlap
+    overlapping: bool,
+    // Whether to scan from the end, i.e. search for the reversed query in the reversed base
+    reverse: bool,
+    // `failure[i]` is the length of the longest proper prefix of `query[..=i]` that is also its
+    // suffix (in scan order).
+    failure: Vec<usize>,
+    // Number of scanned elements of `base`, or of yielded positions for an empty query
     curr: usize,
+    // Length of the prefix of `query` that ends at `curr`
+    matched: usize,
+}
+
+impl<'s, T: Eq> FindIter<'s, T> {
+    fn new(query: &'s [T], base: &'s [T], overlapping: bool, reverse: bool) -> Self {
+        let mut iter = FindIter {
+            query,
+            base,
+            overlapping,
+            reverse,
+            failure: vec![0; query.len()],
+            curr: 0,
+            matched: 0,
+        };
+
+        let mut len = 0;
+        for i in 1..query.len() {
+            while len > 0 && iter.query_at(i) != iter.query_at(len) {
+                len = iter.failure[len - 1];
+            }
+            if iter.query_at(i) == iter.query_at(len) {
+                len += 1;
+            }
+            iter.failure[i] = len;
+        }
+        iter
+    }
+
+    // The `i`-th element of the query in scan order
+    fn query_at(&self, i: usize) -> &'s T {
+        if self.reverse {
+            &self.query[self.query.len() - 1 - i]
+        } else {
+            &self.query[i]
+        }
+    }
+
+    // The `i`-th element of the base in scan order
+    fn base_at(&self, i: usize) -> &'s T {
+        if self.reverse {
+            &self.base[self.base.len() - 1 - i]
+        } else {
+            &self.base[i]
+        }
+    }
 }
 
 impl<T: Eq> Iterator for FindIter<'_, T> {
@@ -18,36 +77,100 @@ impl<T: Eq> Iterator for FindIter<'_, T> {
             if self.curr <= n {
                 let i = self.curr;
                 self.curr += 1;
-                return Some(i);
+                return Some(if self.reverse { n - i } else { i });
             }
             return None;
         }
 
-        let mut i = self.curr;
-        // Only positions where a full match can start
-        while i + m <= n {
-            if self.base[i..].starts_with(self.query) {
-                let pos = i;
-                // Overlapping behavior: slide by 1. For non-overlapping, use i + m.
-                self.curr = i + 1;
-                return Some(pos);
+        while self.curr < n {
+            let item = self.base_at(self.curr);
+            while self.matched > 0 && self.query_at(self.matched) != item {
+                self.matched = self.failure[self.matched - 1];
+            }
+            if self.query_at(self.matched) == item {
+                self.matched += 1;
+            }
+            self.curr += 1;
+
+            if self.matched == m {
+                // Overlapping matches keep the longest proper suffix that is a prefix.
+                self.matched = if self.overlapping {
+                    self.failure[m - 1]
+                } else {
+                    0
+                };
+                return Some(if self.reverse {
+                    n - self.curr
+                } else {
+                    self.curr - m
+                });
             }
-            i += 1;
         }
 
-        // Exhausted: make subsequent calls cheap
-        self.curr = n;
         None
     }
 }
 
 /// Returns an iterator over substring query indexes in the base.
+///
+/// Matches may overlap, and an empty query matches at every index from `0` to `base.len()`. It
+/// takes `O(query.len() + base.len())` time with the Knuth-Morris-Pratt algorithm.
 pub fn find<'s, T: Eq>(query: &'s [T], base: &'s [T]) -> impl 's + Iterator<Item = usize> {
-    FindIter {
-        query,
-        base,
-        curr: 0,
-    }
+    FindIter::new(query, base, true, false)
+}
+
+/// Like [`find`], but a match starts after the end of the previous match.
+///
+/// For instance, `aa` in `aaaaa` matches at `0` and `2`, but not at `1` or `3`.
+pub fn find_non_overlapping<'s, T: Eq>(
+    query: &'s [T],
+    base: &'s [T],
+) -> impl 's + Iterator<Item = usize> {
+    FindIter::new(query, base, false, false)
+}
+
+/// Like [`find`], but yields the indexes from the end of the base.
+pub fn rfind<'s, T: Eq>(query: &'s [T], base: &'s [T]) -> impl 's + Iterator<Item = usize> {
+    FindIter::new(query, base, true, true)
+}
+
+/// Like [`find`], but for strings. Returns the byte offsets of the matches, which are always on
+/// char boundaries.
+///
+/// An empty query matches at every char boundary.
+pub fn find_str<'s>(query: &'s str, base: &'s str) -> impl 's + Iterator<Item = usize> {
+    // A match of a non-empty query always starts at a char boundary, as UTF-8 is self-synchronizing.
+    find(query.as_bytes(), base.as_bytes()).filter(move |&i| base.is_char_boundary(i))
+}
+
+/// Returns an iterator over the substrings of the base separated by the non-overlapping matches of
+/// the query, like [`str::split`].
+///
+/// For instance, `split_on(", ", "a, b, c")` yields `"a"`, `"b"` and `"c"`.
+pub fn split_on<'s>(query: &'s str, base: &'s str) -> impl 's + Iterator<Item = &'s str> {
+    let mut matches = find_non_overlapping(query.as_bytes(), base.as_bytes())
+        .filter(move |&i| base.is_char_boundary(i));
+    // Start of the next substring, or `None` after the last one
+    let mut start = Some(0);
+    std::iter::from_fn(move || {
+        let begin = start?;
+        match matches.next() {
+            Some(i) => {
+                start = Some(i + query.len());
+                Some(&base[begin..i])
+            }
+            None => {
+                start = None;
+                Some(&base[begin..])
+            }
+        }
+    })
+}
+
+/// Like [`find`], but checks every index of the base, which takes `O(query.len() * base.len())`
+/// time in the worst case.
+pub fn find_naive<'s, T: Eq>(query: &'s [T], base: &'s [T]) -> impl 's + Iterator<Item = usize> {
+    (0..=base.len()).filter(move |&i| base[i..].starts_with(query))
 }
 
 /// Implement generic fibonacci iterator
@@ -93,52 +216,88 @@ where
     FibIter::new(first, second)
 }
 
-/// Endpoint of range, inclusive or exclusive.
-#[derive(Debug)]
+/// Endpoint of range, inclusive, exclusive or unbounded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Endpoint {
     /// Inclusive endpoint
     Inclusive(isize),
 
     /// Exclusive endpoint
     Exclusive(isize),
+
+    /// No endpoint, which is only allowed at the right
+    Unbounded,
+}
+
+/// Error for invalid ranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeError {
+    /// The step is zero.
+    ZeroStep,
+
+    /// The left endpoint is unbounded, so there is no first element.
+    UnboundedLeft,
 }
 
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeError::ZeroStep => write!(f, "range step must not be zero"),
+            RangeError::UnboundedLeft => write!(f, "range must have a left endpoint"),
+        }
+    }
+}
+
+impl std::error::Error for RangeError {}
+
 struct RangeIter {
-    // TODO: add necessary fields as you want
-    left_val: isize,
-    right_val: isize,
+    first: isize,
     step: isize,
+    // Indexes of the next elements from the front and the back (exclusive), so that the `i`-th
+    // element is `first + step * i`
+    front: u128,
+    back: u128,
 }
 
 impl RangeIter {
-    fn new(endpoints: (Endpoint, Endpoint), step: isize) -> Self {
-        let lhs = match endpoints.0 {
-            Endpoint::Inclusive(val) => val,
-            Endpoint::Exclusive(val) => {
-                if step > 0 {
-                    val + 1
-                } else {
-                    val - 1
-                }
-            }
+    fn new(endpoints: (Endpoint, Endpoint), step: isize) -> Result<Self, RangeError> {
+        if step == 0 {
+            return Err(RangeError::ZeroStep);
+        }
+        let sign = step.signum() as i128;
+
+        let first = match endpoints.0 {
+            Endpoint::Inclusive(val) => val as i128,
+            Endpoint::Exclusive(val) => val as i128 + sign,
+            Endpoint::Unbounded => return Err(RangeError::UnboundedLeft),
         };
 
-        let rhs = match endpoints.1 {
-            Endpoint::Inclusive(val) => {
-                if step > 0 {
-                    val + 1
-                } else {
-                    val - 1
-                }
-            }
-            Endpoint::Exclusive(val) => val,
+        // The last value in range, as elements stop before overflowing `isize`
+        let last = match endpoints.1 {
+            Endpoint::Inclusive(val) => val as i128,
+            Endpoint::Exclusive(val) => val as i128 - sign,
+            Endpoint::Unbounded if step > 0 => isize::MAX as i128,
+            Endpoint::Unbounded => isize::MIN as i128,
         };
 
-        Self {
-            left_val: lhs,
-            right_val: rhs,
+        let len = if (last - first) * sign < 0 {
+            0
+        } else {
+            (last - first).unsigned_abs() / step.unsigned_abs() as u128 + 1
+        };
+
+        Ok(Self {
+            // An empty range may start out of `isize`, but it is never used.
+            first: first as isize,
             step,
-        }
+            front: 0,
+            back: len,
+        })
+    }
+
+    // The `i`-th element
+    fn element(&self, i: u128) -> isize {
+        (self.first as i128 + self.step as i128 * i as i128) as isize
     }
 }
 
@@ -146,26 +305,56 @@ impl Iterator for RangeIter {
     type Item = isize;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.step > 0 {
-            if self.left_val >= self.right_val {
-                None
-            } else {
-                let ret = self.left_val;
-                self.left_val += self.step;
-                Some(ret)
-            }
-        } else if self.left_val <= self.right_val {
-            None
-        } else {
-            let ret = self.left_val;
-            self.left_val += self.step;
-            Some(ret)
+        self.nth(0)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = usize::try_from(self.back - self.front).unwrap_or(usize::MAX);
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front = (self.front + n as u128).min(self.back);
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.element(self.front - 1))
+    }
+}
+
+impl DoubleEndedIterator for RangeIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.nth_back(0)
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.back = self.back.saturating_sub(n as u128).max(self.front);
+        if self.front == self.back {
+            return None;
         }
+        self.back -= 1;
+        Some(self.element(self.back))
     }
 }
 
+impl ExactSizeIterator for RangeIter {}
+
 /// Returns an iterator over the range [left, right) with the given step.
-pub fn range(left: Endpoint, right: Endpoint, step: isize) -> impl Iterator<Item = isize> {
+///
+/// If the right endpoint is `Unbounded`, the iterator goes on in the step direction until it
+/// overflows `isize`.
+///
+/// Like [`std::ops::Range`], the iterator can be reversed, knows its length, and skips elements in
+/// constant time. (The length saturates at `usize::MAX`, which only matters for the whole `isize`
+/// range with step `1` or `-1`.)
+///
+/// Returns an error if the step is zero or the left endpoint is `Unbounded`.
+pub fn range(
+    left: Endpoint,
+    right: Endpoint,
+    step: isize,
+) -> Result<impl DoubleEndedIterator<Item = isize> + ExactSizeIterator, RangeError> {
     RangeIter::new((left, right), step)
 }
 
@@ -214,3 +403,211 @@ pub fn divisors(n: u64) -> impl Iterator<Item = u64> {
         large: Vec::new(),
     }
 }
+
+// Prime factors by trial division
+struct PrimeFactors {
+    // The part of the number that is not factorized yet
+    n: u64,
+    // The next candidate
+    p: u64,
+}
+
+impl Iterator for PrimeFactors {
+    type Item = (u64, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.p <= self.n / self.p {
+            let p = self.p;
+            self.p += if p == 2 { 1 } else { 2 };
+
+            let mut exp = 0;
+            while self.n % p == 0 {
+                self.n /= p;
+                exp += 1;
+            }
+            if exp > 0 {
+                return Some((p, exp));
+            }
+        }
+
+        // The rest has no factor up to its square root.
+        if self.n > 1 {
+            let p = self.n;
+            self.n = 1;
+            return Some((p, 1));
+        }
+        None
+    }
+}
+
+/// Returns an iterator over the prime factors of n and their exponents, in increasing order of
+/// the primes. Assume n > 0.
+///
+/// For instance, `prime_factors(360)` yields `(2, 3)`, `(3, 2)` and `(5, 1)`.
+pub fn prime_factors(n: u64) -> impl Iterator<Item = (u64, u32)> {
+    PrimeFactors { n, p: 2 }
+}
+
+/// Returns the number of divisors of n. Assume n > 0.
+pub fn divisor_count(n: u64) -> u64 {
+    prime_factors(n)
+        .map(|(_, exp)| u64::from(exp) + 1)
+        .product()
+}
+
+/// Returns the sum of the divisors of n. Assume n > 0.
+pub fn divisor_sum(n: u64) -> u128 {
+    prime_factors(n)
+        .map(|(p, exp)| {
+            // 1 + p + ... + p^exp
+            let p = u128::from(p);
+            (0..exp).fold(1, |sum, _| sum * p + 1)
+        })
+        .product()
+}
+
+// (a + b) % m, for a, b < m
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+// (a * b) % m, for a, b < m
+fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    if m <= u128::from(u64::MAX) {
+        return a * b % m;
+    }
+    // Double and add, as the product may not fit in `u128`
+    let (mut a, mut b, mut result) = (a, b, 0);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    result
+}
+
+// base^exp % m
+fn pow_mod(base: u128, exp: u128, m: u128) -> u128 {
+    let (mut base, mut exp, mut result) = (base % m, exp, 1 % m);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+// Bases of the Miller-Rabin test, which make it deterministic below 3.3 * 10^24
+const MILLER_RABIN_BASES: [u128; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+// Miller-Rabin primality test
+fn is_probable_prime(n: u128) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for p in MILLER_RABIN_BASES {
+        if n % p == 0 {
+            return n == p;
+        }
+    }
+
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    MILLER_RABIN_BASES.iter().all(|&a| {
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        (1..s).any(|_| {
+            x = mul_mod(x, x, n);
+            x == n - 1
+        })
+    })
+}
+
+// A nontrivial factor of the odd composite n, with Pollard's rho algorithm
+fn pollard_rho(n: u128) -> u128 {
+    for c in 1.. {
+        let f = |x| add_mod(mul_mod(x, x, n), c, n);
+        let (mut x, mut y, mut d) = (2, 2, 1);
+        while d == 1 {
+            x = f(x);
+            y = f(f(y));
+            d = gcd_u128(x.abs_diff(y), n);
+        }
+        // Retry with another polynomial if the cycle is found modulo n itself.
+        if d != n {
+            return d;
+        }
+    }
+    unreachable!("a composite number has a nontrivial factor")
+}
+
+// Push the prime factors of n, with multiplicity, in arbitrary order
+fn push_prime_factors(n: u128, factors: &mut Vec<u128>) {
+    if n == 1 {
+        return;
+    }
+    if is_probable_prime(n) {
+        factors.push(n);
+        return;
+    }
+    let d = if n % 2 == 0 { 2 } else { pollard_rho(n) };
+    push_prime_factors(d, factors);
+    push_prime_factors(n / d, factors);
+}
+
+/// Returns the prime factors of n and their exponents, in increasing order of the primes. Assume
+/// n > 0.
+///
+/// Unlike [`prime_factors`], this works for numbers with large prime factors using Pollard's rho
+/// algorithm and the Miller-Rabin primality test. The test is deterministic below `3.3 * 10^24`,
+/// and probabilistic above it.
+pub fn factorize_u128(n: u128) -> Vec<(u128, u32)> {
+    let mut factors = Vec::new();
+    let mut n = n;
+    // Trial division is faster for small factors.
+    for p in 2..1000 {
+        while n % p == 0 {
+            factors.push(p);
+            n /= p;
+        }
+    }
+    push_prime_factors(n, &mut factors);
+
+    factors.sort_unstable();
+    factors
+        .into_iter()
+        .dedup_with_count()
+        .map(|(count, p)| (p, count as u32))
+        .collect()
+}
+
+/// Returns the divisors of n in increasing order, using [`factorize_u128`]. Assume n > 0.
+pub fn divisors_u128(n: u128) -> Vec<u128> {
+    let mut divisors = vec![1];
+    for (p, exp) in factorize_u128(n) {
+        let mut powers = divisors.clone();
+        for _ in 0..exp {
+            powers = powers.into_iter().map(|d| d * p).collect();
+            divisors.extend_from_slice(&powers);
+        }
+    }
+    divisors.sort_unstable();
+    divisors
+}
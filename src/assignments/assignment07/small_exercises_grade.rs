@@ -19,12 +19,12 @@ mod test {
 
         assert_eq!(
             find("ababac".as_bytes(), "abababcabababcabababc".as_bytes()).collect::<Vec<usize>>(),
-            vec![]
+            Vec::<usize>::new()
         );
 
         assert_eq!(
             find("ababc".as_bytes(), "abc".as_bytes()).collect::<Vec<usize>>(),
-            vec![]
+            Vec::<usize>::new()
         );
     }
 
@@ -45,6 +45,137 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_find_overlapping() {
+        assert_eq!(
+            find("aa".as_bytes(), "aaaa".as_bytes()).collect::<Vec<usize>>(),
+            vec![0, 1, 2]
+        );
+        assert_eq!(
+            find("abab".as_bytes(), "abababab".as_bytes()).collect::<Vec<usize>>(),
+            vec![0, 2, 4]
+        );
+        assert_eq!(
+            find("".as_bytes(), "ab".as_bytes()).collect::<Vec<usize>>(),
+            vec![0, 1, 2]
+        );
+
+        // Every query and base over `ab` up to length 4 and 8
+        let words = |max_len| {
+            (0..=max_len).flat_map(|len| {
+                (0..1usize << len).map(move |bits| {
+                    (0..len)
+                        .map(|i| if bits >> i & 1 == 0 { b'a' } else { b'b' })
+                        .collect::<Vec<u8>>()
+                })
+            })
+        };
+        for query in words(4) {
+            for base in words(8) {
+                assert_eq!(
+                    find(&query, &base).collect::<Vec<usize>>(),
+                    find_naive(&query, &base).collect::<Vec<usize>>(),
+                    "{:?} in {:?}",
+                    query,
+                    base
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_find_non_overlapping_and_rfind() {
+        assert_eq!(
+            find_non_overlapping("aa".as_bytes(), "aaaaa".as_bytes()).collect::<Vec<usize>>(),
+            vec![0, 2]
+        );
+        assert_eq!(
+            find_non_overlapping("aba".as_bytes(), "ababababa".as_bytes()).collect::<Vec<usize>>(),
+            vec![0, 4]
+        );
+        assert_eq!(
+            find_non_overlapping(&[1, 2], &[1, 2, 1, 2, 2]).collect::<Vec<usize>>(),
+            vec![0, 2]
+        );
+
+        assert_eq!(
+            rfind("aa".as_bytes(), "aaaa".as_bytes()).collect::<Vec<usize>>(),
+            vec![2, 1, 0]
+        );
+        assert_eq!(
+            rfind("aaba".as_bytes(), "aabaacaadaabaaba".as_bytes()).collect::<Vec<usize>>(),
+            vec![12, 9, 0]
+        );
+        assert_eq!(
+            rfind("".as_bytes(), "ab".as_bytes()).collect::<Vec<usize>>(),
+            vec![2, 1, 0]
+        );
+        assert_eq!(
+            rfind("abc".as_bytes(), "ab".as_bytes()).collect::<Vec<usize>>(),
+            Vec::<usize>::new()
+        );
+
+        for (query, base) in [("abab", "abababab"), ("aab", "aabaabaab"), ("x", "xyxyx")] {
+            let (query, base) = (query.as_bytes(), base.as_bytes());
+            let mut forward = find(query, base).collect::<Vec<usize>>();
+            forward.reverse();
+            assert_eq!(rfind(query, base).collect::<Vec<usize>>(), forward);
+
+            // Non-overlapping matches are greedy from the start.
+            let mut end = 0;
+            let greedy = find(query, base)
+                .filter(|&i| {
+                    let fits = i >= end;
+                    if fits {
+                        end = i + query.len();
+                    }
+                    fits
+                })
+                .collect::<Vec<usize>>();
+            assert_eq!(
+                find_non_overlapping(query, base).collect::<Vec<usize>>(),
+                greedy
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_str() {
+        assert_eq!(find_str("ab", "abcab").collect::<Vec<_>>(), vec![0, 3]);
+        assert_eq!(
+            find_str("한글", "한글과 한글").collect::<Vec<_>>(),
+            vec![0, 10]
+        );
+        assert_eq!(find_str("é", "café é").collect::<Vec<_>>(), vec![3, 6]);
+        assert_eq!(find_str("", "aé").collect::<Vec<_>>(), vec![0, 1, 3]);
+        assert_eq!(find_str("x", "").collect::<Vec<_>>(), Vec::<usize>::new());
+
+        // Every offset is a char boundary.
+        let base = "❤a❤❤b";
+        for i in find_str("❤", base) {
+            assert!(base[i..].starts_with('❤'));
+        }
+
+        for (query, base) in [
+            (", ", "a, b, c"),
+            (", ", ", a, , b, "),
+            ("aa", "aaaaa"),
+            ("", "aé"),
+            ("", ""),
+            ("x", ""),
+            ("글", "한글과 한글"),
+            ("zz", "abc"),
+        ] {
+            assert_eq!(
+                split_on(query, base).collect::<Vec<_>>(),
+                base.split(query).collect::<Vec<_>>(),
+                "{:?} in {:?}",
+                query,
+                base
+            );
+        }
+    }
+
     #[test]
     fn test_fib_iter() {
         assert_eq!(
@@ -100,44 +231,173 @@ mod test {
     fn test_range_iter() {
         let one_to_tens = [
             vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
-            range(Endpoint::Inclusive(1), Endpoint::Inclusive(10), 1).collect(),
-            range(Endpoint::Exclusive(0), Endpoint::Inclusive(10), 1).collect(),
-            range(Endpoint::Inclusive(1), Endpoint::Exclusive(11), 1).collect(),
-            range(Endpoint::Exclusive(0), Endpoint::Exclusive(11), 1).collect(),
+            range(Endpoint::Inclusive(1), Endpoint::Inclusive(10), 1)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Exclusive(0), Endpoint::Inclusive(10), 1)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Inclusive(1), Endpoint::Exclusive(11), 1)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Exclusive(0), Endpoint::Exclusive(11), 1)
+                .unwrap()
+                .collect(),
         ];
         assert!(one_to_tens.iter().all_equal());
 
         let ten_to_ones = [
             vec![10, 9, 8, 7, 6, 5, 4, 3, 2, 1],
-            range(Endpoint::Inclusive(10), Endpoint::Inclusive(1), -1).collect(),
-            range(Endpoint::Exclusive(11), Endpoint::Inclusive(1), -1).collect(),
-            range(Endpoint::Inclusive(10), Endpoint::Exclusive(0), -1).collect(),
-            range(Endpoint::Exclusive(11), Endpoint::Exclusive(0), -1).collect(),
+            range(Endpoint::Inclusive(10), Endpoint::Inclusive(1), -1)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Exclusive(11), Endpoint::Inclusive(1), -1)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Inclusive(10), Endpoint::Exclusive(0), -1)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Exclusive(11), Endpoint::Exclusive(0), -1)
+                .unwrap()
+                .collect(),
         ];
         assert!(ten_to_ones.iter().all_equal());
 
         let five_evens = vec![
             vec![2, 4, 6, 8, 10],
-            range(Endpoint::Inclusive(2), Endpoint::Inclusive(10), 2).collect(),
-            range(Endpoint::Inclusive(2), Endpoint::Inclusive(11), 2).collect(),
-            range(Endpoint::Exclusive(1), Endpoint::Inclusive(10), 2).collect(),
-            range(Endpoint::Exclusive(1), Endpoint::Inclusive(11), 2).collect(),
-            range(Endpoint::Inclusive(2), Endpoint::Exclusive(11), 2).collect(),
-            range(Endpoint::Inclusive(2), Endpoint::Exclusive(12), 2).collect(),
-            range(Endpoint::Exclusive(1), Endpoint::Exclusive(11), 2).collect(),
-            range(Endpoint::Exclusive(1), Endpoint::Exclusive(12), 2).collect(),
+            range(Endpoint::Inclusive(2), Endpoint::Inclusive(10), 2)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Inclusive(2), Endpoint::Inclusive(11), 2)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Exclusive(1), Endpoint::Inclusive(10), 2)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Exclusive(1), Endpoint::Inclusive(11), 2)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Inclusive(2), Endpoint::Exclusive(11), 2)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Inclusive(2), Endpoint::Exclusive(12), 2)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Exclusive(1), Endpoint::Exclusive(11), 2)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Exclusive(1), Endpoint::Exclusive(12), 2)
+                .unwrap()
+                .collect(),
         ];
         assert!(five_evens.iter().all_equal());
 
         let emptys = [
             vec![],
-            range(Endpoint::Inclusive(2), Endpoint::Inclusive(10), -1).collect(),
-            range(Endpoint::Inclusive(10), Endpoint::Inclusive(-100), 1).collect(),
-            range(Endpoint::Inclusive(1), Endpoint::Exclusive(1), 1).collect(),
+            range(Endpoint::Inclusive(2), Endpoint::Inclusive(10), -1)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Inclusive(10), Endpoint::Inclusive(-100), 1)
+                .unwrap()
+                .collect(),
+            range(Endpoint::Inclusive(1), Endpoint::Exclusive(1), 1)
+                .unwrap()
+                .collect(),
         ];
         assert!(emptys.iter().all_equal());
     }
 
+    #[test]
+    fn test_range_double_ended() {
+        let evens = || range(Endpoint::Inclusive(2), Endpoint::Exclusive(12), 2).unwrap();
+        assert_eq!(evens().rev().collect::<Vec<_>>(), vec![10, 8, 6, 4, 2]);
+        assert_eq!(evens().len(), 5);
+        assert_eq!(evens().nth(3), Some(8));
+        assert_eq!(evens().nth(5), None);
+        assert_eq!(evens().skip(2).collect::<Vec<_>>(), vec![6, 8, 10]);
+        assert_eq!(evens().rev().skip(4).collect::<Vec<_>>(), vec![2]);
+
+        let mut iter = evens();
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next_back(), Some(10));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.nth_back(1), Some(6));
+        assert_eq!(iter.next(), Some(4));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.len(), 0);
+
+        let down = range(Endpoint::Exclusive(10), Endpoint::Inclusive(1), -3).unwrap();
+        assert_eq!(down.len(), 3);
+        assert_eq!(down.rev().collect::<Vec<_>>(), vec![3, 6, 9]);
+
+        assert_eq!(
+            range(Endpoint::Inclusive(1), Endpoint::Inclusive(0), 1)
+                .unwrap()
+                .len(),
+            0
+        );
+
+        // Skipping is constant time, and the unbounded range ends before overflowing.
+        let mut all = range(Endpoint::Inclusive(0), Endpoint::Unbounded, 1).unwrap();
+        assert_eq!(all.len(), isize::MAX as usize + 1);
+        assert_eq!(all.nth(1 << 62), Some(1 << 62));
+        assert_eq!(all.next_back(), Some(isize::MAX));
+        let mut down = range(Endpoint::Inclusive(0), Endpoint::Unbounded, -7).unwrap();
+        assert_eq!(down.next_back(), Some(isize::MIN / 7 * 7));
+        assert_eq!(
+            range(Endpoint::Exclusive(isize::MIN), Endpoint::Unbounded, -1)
+                .unwrap()
+                .len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_range_unbounded() {
+        assert_eq!(
+            range(Endpoint::Inclusive(3), Endpoint::Unbounded, 4)
+                .unwrap()
+                .take(4)
+                .collect::<Vec<_>>(),
+            vec![3, 7, 11, 15]
+        );
+        assert_eq!(
+            range(Endpoint::Exclusive(0), Endpoint::Unbounded, -1)
+                .unwrap()
+                .take(3)
+                .collect::<Vec<_>>(),
+            vec![-1, -2, -3]
+        );
+
+        // The iterator stops instead of overflowing.
+        assert_eq!(
+            range(Endpoint::Inclusive(isize::MAX - 3), Endpoint::Unbounded, 2)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            vec![isize::MAX - 3, isize::MAX - 1]
+        );
+        assert_eq!(
+            range(
+                Endpoint::Inclusive(isize::MAX - 1),
+                Endpoint::Inclusive(isize::MAX),
+                1
+            )
+            .unwrap()
+            .collect::<Vec<_>>(),
+            vec![isize::MAX - 1, isize::MAX]
+        );
+
+        assert_eq!(
+            range(Endpoint::Inclusive(1), Endpoint::Inclusive(10), 0).err(),
+            Some(RangeError::ZeroStep)
+        );
+        assert_eq!(
+            range(Endpoint::Unbounded, Endpoint::Inclusive(10), 1).err(),
+            Some(RangeError::UnboundedLeft)
+        );
+    }
+
     #[test]
     fn test_small() {
         assert_eq!(divisors(10).collect::<Vec<u64>>(), vec![1, 2, 5, 10]);
@@ -186,4 +446,100 @@ mod test {
 
         assert_eq!(divisors(97_821_761_637_600).count(), 17280);
     }
+
+    #[test]
+    fn test_prime_factors() {
+        assert_eq!(prime_factors(1).collect::<Vec<_>>(), vec![]);
+        assert_eq!(prime_factors(2).collect::<Vec<_>>(), vec![(2, 1)]);
+        assert_eq!(
+            prime_factors(360).collect::<Vec<_>>(),
+            vec![(2, 3), (3, 2), (5, 1)]
+        );
+        assert_eq!(
+            prime_factors(99_999_820_000_081).collect::<Vec<_>>(),
+            vec![(9_999_991, 2)]
+        );
+        assert_eq!(
+            prime_factors(1_234_567_890_123).collect::<Vec<_>>(),
+            vec![(3, 1), (3_541, 1), (116_216_501, 1)]
+        );
+        assert_eq!(prime_factors(1 << 63).collect::<Vec<_>>(), vec![(2, 63)]);
+        assert_eq!(
+            prime_factors(1_000_000_000_000_037).collect::<Vec<_>>(),
+            vec![(1_000_000_000_000_037, 1)]
+        );
+
+        for n in 1..=1000 {
+            let divisors = divisors(n).collect::<Vec<_>>();
+            assert_eq!(divisor_count(n), divisors.len() as u64);
+            assert_eq!(
+                divisor_sum(n),
+                divisors.iter().map(|&d| u128::from(d)).sum::<u128>()
+            );
+        }
+        assert_eq!(divisor_count(97_821_761_637_600), 17280);
+        assert_eq!(divisor_sum(1_000_000_000_000_037), 1_000_000_000_000_038);
+        assert_eq!(divisor_sum(1 << 63), (1 << 64) - 1);
+    }
+
+    #[test]
+    fn test_factorize_u128() {
+        assert_eq!(factorize_u128(1), vec![]);
+        assert_eq!(factorize_u128(360), vec![(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(
+            factorize_u128(18_446_744_073_709_551_557),
+            vec![(18_446_744_073_709_551_557, 1)]
+        );
+        assert_eq!(
+            factorize_u128(1_000_000_000_000_037 * 1_000_000_007),
+            vec![(1_000_000_007, 1), (1_000_000_000_000_037, 1)]
+        );
+        assert_eq!(
+            factorize_u128(999_999_999_989 * 999_999_999_989 * 7),
+            vec![(7, 1), (999_999_999_989, 2)]
+        );
+        assert_eq!(factorize_u128((1 << 127) - 1), vec![((1 << 127) - 1, 1)]);
+        assert_eq!(
+            factorize_u128(u128::MAX),
+            vec![
+                (3, 1),
+                (5, 1),
+                (17, 1),
+                (257, 1),
+                (641, 1),
+                (65_537, 1),
+                (274_177, 1),
+                (6_700_417, 1),
+                (67_280_421_310_721, 1)
+            ]
+        );
+
+        for n in [97_821_761_637_600u64, 1_234_567_890_123, 99_999_820_000_081] {
+            assert_eq!(
+                factorize_u128(u128::from(n)),
+                prime_factors(n)
+                    .map(|(p, exp)| (u128::from(p), exp))
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_divisors_u128() {
+        assert_eq!(divisors_u128(1), vec![1]);
+        for n in [120u64, 49, 1_234_567_890_123] {
+            assert_eq!(
+                divisors_u128(u128::from(n)),
+                divisors(n).map(u128::from).collect::<Vec<_>>()
+            );
+        }
+        assert_eq!(divisors_u128(97_821_761_637_600).len(), 17280);
+
+        // 10^20 = 2^20 * 5^20
+        let divisors = divisors_u128(100_000_000_000_000_000_000);
+        assert_eq!(divisors.len(), 21 * 21);
+        assert_eq!(divisors[..4], [1, 2, 4, 5]);
+        assert_eq!(divisors.last(), Some(&100_000_000_000_000_000_000));
+        assert!(divisors.windows(2).all(|w| w[0] < w[1]));
+    }
 }
@@ -1,4 +1,7 @@
 //! Tranformer
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
 use std::marker::PhantomData;
 use std::ops::Add;
 
@@ -6,6 +9,17 @@ use std::ops::Add;
 pub trait Transform<T> {
     /// Transforms value.
     fn transform(&self, value: T) -> T;
+
+    /// Returns the transformation that applies `self` and then `next`.
+    fn then<Tr: Transform<T>>(self, next: Tr) -> Then<Self, Tr>
+    where
+        Self: Sized,
+    {
+        Then {
+            first: self,
+            second: next,
+        }
+    }
 }
 
 impl<T1, T2, Tr1: Transform<T1>, Tr2: Transform<T2>> Transform<(T1, T2)> for (Tr1, Tr2) {
@@ -15,6 +29,154 @@ impl<T1, T2, Tr1: Transform<T1>, Tr2: Transform<T2>> Transform<(T1, T2)> for (Tr
     }
 }
 
+impl<T, Tr: Transform<T> + ?Sized> Transform<T> for &Tr {
+    fn transform(&self, value: T) -> T {
+        (**self).transform(value)
+    }
+}
+
+/// Represents stateful transformation of type `T`, which may update itself on every call.
+///
+/// Wrap it in a `RefCell` to use it as a `Transform`, and wrap a `Transform` in `Lifted` to use it
+/// as a `TransformMut`.
+pub trait TransformMut<T> {
+    /// Transforms value.
+    fn transform_mut(&mut self, value: T) -> T;
+}
+
+impl<T, Tr: TransformMut<T>> Transform<T> for RefCell<Tr> {
+    fn transform(&self, value: T) -> T {
+        self.borrow_mut().transform_mut(value)
+    }
+}
+
+/// Represents transformation of type `T` that updates the value in place, avoiding moving large
+/// values through every stage.
+///
+/// Wrap it in `InPlace` to use it as a `Transform`, and wrap a `Transform` in `Lifted` to use it
+/// as a `TransformInPlace`.
+pub trait TransformInPlace<T> {
+    /// Transforms value in place.
+    fn transform_in_place(&self, value: &mut T);
+}
+
+/// Represents fallible transformation of type `T`.
+pub trait TryTransform<T> {
+    /// The error of the transformation.
+    type Error;
+
+    /// Transforms value, or returns an error if it fails.
+    fn try_transform(&self, value: T) -> Result<T, Self::Error>;
+
+    /// Returns the transformation that applies `self` and then `next`, stopping at the first
+    /// error.
+    fn try_then<Tr: TryTransform<T, Error = Self::Error>>(self, next: Tr) -> Then<Self, Tr>
+    where
+        Self: Sized,
+    {
+        Then {
+            first: self,
+            second: next,
+        }
+    }
+}
+
+/// Composition of two transformations, applying `first` and then `second`.
+#[derive(Debug, Clone, Copy)]
+pub struct Then<Tr1, Tr2> {
+    first: Tr1,
+    second: Tr2,
+}
+
+impl<T, Tr1: Transform<T>, Tr2: Transform<T>> Transform<T> for Then<Tr1, Tr2> {
+    fn transform(&self, value: T) -> T {
+        self.second.transform(self.first.transform(value))
+    }
+}
+
+impl<T, E, Tr1, Tr2> TryTransform<T> for Then<Tr1, Tr2>
+where
+    Tr1: TryTransform<T, Error = E>,
+    Tr2: TryTransform<T, Error = E>,
+{
+    type Error = E;
+
+    fn try_transform(&self, value: T) -> Result<T, E> {
+        self.second.try_transform(self.first.try_transform(value)?)
+    }
+}
+
+/// Applies the inner transformation to each element of a container.
+///
+/// This is a wrapper rather than a blanket impl for every `Tr: Transform<T>`, since such an impl
+/// would overlap with `Identity`, which already transforms every type.
+#[derive(Debug, Clone, Copy)]
+pub struct Elementwise<Tr> {
+    inner: Tr,
+}
+
+impl<Tr> Elementwise<Tr> {
+    /// Creates a new elementwise transformation.
+    pub fn new(inner: Tr) -> Self {
+        Elementwise { inner }
+    }
+}
+
+impl<T, Tr: Transform<T>> Transform<Vec<T>> for Elementwise<Tr> {
+    fn transform(&self, value: Vec<T>) -> Vec<T> {
+        value.into_iter().map(|v| self.inner.transform(v)).collect()
+    }
+}
+
+impl<T, Tr: Transform<T>, const N: usize> Transform<[T; N]> for Elementwise<Tr> {
+    fn transform(&self, value: [T; N]) -> [T; N] {
+        value.map(|v| self.inner.transform(v))
+    }
+}
+
+impl<T, Tr: Transform<T>> Transform<Option<T>> for Elementwise<Tr> {
+    fn transform(&self, value: Option<T>) -> Option<T> {
+        value.map(|v| self.inner.transform(v))
+    }
+}
+
+/// Records every value produced by the inner transformation.
+///
+/// Wrap a reference to it in `Repeat` or `RepeatUntilConverge` to inspect the intermediate values.
+#[derive(Debug, Clone)]
+pub struct Traced<T, Tr: Transform<T>> {
+    inner: Tr,
+    trace: RefCell<Vec<T>>,
+}
+
+impl<T: Clone, Tr: Transform<T>> Traced<T, Tr> {
+    /// Creates a new traced transformation with an empty trace.
+    pub fn new(inner: Tr) -> Self {
+        Traced {
+            inner,
+            trace: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Returns the values recorded so far.
+    pub fn trace(&self) -> Vec<T> {
+        self.trace.borrow().clone()
+    }
+
+    /// Returns the values recorded so far, clearing the trace.
+    pub fn take_trace(&self) -> Vec<T> {
+        self.trace.take()
+    }
+}
+
+impl<T: Clone, Tr: Transform<T>> Transform<T> for Traced<T, Tr> {
+    fn transform(&self, value: T) -> T {
+        let result = self.inner.transform(value);
+        self.trace.borrow_mut().push(result.clone());
+        result
+    }
+}
+
 /// Identity transformation.
 #[derive(Debug, Clone, Copy)]
 pub struct Identity;
@@ -47,15 +209,130 @@ impl<T, F: Fn(T) -> T> Transform<T> for Custom<T, F> {
     }
 }
 
+/// Custom stateful transformation.
+#[derive(Debug, Clone, Copy)]
+pub struct CustomMut<T, F: FnMut(T) -> T> {
+    f: F,
+    _marker: PhantomData<T>,
+}
+
+impl<T, F: FnMut(T) -> T> From<F> for CustomMut<T, F> {
+    fn from(f: F) -> Self {
+        Self {
+            f,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, F: FnMut(T) -> T> TransformMut<T> for CustomMut<T, F> {
+    fn transform_mut(&mut self, value: T) -> T {
+        (self.f)(value)
+    }
+}
+
+/// Custom in-place transformation.
+#[derive(Debug, Clone, Copy)]
+pub struct CustomInPlace<T, F: Fn(&mut T)> {
+    f: F,
+    _marker: PhantomData<T>,
+}
+
+impl<T, F: Fn(&mut T)> From<F> for CustomInPlace<T, F> {
+    fn from(f: F) -> Self {
+        Self {
+            f,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, F: Fn(&mut T)> TransformInPlace<T> for CustomInPlace<T, F> {
+    fn transform_in_place(&self, value: &mut T) {
+        (self.f)(value)
+    }
+}
+
+/// Adapts an in-place transformation into a `Transform`.
+#[derive(Debug, Clone, Copy)]
+pub struct InPlace<Tr> {
+    inner: Tr,
+}
+
+impl<Tr> InPlace<Tr> {
+    /// Creates a new adapter.
+    pub fn new(inner: Tr) -> Self {
+        InPlace { inner }
+    }
+}
+
+impl<T, Tr: TransformInPlace<T>> Transform<T> for InPlace<Tr> {
+    fn transform(&self, mut value: T) -> T {
+        self.inner.transform_in_place(&mut value);
+        value
+    }
+}
+
+/// Adapts a `Transform` into a `TransformMut` or `TransformInPlace`.
+#[derive(Debug, Clone, Copy)]
+pub struct Lifted<Tr> {
+    inner: Tr,
+}
+
+impl<Tr> Lifted<Tr> {
+    /// Creates a new adapter.
+    pub fn new(inner: Tr) -> Self {
+        Lifted { inner }
+    }
+}
+
+impl<T, Tr: Transform<T>> TransformMut<T> for Lifted<Tr> {
+    fn transform_mut(&mut self, value: T) -> T {
+        self.inner.transform(value)
+    }
+}
+
+impl<T: Default, Tr: Transform<T>> TransformInPlace<T> for Lifted<Tr> {
+    fn transform_in_place(&self, value: &mut T) {
+        *value = self.inner.transform(std::mem::take(value));
+    }
+}
+
+/// Custom fallible transformation.
+#[derive(Debug, Clone, Copy)]
+pub struct TryCustom<T, E, F: Fn(T) -> Result<T, E>> {
+    f: F,
+    _marker: PhantomData<(T, E)>,
+}
+
+impl<T, E, F: Fn(T) -> Result<T, E>> From<F> for TryCustom<T, E, F> {
+    fn from(f: F) -> Self {
+        Self {
+            f,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, E, F: Fn(T) -> Result<T, E>> TryTransform<T> for TryCustom<T, E, F> {
+    type Error = E;
+
+    fn try_transform(&self, value: T) -> Result<T, E> {
+        (self.f)(value)
+    }
+}
+
 /// Repeats transformation for `n` times.
+///
+/// If the inner transformation is fallible, the repetition stops at the first error.
 #[derive(Debug, Clone, Copy)]
-pub struct Repeat<T, Tr: Transform<T>> {
+pub struct Repeat<T, Tr> {
     inner: Tr,
     n: u32,
     _marker: PhantomData<T>,
 }
 
-impl<T, Tr: Transform<T>> Repeat<T, Tr> {
+impl<T, Tr> Repeat<T, Tr> {
     /// Creates a new repeat transformation.
     pub fn new(inner: Tr, n: u32) -> Self {
         Repeat {
@@ -75,6 +352,17 @@ impl<T, Tr: Transform<T>> Transform<T> for Repeat<T, Tr> {
     }
 }
 
+impl<T, Tr: TryTransform<T>> TryTransform<T> for Repeat<T, Tr> {
+    type Error = Tr::Error;
+
+    fn try_transform(&self, mut value: T) -> Result<T, Self::Error> {
+        for _ in 0..self.n {
+            value = self.inner.try_transform(value)?;
+        }
+        Ok(value)
+    }
+}
+
 /// Repeats transformation until converges.
 #[derive(Debug, Clone, Copy)]
 pub struct RepeatUntilConverge<T: Eq, Tr: Transform<T>> {
@@ -90,6 +378,16 @@ impl<T: Clone + Eq, Tr: Transform<T>> RepeatUntilConverge<T, Tr> {
             _marker: PhantomData,
         }
     }
+
+    /// Gives up after `max_iters` applications instead of looping forever, e.g. on oscillating
+    /// inputs.
+    pub fn with_max_iters(self, max_iters: u32) -> BoundedConverge<T, Tr> {
+        BoundedConverge {
+            inner: self.inner,
+            max_iters,
+            _marker: PhantomData,
+        }
+    }
 }
 
 impl<T: Clone + Eq, Tr: Transform<T>> Transform<T> for RepeatUntilConverge<T, Tr> {
@@ -104,3 +402,203 @@ impl<T: Clone + Eq, Tr: Transform<T>> Transform<T> for RepeatUntilConverge<T, Tr
         }
     }
 }
+
+/// Error of a convergence that did not reach a fixed point in time, holding the last value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotConverged<T>(pub T);
+
+/// Repeats transformation until converges, for at most `max_iters` times.
+///
+/// As a `Transform`, returns the last value on a best-effort basis; as a `TryTransform`, returns
+/// `NotConverged` instead.
+#[derive(Debug, Clone, Copy)]
+pub struct BoundedConverge<T: Eq, Tr: Transform<T>> {
+    inner: Tr,
+    max_iters: u32,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Clone + Eq, Tr: Transform<T>> TryTransform<T> for BoundedConverge<T, Tr> {
+    type Error = NotConverged<T>;
+
+    fn try_transform(&self, mut value: T) -> Result<T, NotConverged<T>> {
+        for _ in 0..self.max_iters {
+            let temp = self.inner.transform(value.clone());
+            if temp == value {
+                return Ok(value);
+            }
+            value = temp;
+        }
+        Err(NotConverged(value))
+    }
+}
+
+impl<T: Clone + Eq, Tr: Transform<T>> Transform<T> for BoundedConverge<T, Tr> {
+    fn transform(&self, value: T) -> T {
+        self.try_transform(value).unwrap_or_else(|e| e.0)
+    }
+}
+
+/// Repeats transformation of `f64` until successive values differ by less than `tolerance`, for
+/// at most `max_iters` times.
+///
+/// As a `Transform`, returns the last value on a best-effort basis; as a `TryTransform`, returns
+/// `NotConverged` instead.
+#[derive(Debug, Clone, Copy)]
+pub struct ConvergeWithin<Tr: Transform<f64>> {
+    inner: Tr,
+    tolerance: f64,
+    max_iters: u32,
+}
+
+impl<Tr: Transform<f64>> ConvergeWithin<Tr> {
+    /// Creates a new tolerance-based convergence.
+    pub fn new(inner: Tr, tolerance: f64, max_iters: u32) -> Self {
+        ConvergeWithin {
+            inner,
+            tolerance,
+            max_iters,
+        }
+    }
+}
+
+impl<Tr: Transform<f64>> TryTransform<f64> for ConvergeWithin<Tr> {
+    type Error = NotConverged<f64>;
+
+    fn try_transform(&self, mut value: f64) -> Result<f64, NotConverged<f64>> {
+        for _ in 0..self.max_iters {
+            let temp = self.inner.transform(value);
+            if (temp - value).abs() < self.tolerance {
+                return Ok(temp);
+            }
+            value = temp;
+        }
+        Err(NotConverged(value))
+    }
+}
+
+impl<Tr: Transform<f64>> Transform<f64> for ConvergeWithin<Tr> {
+    fn transform(&self, value: f64) -> f64 {
+        self.try_transform(value).unwrap_or_else(|e| e.0)
+    }
+}
+
+/// Sequence of transformations assembled at runtime, applied in order.
+pub struct Pipeline<T> {
+    stages: Vec<Box<dyn Transform<T>>>,
+}
+
+impl<T> fmt::Debug for Pipeline<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pipeline")
+            .field("stages", &self.stages.len())
+            .finish()
+    }
+}
+
+impl<T> Default for Pipeline<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Pipeline<T> {
+    /// Creates an empty pipeline, which behaves as the identity.
+    pub fn new() -> Self {
+        Pipeline { stages: Vec::new() }
+    }
+
+    /// Returns the number of stages.
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Returns `true` if the pipeline has no stages.
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Appends a stage at the end of the pipeline.
+    pub fn push<Tr: Transform<T> + 'static>(&mut self, tr: Tr) {
+        self.stages.push(Box::new(tr));
+    }
+
+    /// Inserts a stage at position `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > self.len()`.
+    pub fn insert<Tr: Transform<T> + 'static>(&mut self, index: usize, tr: Tr) {
+        self.stages.insert(index, Box::new(tr));
+    }
+
+    /// Runs every stage on `value` in order.
+    pub fn run(&self, value: T) -> T {
+        self.stages
+            .iter()
+            .fold(value, |value, stage| stage.transform(value))
+    }
+}
+
+impl<T> Transform<T> for Pipeline<T> {
+    fn transform(&self, value: T) -> T {
+        self.run(value)
+    }
+}
+
+type TransformFactory<T> = Box<dyn Fn() -> Box<dyn Transform<T>>>;
+
+/// Name-keyed registry of transformations, used to build pipelines from configuration.
+pub struct Registry<T> {
+    factories: HashMap<String, TransformFactory<T>>,
+}
+
+impl<T> fmt::Debug for Registry<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names = self.factories.keys().collect::<Vec<_>>();
+        names.sort();
+        f.debug_struct("Registry").field("names", &names).finish()
+    }
+}
+
+impl<T> Default for Registry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Registry<T> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Registry {
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Registers a transformation under `name`, replacing any previous one.
+    ///
+    /// The transformation is cloned every time it is looked up.
+    pub fn register<Tr: Transform<T> + Clone + 'static>(&mut self, name: &str, tr: Tr) {
+        let _unused = self
+            .factories
+            .insert(name.to_string(), Box::new(move || Box::new(tr.clone())));
+    }
+
+    /// Returns `true` if a transformation is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+
+    /// Builds a pipeline from the given names, in order.
+    ///
+    /// Returns the first unknown name as the error.
+    pub fn build<S: AsRef<str>>(&self, names: &[S]) -> Result<Pipeline<T>, String> {
+        let mut pipeline = Pipeline::new();
+        for name in names {
+            let name = name.as_ref();
+            let factory = self.factories.get(name).ok_or_else(|| name.to_string())?;
+            pipeline.stages.push(factory());
+        }
+        Ok(pipeline)
+    }
+}
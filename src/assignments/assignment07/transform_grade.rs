@@ -1,5 +1,7 @@
 #[cfg(test)]
 mod test {
+    use std::cell::RefCell;
+
     use itertools::Itertools;
     use ntest::assert_about_eq;
 
@@ -59,4 +61,262 @@ mod test {
         assert_eq!(RepeatUntilConverge::new(dec).transform(40), 40);
         assert_eq!(RepeatUntilConverge::new(dec).transform(60), 50);
     }
+
+    #[test]
+    fn test_transform_then() {
+        let inc = Custom::from(|x: i32| x + 1);
+        let double = Custom::from(|x: i32| x * 2);
+        let square = Custom::from(|x: i32| x * x);
+
+        for x in -10..10 {
+            assert_eq!(inc.then(double).transform(x), (x + 1) * 2);
+            assert_eq!(double.then(inc).transform(x), x * 2 + 1);
+            assert_eq!(
+                inc.then(double).then(square).transform(x),
+                inc.then(double.then(square)).transform(x)
+            );
+        }
+
+        let tr = Repeat::new(inc, 3).then(Repeat::new(double, 2));
+        assert_eq!(tr.transform(1), 16);
+    }
+
+    #[test]
+    fn test_transform_then_identity() {
+        let f = Custom::from(|x: i32| 3 * x - 7);
+
+        for x in -10..10 {
+            assert_eq!(Identity.then(f).transform(x), f.transform(x));
+            assert_eq!(f.then(Identity).transform(x), f.transform(x));
+        }
+
+        let s = Custom::from(|s: String| s + "!");
+        assert_eq!(Identity.then(s.clone()).transform("a".to_string()), "a!");
+        assert_eq!(s.then(Identity).transform("a".to_string()), "a!");
+    }
+
+    #[test]
+    fn test_transform_elementwise() {
+        let inc = Custom::from(|x: i32| x + 1);
+        let tr = Elementwise::new(inc);
+
+        assert_eq!(tr.transform(vec![1, 2, 3]), vec![2, 3, 4]);
+        assert_eq!(tr.transform(Vec::<i32>::new()), Vec::<i32>::new());
+        assert_eq!(tr.transform([10, 20]), [11, 21]);
+        assert_eq!(tr.transform(Some(5)), Some(6));
+        assert_eq!(tr.transform(None::<i32>), None);
+
+        // Elementwise transformations nest and compose like any other transformation.
+        let nested = Elementwise::new(Elementwise::new(Repeat::new(inc, 2)));
+        assert_eq!(
+            nested.transform(vec![Some(1), None, Some(3)]),
+            vec![Some(3), None, Some(5)]
+        );
+        let square = Elementwise::new(Custom::from(|x: i32| x * x));
+        let tr = Transform::<[i32; 3]>::then(Elementwise::new(inc), square);
+        assert_eq!(tr.transform([1, 2, 3]), [4, 9, 16]);
+    }
+
+    #[test]
+    fn test_try_transform() {
+        fn halve(x: i32) -> Result<i32, String> {
+            if x % 2 == 0 {
+                Ok(x / 2)
+            } else {
+                Err(format!("{x} is odd"))
+            }
+        }
+        let tr = TryCustom::from(halve);
+        assert_eq!(tr.try_transform(10), Ok(5));
+        assert_eq!(tr.try_transform(5), Err("5 is odd".to_string()));
+
+        assert_eq!(
+            Repeat::new(TryCustom::from(halve), 3).try_transform(40),
+            Ok(5)
+        );
+        assert_eq!(
+            Repeat::new(TryCustom::from(halve), 4).try_transform(40),
+            Err("5 is odd".to_string())
+        );
+        assert_eq!(
+            Repeat::new(TryCustom::from(halve), 0).try_transform(7),
+            Ok(7)
+        );
+
+        let parse = TryCustom::from(|s: String| {
+            s.parse::<i32>()
+                .map(|x| (x * 10).to_string())
+                .map_err(|e| e.to_string())
+        });
+        let append_zero = TryCustom::from(|s: String| Ok(s + "0"));
+        let tr = parse.try_then(append_zero);
+        assert_eq!(tr.try_transform("12".to_string()), Ok("1200".to_string()));
+        assert!(tr.try_transform("x".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_try_transform_short_circuits() {
+        let calls = std::cell::Cell::new(0);
+        let checked_dec = TryCustom::from(|x: u32| {
+            calls.set(calls.get() + 1);
+            x.checked_sub(1).ok_or("underflow")
+        });
+
+        assert_eq!(
+            Repeat::new(checked_dec, 100).try_transform(3),
+            Err("underflow")
+        );
+        assert_eq!(calls.get(), 4);
+
+        calls.set(0);
+        let tr = checked_dec.try_then(checked_dec).try_then(checked_dec);
+        assert_eq!(tr.try_transform(1), Err("underflow"));
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn test_pipeline() {
+        let mut pipeline = Pipeline::new();
+        assert!(pipeline.is_empty());
+        assert_eq!(pipeline.run(3), 3);
+
+        pipeline.push(Custom::from(|x: i32| x + 1));
+        pipeline.push(Custom::from(|x: i32| x * 2));
+        assert_eq!(pipeline.len(), 2);
+        assert_eq!(pipeline.run(3), 8);
+
+        pipeline.insert(0, Custom::from(|x: i32| x - 10));
+        assert_eq!(pipeline.run(3), -12);
+
+        pipeline.push(Repeat::new(Custom::from(|x: i32| x + 1), 2));
+        assert_eq!(pipeline.transform(3), -10);
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = Registry::new();
+        registry.register("inc", Custom::from(|x: i64| x + 1));
+        registry.register("double", Custom::from(|x: i64| x * 2));
+        registry.register("id", Identity);
+        assert!(registry.contains("inc"));
+        assert!(!registry.contains("square"));
+
+        let config = "inc double double id inc";
+        let names = config.split_whitespace().collect::<Vec<_>>();
+        let pipeline = registry.build(&names).unwrap();
+        assert_eq!(pipeline.len(), 5);
+        assert_eq!(pipeline.run(0), 5);
+
+        assert_eq!(registry.build(&["inc", "square"]).unwrap_err(), "square");
+        assert!(registry.build::<&str>(&[]).unwrap().is_empty());
+
+        registry.register("inc", Custom::from(|x: i64| x + 100));
+        assert_eq!(registry.build(&["inc"]).unwrap().run(0), 100);
+    }
+
+    #[test]
+    fn test_bounded_converge() {
+        let halve = Custom::from(|x: u32| x / 2);
+        let tr = RepeatUntilConverge::new(halve).with_max_iters(100);
+        assert_eq!(tr.try_transform(1000), Ok(0));
+        assert_eq!(tr.transform(1000), 0);
+
+        let tr = RepeatUntilConverge::new(halve).with_max_iters(3);
+        assert_eq!(tr.try_transform(1000), Err(NotConverged(125)));
+        assert_eq!(tr.transform(1000), 125);
+
+        let flip = Custom::from(|x: bool| !x);
+        let tr = RepeatUntilConverge::new(flip).with_max_iters(5);
+        assert_eq!(tr.try_transform(true), Err(NotConverged(false)));
+        assert!(!tr.transform(true));
+
+        let tr = RepeatUntilConverge::new(halve).with_max_iters(0);
+        assert_eq!(tr.try_transform(0), Err(NotConverged(0)));
+    }
+
+    #[test]
+    fn test_converge_within() {
+        // Newton's method for the square root of 2.
+        let newton = Custom::from(|x: f64| (x + 2.0 / x) / 2.0);
+        let tr = ConvergeWithin::new(newton, 1e-12, 100);
+        let root = tr.try_transform(1.0).unwrap();
+        assert!((root - 2.0f64.sqrt()).abs() < 1e-12);
+        assert!((tr.transform(1.0) - 2.0f64.sqrt()).abs() < 1e-12);
+
+        let tr = ConvergeWithin::new(newton, 1e-12, 1);
+        assert_eq!(tr.try_transform(1.0), Err(NotConverged(1.5)));
+
+        let negate = Custom::from(|x: f64| -x);
+        let tr = ConvergeWithin::new(negate, 1e-9, 10);
+        assert_eq!(tr.try_transform(1.0), Err(NotConverged(1.0)));
+        assert_eq!(tr.transform(1.0), 1.0);
+        assert_eq!(tr.try_transform(0.0), Ok(-0.0));
+
+        let tr = ConvergeWithin::new(Identity, 1e-9, 10);
+        assert!(tr.try_transform(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_traced() {
+        let inc = Traced::new(Custom::from(|x: i32| x + 1));
+        assert_eq!(Repeat::new(&inc, 3).transform(0), 3);
+        assert_eq!(inc.trace(), vec![1, 2, 3]);
+        assert_eq!(inc.take_trace(), vec![1, 2, 3]);
+        assert!(inc.trace().is_empty());
+
+        let halve = Traced::new(Custom::from(|x: u32| x / 2));
+        assert_eq!(RepeatUntilConverge::new(&halve).transform(20), 0);
+        assert_eq!(halve.trace(), vec![10, 5, 2, 1, 0, 0]);
+
+        let flip = Traced::new(Custom::from(|x: bool| !x));
+        let tr = RepeatUntilConverge::new(&flip).with_max_iters(4);
+        assert!(tr.try_transform(true).is_err());
+        assert_eq!(flip.trace(), vec![false, true, false, true]);
+    }
+
+    #[test]
+    fn test_transform_mut() {
+        let mut calls = 0;
+        let mut tr = CustomMut::from(|x: i32| {
+            calls += 1;
+            x + calls
+        });
+        assert_eq!(tr.transform_mut(10), 11);
+        assert_eq!(tr.transform_mut(10), 12);
+        assert_eq!(tr.transform_mut(10), 13);
+        assert_eq!(calls, 3);
+
+        let mut calls = 0;
+        let counter = RefCell::new(CustomMut::from(|x: i32| {
+            calls += 1;
+            x * 2
+        }));
+        assert_eq!(Repeat::new(&counter, 4).transform(1), 16);
+        assert_eq!(calls, 4);
+
+        let mut tr = Lifted::new(Repeat::new(Custom::from(|x: i32| x + 1), 3));
+        assert_eq!(tr.transform_mut(1), 4);
+    }
+
+    #[test]
+    fn test_transform_in_place() {
+        let push_zero = CustomInPlace::from(|v: &mut Vec<i32>| v.push(0));
+        let mut v = vec![1, 2];
+        push_zero.transform_in_place(&mut v);
+        assert_eq!(v, [1, 2, 0]);
+
+        let reverse = Custom::from(|mut v: Vec<i32>| {
+            v.reverse();
+            v
+        });
+        Lifted::new(&reverse).transform_in_place(&mut v);
+        assert_eq!(v, [0, 2, 1]);
+
+        let tr = Repeat::new(InPlace::new(push_zero), 2).then(reverse);
+        assert_eq!(tr.transform(vec![1]), [0, 0, 1]);
+
+        let mut s = String::from("ab");
+        Lifted::new(Custom::from(|s: String| s.repeat(2))).transform_in_place(&mut s);
+        assert_eq!(s, "abab");
+    }
 }
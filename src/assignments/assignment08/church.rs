@@ -9,12 +9,48 @@
 //! - <https://en.wikipedia.org/wiki/Church_encoding>
 //! - <https://opendsa-server.cs.vt.edu/OpenDSA/Books/PL/html/ChurchNumerals.html>
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 /// Church numerals are represented as higher-order functions that take a function `f`
 pub type Church<T> = Rc<dyn Fn(Rc<dyn Fn(T) -> T>) -> Rc<dyn Fn(T) -> T>>;
 
+/// Church booleans are represented as functions that choose one of two arguments.
+pub type ChurchBool<T> = Rc<dyn Fn(T, T) -> T>;
+
+/// Returns the Church boolean for true, which chooses the first argument.
+pub fn church_true<T: 'static>() -> ChurchBool<T> {
+    Rc::new(|a, _| a)
+}
+
+/// Returns the Church boolean for false, which chooses the second argument.
+pub fn church_false<T: 'static>() -> ChurchBool<T> {
+    Rc::new(|_, b| b)
+}
+
+/// Converts a Church boolean to a bool.
+pub fn to_bool(b: ChurchBool<bool>) -> bool {
+    b(true, false)
+}
+
+/// Church pairs are represented as functions that pass both components to a Church boolean.
+pub type ChurchPair<T> = Rc<dyn Fn(ChurchBool<T>) -> T>;
+
+/// Returns the Church pair of `a` and `b`.
+pub fn pair<T: Clone + 'static>(a: T, b: T) -> ChurchPair<T> {
+    Rc::new(move |s| s(a.clone(), b.clone()))
+}
+
+/// Returns the first component of a Church pair.
+pub fn fst<T: 'static>(p: ChurchPair<T>) -> T {
+    p(church_true())
+}
+
+/// Returns the second component of a Church pair.
+pub fn snd<T: 'static>(p: ChurchPair<T>) -> T {
+    p(church_false())
+}
+
 /// This function returns a Church numeral equivalent of the natural number 1.
 /// It takes a function `f` and applies it exactly once.
 pub fn one<T: 'static>() -> Church<T> {
@@ -33,6 +69,59 @@ pub fn zero<T: 'static>() -> Church<T> {
     Rc::new(|_| Rc::new(|x| x))
 }
 
+/// Church lists are represented as their right folds: a list of `a1, ..., ak` takes a function `c`
+/// and an initial value `n`, and returns `c(a1, ... c(ak, n))`.
+///
+/// Since Rust has no higher-rank types, the result type `R` of the fold is fixed by the list type.
+pub type ChurchList<T, R> = Rc<dyn Fn(Rc<dyn Fn(T, R) -> R>, R) -> R>;
+
+/// Returns the empty Church list.
+pub fn nil<T: 'static, R: 'static>() -> ChurchList<T, R> {
+    Rc::new(|_, n| n)
+}
+
+/// Returns the Church list with `x` in front of `l`.
+pub fn cons<T: Clone + 'static, R: 'static>(x: T, l: ChurchList<T, R>) -> ChurchList<T, R> {
+    Rc::new(move |c, n| {
+        let rest = l(Rc::clone(&c), n);
+        c(x.clone(), rest)
+    })
+}
+
+/// Converts a vector to a Church list.
+pub fn list_from_vec<T: Clone + 'static, R: 'static>(v: Vec<T>) -> ChurchList<T, R> {
+    v.into_iter().rev().fold(nil(), |l, x| cons(x, l))
+}
+
+/// Folds a Church list from the right, starting from `n`.
+pub fn fold<T: 'static, R: 'static>(l: ChurchList<T, R>, c: Rc<dyn Fn(T, R) -> R>, n: R) -> R {
+    l(c, n)
+}
+
+/// Applies `g` to each element of a Church list.
+pub fn map<T: 'static, U: 'static, R: 'static>(
+    l: ChurchList<T, R>,
+    g: Rc<dyn Fn(T) -> U>,
+) -> ChurchList<U, R> {
+    Rc::new(move |c, n| {
+        let g = Rc::clone(&g);
+        l(Rc::new(move |x, r| c(g(x), r)), n)
+    })
+}
+
+/// Returns the Church list with the elements of `l` followed by the elements of `m`.
+pub fn append<T: 'static, R: 'static>(
+    l: ChurchList<T, R>,
+    m: ChurchList<T, R>,
+) -> ChurchList<T, R> {
+    Rc::new(move |c, n| l(Rc::clone(&c), m(c, n)))
+}
+
+/// Returns the length of a Church list as a Church numeral.
+pub fn length<T: 'static, U: 'static>(l: ChurchList<T, Church<U>>) -> Church<U> {
+    l(Rc::new(|_, r| succ(r)), zero())
+}
+
 /// Implement a function to add 1 to a given Church numeral.
 pub fn succ<T: 'static>(n: Church<T>) -> Church<T> {
     Rc::new(move |f| {
@@ -41,6 +130,51 @@ pub fn succ<T: 'static>(n: Church<T>) -> Church<T> {
     })
 }
 
+/// Returns the predecessor of a Church numeral. The predecessor of zero is zero.
+///
+/// This is not the classic pair-based encoding (see [`pred_pair`]). Instead, `n` is applied to a
+/// version of `f` that skips its first call, tracked by a [`Cell`] flag, so `f` is applied one time
+/// fewer. This side effect is not expressible in the lambda calculus, but it keeps the type of the
+/// numeral: the pair-based encoding applies `n` to functions on pairs, which needs `n` at type
+/// `Church<(T, T)>`, and Rust has no higher-rank types to turn a `Church<T>` into one. Functions
+/// like [`sub`] that apply `pred` repeatedly to a `Church<T>` therefore cannot use the pairs.
+pub fn pred<T: 'static>(n: Church<T>) -> Church<T> {
+    Rc::new(move |f| {
+        let n = Rc::clone(&n);
+        Rc::new(move |x| {
+            let f = Rc::clone(&f);
+            let skipped = Cell::new(false);
+            let lagging: Rc<dyn Fn(T) -> T> =
+                Rc::new(move |y| if skipped.replace(true) { f(y) } else { y });
+            n(lagging)(x)
+        })
+    })
+}
+
+/// Returns the predecessor of a Church numeral with the classic pair-based encoding
+/// `λn.λf.λx. fst (n (λp. (snd p, f (snd p))) (x, x))`. The predecessor of zero is zero.
+///
+/// Starting from `(x, x)`, each application of `n` shifts the pair to `(snd p, f (snd p))`, so the
+/// first component stays one application of `f` behind the second. The pairs are Rust tuples, and
+/// `n` must be given at the pair type, since its type cannot be changed after construction.
+///
+/// `f` is still called `n` times, and the last result is dropped with the second component. So
+/// read the result by its value, e.g., with [`to_usize_counter`], rather than by counting the calls
+/// of `f` as [`to_usize`] does.
+pub fn pred_pair<T: Clone + 'static>(n: Church<(T, T)>) -> Church<T> {
+    Rc::new(move |f| {
+        let n = Rc::clone(&n);
+        Rc::new(move |x: T| {
+            let f = Rc::clone(&f);
+            let shift = Rc::new(move |(_, b): (T, T)| {
+                let next = f(b.clone());
+                (b, next)
+            });
+            n(shift)((x.clone(), x)).0
+        })
+    })
+}
+
 /// Implement a function to add two Church numerals.
 pub fn add<T: 'static>(n: Church<T>, m: Church<T>) -> Church<T> {
     Rc::new(move |f| {
@@ -50,6 +184,136 @@ pub fn add<T: 'static>(n: Church<T>, m: Church<T>) -> Church<T> {
     })
 }
 
+/// Subtracts `m` from `n`, truncating at zero (monus), by applying [`pred`] to `n` `m` times.
+pub fn sub<T: 'static>(n: Church<T>, m: Church<T>) -> Church<T> {
+    Rc::new(move |f| {
+        let n = Rc::clone(&n);
+        let m = Rc::clone(&m);
+        Rc::new(move |x| {
+            let (count, x) = count(&m, x);
+            let mut result = Rc::clone(&n);
+            for _ in 0..count {
+                result = pred(result);
+            }
+            result(Rc::clone(&f))(x)
+        })
+    })
+}
+
+/// Counts the applications in `n` by threading `x` through it unchanged.
+fn count<T: 'static>(n: &Church<T>, x: T) -> (usize, T) {
+    let count = Rc::new(Cell::new(0));
+    let c = Rc::clone(&count);
+    let x = n(Rc::new(move |y| {
+        c.set(c.get() + 1);
+        y
+    }))(x);
+    (count.get(), x)
+}
+
+/// Returns whether a Church numeral is zero.
+pub fn is_zero<T: 'static>(n: Church<T>) -> ChurchBool<T> {
+    Rc::new(move |a, b| {
+        let (count, a) = count(&n, a);
+        if count == 0 {
+            a
+        } else {
+            b
+        }
+    })
+}
+
+/// Returns whether `n` is less than or equal to `m`, i.e., whether `n - m` is zero.
+pub fn leq<T: 'static>(n: Church<T>, m: Church<T>) -> ChurchBool<T> {
+    is_zero(sub(n, m))
+}
+
+/// Returns whether `n` is equal to `m`, i.e., whether both `n - m` and `m - n` are zero.
+pub fn church_eq<T: 'static>(n: Church<T>, m: Church<T>) -> ChurchBool<T> {
+    let n_minus_m = sub(Rc::clone(&n), Rc::clone(&m));
+    let m_minus_n = sub(m, n);
+    Rc::new(move |a, b| {
+        let (count_nm, a) = count(&n_minus_m, a);
+        let (count_mn, a) = count(&m_minus_n, a);
+        if count_nm == 0 && count_mn == 0 {
+            a
+        } else {
+            b
+        }
+    })
+}
+
+/// Returns whether `n` is less than or equal to `m` as a bool.
+pub fn leq_bool(n: Church<bool>, m: Church<bool>) -> bool {
+    to_bool(leq(n, m))
+}
+
+/// Returns whether `n` is equal to `m` as a bool.
+pub fn eq_bool(n: Church<bool>, m: Church<bool>) -> bool {
+    to_bool(church_eq(n, m))
+}
+
+/// Divides `n` by `m` with repeated subtraction, threading `x` through the comparisons unchanged.
+///
+/// Returns the pair of the quotient and the remainder.
+fn long_division<T: 'static>(n: &Church<T>, m: &Church<T>, x: T) -> (ChurchPair<Church<T>>, T) {
+    let mut state = pair(zero(), Rc::clone(n));
+    let (divisor, x) = count(m, x);
+    let (bound, mut x) = count(n, x);
+    if divisor == 0 {
+        return (state, x);
+    }
+
+    // The quotient is at most `n`, so `n` steps suffice.
+    for _ in 0..bound {
+        let remainder = snd(Rc::clone(&state));
+        let (shortfall, y) = count(&sub(Rc::clone(m), Rc::clone(&remainder)), x);
+        x = y;
+        if shortfall > 0 {
+            break;
+        }
+        state = pair(succ(fst(Rc::clone(&state))), sub(remainder, Rc::clone(m)));
+    }
+    (state, x)
+}
+
+/// Returns the pair of the quotient and the remainder of `n` divided by `m`.
+///
+/// Dividing by zero gives the quotient zero and the remainder `n`.
+pub fn div_mod<T: 'static>(n: Church<T>, m: Church<T>) -> ChurchPair<Church<T>> {
+    let quotient: Church<T> = {
+        let n = Rc::clone(&n);
+        let m = Rc::clone(&m);
+        Rc::new(move |f| {
+            let n = Rc::clone(&n);
+            let m = Rc::clone(&m);
+            Rc::new(move |x| {
+                let (state, x) = long_division(&n, &m, x);
+                fst(state)(Rc::clone(&f))(x)
+            })
+        })
+    };
+    let remainder: Church<T> = Rc::new(move |f| {
+        let n = Rc::clone(&n);
+        let m = Rc::clone(&m);
+        Rc::new(move |x| {
+            let (state, x) = long_division(&n, &m, x);
+            snd(state)(Rc::clone(&f))(x)
+        })
+    });
+    pair(quotient, remainder)
+}
+
+/// Divides `n` by `m`, rounding down. Dividing by zero gives zero.
+pub fn div<T: 'static>(n: Church<T>, m: Church<T>) -> Church<T> {
+    fst(div_mod(n, m))
+}
+
+/// Returns the remainder of `n` divided by `m`. The remainder of dividing by zero is `n`.
+pub fn modulo<T: 'static>(n: Church<T>, m: Church<T>) -> Church<T> {
+    snd(div_mod(n, m))
+}
+
 /// Implement a function to multiply (mult) two Church numerals.
 pub fn mult<T: 'static>(n: Church<T>, m: Church<T>) -> Church<T> {
     Rc::new(move |f| {
@@ -59,6 +323,40 @@ pub fn mult<T: 'static>(n: Church<T>, m: Church<T>) -> Church<T> {
     })
 }
 
+/// Step function of [`prim_rec`], taking the counter and the accumulator.
+pub type ChurchStep<T> = Rc<dyn Fn(Church<T>, Church<T>) -> Church<T>>;
+
+/// Primitive recursion on Church numerals: returns `step(n - 1, ... step(1, step(0, base)))`.
+///
+/// The pair `(k, acc)` starts as `(0, base)` and each application of `n` replaces it with
+/// `(k + 1, step(k, acc))`. Since `n` can only apply functions on `T`, the pair is kept in a cell
+/// and `x` is threaded through unchanged.
+pub fn prim_rec<T: 'static>(n: Church<T>, base: Church<T>, step: ChurchStep<T>) -> Church<T> {
+    Rc::new(move |f| {
+        let n = Rc::clone(&n);
+        let base = Rc::clone(&base);
+        let step = Rc::clone(&step);
+        Rc::new(move |x| {
+            let state = Rc::new(RefCell::new(pair(zero(), Rc::clone(&base))));
+            let s = Rc::clone(&state);
+            let step = Rc::clone(&step);
+            let x = n(Rc::new(move |y| {
+                let p = Rc::clone(&s.borrow());
+                let k = fst(Rc::clone(&p));
+                *s.borrow_mut() = pair(succ(Rc::clone(&k)), step(k, snd(p)));
+                y
+            }))(x);
+            let result = snd(Rc::clone(&state.borrow()));
+            result(Rc::clone(&f))(x)
+        })
+    })
+}
+
+/// Returns the factorial of a Church numeral.
+pub fn factorial<T: 'static>(n: Church<T>) -> Church<T> {
+    prim_rec(n, one(), Rc::new(|k, acc| mult(succ(k), acc)))
+}
+
 /// Implement a function to raise one Church numeral to the power of another.
 /// This is the Church numeral equivalent of the natural number operation of exponentiation.
 /// Given two natural numbers `n` and `m`, the function should return a Church numeral
@@ -67,37 +365,30 @@ pub fn mult<T: 'static>(n: Church<T>, m: Church<T>) -> Church<T> {
 /// base). Note: This function should be implemented *WITHOUT* using the `to_usize` or any
 /// `pow`-like method.
 pub fn exp<T: 'static>(n: usize, m: usize) -> Church<T> {
-    // ACTION ITEM: Uncomment the following lines and replace `todo!()` with your code.
+    // `church_n` is itself a function on `Rc<dyn Fn(T) -> T>`, so `church_m` iterates it over that
+    // type: `m n` applies `n` `m` times.
     let church_n: Church<T> = from_usize(n);
-    let church_m: Church<T> = from_usize(m);
-    Rc::new(move |f| {
-        let f_m = Rc::clone(&church_m);
-        let f_n = Rc::clone(&church_n);
-        let mut result = f.clone();
-        for _ in 0..n {
-            result = f_n(result.clone());
-        }
-        result
-    })
+    let church_m: Church<Rc<dyn Fn(T) -> T>> = from_usize(m);
+    church_m(church_n)
 }
 
 /// Implement a function to convert a Church numeral to a usize type.
 pub fn to_usize<T: 'static + Default>(n: Church<T>) -> usize {
-    let count = Rc::new(RefCell::new(0));
-
-    let c = Rc::clone(&count);
-    let default_function: Rc<dyn Fn(T) -> T> = Rc::new(move |x| {
-        let mut count_mut = c.borrow_mut();
-        *count_mut += 1;
-        x
-    });
-
-    let result_function = n(default_function);
+    to_usize_with(n, T::default())
+}
 
-    let _unused = result_function(Default::default());
+/// Converts a Church numeral to a usize, passing `seed` to the numeral as the argument.
+///
+/// Unlike [`to_usize`], this works for numerals over types without a default value.
+pub fn to_usize_with<T: 'static>(n: Church<T>, seed: T) -> usize {
+    count(&n, seed).0
+}
 
-    let result = *count.borrow();
-    result
+/// Converts a Church numeral over usize to a usize by applying the successor function to 0.
+///
+/// This does not need a separate counter, since the argument itself counts the applications.
+pub fn to_usize_counter(n: Church<usize>) -> usize {
+    n(Rc::new(|count| count + 1))(0)
 }
 
 /// Implement a function to convert a usize type to a Church numeral.
@@ -0,0 +1,114 @@
+//! Church numerals with static dispatch.
+//!
+//! The numerals in [`church`](super::church) allocate an `Rc<dyn Fn>` for every application, which
+//! makes large numerals very slow. Here, a numeral is any type implementing [`ChurchFn`], and the
+//! combinators return `impl ChurchFn`, so applying a numeral is a chain of statically dispatched
+//! calls without any allocation.
+//!
+//! See `src/bin/church_bench.rs` for a comparison of the two encodings on numerals built from the
+//! same combinators.
+
+/// Church numerals that apply a given function `f` to `x` a fixed number of times.
+pub trait ChurchFn {
+    /// Applies `f` to `x` as many times as the number this numeral represents.
+    fn apply<T, F: Fn(T) -> T>(&self, f: &F, x: T) -> T;
+}
+
+impl<N: ChurchFn + ?Sized> ChurchFn for &N {
+    fn apply<T, F: Fn(T) -> T>(&self, f: &F, x: T) -> T {
+        (**self).apply(f, x)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Zero;
+
+impl ChurchFn for Zero {
+    fn apply<T, F: Fn(T) -> T>(&self, _f: &F, x: T) -> T {
+        x
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Succ<N>(N);
+
+impl<N: ChurchFn> ChurchFn for Succ<N> {
+    fn apply<T, F: Fn(T) -> T>(&self, f: &F, x: T) -> T {
+        f(self.0.apply(f, x))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Add<N, M>(N, M);
+
+impl<N: ChurchFn, M: ChurchFn> ChurchFn for Add<N, M> {
+    fn apply<T, F: Fn(T) -> T>(&self, f: &F, x: T) -> T {
+        self.1.apply(f, self.0.apply(f, x))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Mult<N, M>(N, M);
+
+impl<N: ChurchFn, M: ChurchFn> ChurchFn for Mult<N, M> {
+    fn apply<T, F: Fn(T) -> T>(&self, f: &F, x: T) -> T {
+        self.1.apply(&|y| self.0.apply(f, y), x)
+    }
+}
+
+/// Numeral whose number is only known at runtime.
+///
+/// The type of a composed numeral encodes its number, so this cannot be built from [`Succ`]s or
+/// [`Add`]s; it applies `f` in a plain loop instead.
+#[derive(Debug, Clone, Copy)]
+struct Repeat(usize);
+
+impl ChurchFn for Repeat {
+    fn apply<T, F: Fn(T) -> T>(&self, f: &F, x: T) -> T {
+        (0..self.0).fold(x, |x, _| f(x))
+    }
+}
+
+/// Returns the numeral for zero.
+pub fn zero() -> impl ChurchFn + Copy {
+    Zero
+}
+
+/// Returns the numeral for one.
+pub fn one() -> impl ChurchFn + Copy {
+    Succ(Zero)
+}
+
+/// Returns the numeral for two.
+pub fn two() -> impl ChurchFn + Copy {
+    Succ(Succ(Zero))
+}
+
+/// Adds 1 to a numeral.
+pub fn succ(n: impl ChurchFn) -> impl ChurchFn {
+    Succ(n)
+}
+
+/// Adds two numerals.
+pub fn add(n: impl ChurchFn, m: impl ChurchFn) -> impl ChurchFn {
+    Add(n, m)
+}
+
+/// Multiplies two numerals.
+pub fn mult(n: impl ChurchFn, m: impl ChurchFn) -> impl ChurchFn {
+    Mult(n, m)
+}
+
+/// Converts a usize to a numeral.
+///
+/// Unlike [`church::from_usize`](super::church::from_usize), the numeral is not a chain of
+/// [`succ`]s but applies `f` in a plain loop, since the type of a composed numeral depends on its
+/// number.
+pub fn from_usize(n: usize) -> impl ChurchFn + Copy {
+    Repeat(n)
+}
+
+/// Converts a numeral to a usize.
+pub fn to_usize(n: impl ChurchFn) -> usize {
+    n.apply(&|count| count + 1, 0)
+}
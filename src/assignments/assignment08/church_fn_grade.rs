@@ -0,0 +1,25 @@
+#[cfg(test)]
+mod test {
+    use crate::assignments::assignment08::church_fn::*;
+
+    #[test]
+    fn test_church_fn() {
+        assert_eq!(to_usize(zero()), 0);
+        assert_eq!(to_usize(one()), 1);
+        assert_eq!(to_usize(succ(two())), 3);
+        assert_eq!(to_usize(add(two(), from_usize(5))), 7);
+        assert_eq!(to_usize(mult(from_usize(3), from_usize(4))), 12);
+        assert_eq!(to_usize(mult(zero(), two())), 0);
+
+        let three = succ(two());
+        assert_eq!(three.apply(&|s: String| s + "a", String::new()), "aaa");
+        assert_eq!(to_usize(&three), 3);
+    }
+
+    #[test]
+    fn test_church_fn_large() {
+        const N: usize = 777_777;
+        assert_eq!(N, to_usize(from_usize(N)));
+        assert_eq!(to_usize(mult(from_usize(N), from_usize(100))), N * 100);
+    }
+}
@@ -1,5 +1,8 @@
 #[cfg(test)]
 mod test {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
     use rand::Rng;
 
     use crate::assignments::assignment08::church::*;
@@ -70,4 +73,203 @@ mod test {
             assert_eq!(to_usize(c_id(c_exp)), x.pow(y as u32));
         }
     }
+
+    #[test]
+    fn test_pred() {
+        assert_eq!(to_usize(pred(zero::<()>())), 0);
+        assert_eq!(to_usize(pred(one::<()>())), 0);
+        assert_eq!(to_usize(pred(two::<()>())), 1);
+        for n in 1..20 {
+            assert_eq!(to_usize(pred(from_usize::<()>(n))), n - 1);
+        }
+        assert_eq!(to_usize(pred(pred(from_usize::<()>(5)))), 3);
+
+        // Each application starts over from the given argument.
+        let c_four = pred(from_usize::<usize>(5));
+        let add_four = c_four(Rc::new(|x| x + 1));
+        assert_eq!(add_four(0), 4);
+        assert_eq!(add_four(10), 14);
+    }
+
+    #[test]
+    fn test_pred_pair() {
+        assert_eq!(to_usize_counter(pred_pair(zero())), 0);
+        assert_eq!(to_usize_counter(pred_pair(one())), 0);
+        assert_eq!(to_usize_counter(pred_pair(two())), 1);
+        for n in 1..10 {
+            assert_eq!(
+                to_usize_counter(pred_pair(from_usize::<(usize, usize)>(n))),
+                n - 1
+            );
+        }
+        let c_pairs = pred_pair(pred_pair(from_usize::<((usize, usize), (usize, usize))>(5)));
+        assert_eq!(to_usize_counter(c_pairs), 3);
+
+        // `f` is called once per application of `n`, and the last result is dropped.
+        let calls = Rc::new(Cell::new(0));
+        let c = Rc::clone(&calls);
+        let f: Rc<dyn Fn(usize) -> usize> = Rc::new(move |x| {
+            c.set(c.get() + 1);
+            x * 2
+        });
+        assert_eq!(pred_pair(from_usize::<(usize, usize)>(4))(f)(1), 8);
+        assert_eq!(calls.get(), 4);
+    }
+
+    #[test]
+    fn test_sub() {
+        for n in 0..10 {
+            for m in 0..10 {
+                let c_diff = sub(from_usize::<()>(n), from_usize(m));
+                assert_eq!(to_usize(c_diff), n.saturating_sub(m));
+            }
+        }
+
+        let c_diff = sub(from_usize::<String>(5), two());
+        let push_a = c_diff(Rc::new(|s| s + "a"));
+        assert_eq!(push_a("b".to_string()), "baaa");
+        assert_eq!(push_a(String::new()), "aaa");
+    }
+
+    #[test]
+    fn test_church_bool() {
+        assert!(to_bool(church_true()));
+        assert!(!to_bool(church_false()));
+        assert_eq!(church_true::<&str>()("yes", "no"), "yes");
+        assert_eq!(church_false::<&str>()("yes", "no"), "no");
+
+        assert!(to_bool(is_zero(zero())));
+        assert!(!to_bool(is_zero(one())));
+        assert!(to_bool(is_zero(pred(one()))));
+        assert_eq!(
+            is_zero::<String>(two())("zero".to_string(), "nonzero".to_string()),
+            "nonzero"
+        );
+    }
+
+    #[test]
+    fn test_leq_and_eq() {
+        for n in 0..8 {
+            for m in 0..8 {
+                assert_eq!(leq_bool(from_usize(n), from_usize(m)), n <= m);
+                assert_eq!(eq_bool(from_usize(n), from_usize(m)), n == m);
+            }
+        }
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..77 {
+            let x = rng.gen_range(0..=30);
+            let y = rng.gen_range(0..=30);
+            assert_eq!(leq_bool(from_usize(x), from_usize(y)), x <= y);
+            assert_eq!(eq_bool(from_usize(x), from_usize(y)), x == y);
+        }
+    }
+
+    #[test]
+    fn test_church_pair() {
+        let p = pair(1, 2);
+        assert_eq!(fst(p.clone()), 1);
+        assert_eq!(snd(p), 2);
+
+        let p = pair(two::<()>(), zero());
+        assert_eq!(to_usize(fst(p.clone())), 2);
+        assert_eq!(to_usize(snd(p)), 0);
+    }
+
+    #[test]
+    fn test_div_and_modulo() {
+        for n in 0..16 {
+            for m in 1..6 {
+                assert_eq!(to_usize(div(from_usize::<()>(n), from_usize(m))), n / m);
+                assert_eq!(to_usize(modulo(from_usize::<()>(n), from_usize(m))), n % m);
+            }
+        }
+
+        assert_eq!(to_usize(div(from_usize::<()>(7), zero())), 0);
+        assert_eq!(to_usize(modulo(from_usize::<()>(7), zero())), 7);
+
+        let p = div_mod(from_usize::<usize>(17), from_usize(5));
+        assert_eq!(fst(p.clone())(Rc::new(|x| x + 1))(0), 3);
+        assert_eq!(snd(p)(Rc::new(|x| x * 2))(1), 4);
+    }
+
+    #[test]
+    fn test_exp() {
+        assert_eq!(to_usize(exp::<()>(0, 0)), 1);
+        for n in 1..6 {
+            assert_eq!(to_usize(exp::<()>(n, 0)), 1);
+        }
+        for m in 1..6 {
+            assert_eq!(to_usize(exp::<()>(0, m)), 0);
+        }
+        for n in 1..5 {
+            for m in 1..5 {
+                assert_eq!(to_usize(exp::<()>(n, m)), n.pow(m as u32));
+            }
+        }
+        assert_eq!(exp::<usize>(2, 10)(Rc::new(|x| x + 1))(0), 1024);
+    }
+
+    #[test]
+    fn test_church_list() {
+        fn to_vec(l: ChurchList<i32, Vec<i32>>) -> Vec<i32> {
+            fold(
+                l,
+                Rc::new(|x, mut v: Vec<i32>| {
+                    v.insert(0, x);
+                    v
+                }),
+                Vec::new(),
+            )
+        }
+
+        assert_eq!(to_vec(nil()), Vec::<i32>::new());
+        assert_eq!(to_vec(cons(1, cons(2, nil()))), vec![1, 2]);
+
+        let l = list_from_vec(vec![1, 2, 3]);
+        assert_eq!(fold(l.clone(), Rc::new(|x, r| x + r), 0), 6);
+        assert_eq!(fold(l, Rc::new(|x, r| x - r), 0), 2);
+
+        let l = map(list_from_vec(vec![1, 2, 3]), Rc::new(|x| x * 10));
+        assert_eq!(to_vec(l), vec![10, 20, 30]);
+
+        let l = append(list_from_vec(vec![1, 2]), list_from_vec(vec![3]));
+        assert_eq!(to_vec(l), vec![1, 2, 3]);
+
+        assert_eq!(to_usize(length::<&str, ()>(nil())), 0);
+        let l = append(list_from_vec(vec!["a", "b"]), list_from_vec(vec!["c"]));
+        assert_eq!(to_usize(length::<_, ()>(map(l, Rc::new(str::len)))), 3);
+    }
+
+    #[test]
+    fn test_factorial() {
+        let expected = [1, 1, 2, 6, 24, 120, 720];
+        for (n, expected) in expected.into_iter().enumerate() {
+            assert_eq!(to_usize(factorial(from_usize::<()>(n))), expected);
+        }
+
+        let double = prim_rec(
+            from_usize::<()>(5),
+            zero(),
+            Rc::new(|_, acc| succ(succ(acc))),
+        );
+        assert_eq!(to_usize(double), 10);
+        let counter = prim_rec(from_usize::<()>(4), zero(), Rc::new(|k, _| succ(k)));
+        assert_eq!(to_usize(counter), 4);
+    }
+
+    #[test]
+    fn test_to_usize_with() {
+        #[derive(Debug)]
+        struct NoDefault(i32);
+
+        let c_three = succ(two::<NoDefault>());
+        assert_eq!(to_usize_with(c_three, NoDefault(0)), 3);
+        assert_eq!(to_usize_with(zero(), NoDefault(1)), 0);
+        assert_eq!(to_usize_with(from_usize::<&str>(10), "seed"), 10);
+
+        assert_eq!(to_usize_counter(zero()), 0);
+        assert_eq!(to_usize_counter(from_usize(42)), 42);
+        assert_eq!(to_usize_counter(mult(from_usize(6), from_usize(7))), 42);
+    }
 }
@@ -13,7 +13,9 @@
 //! and submit the generated `assignment08.zip` file in `target` directory.
 
 pub mod church;
+pub mod church_fn;
 pub mod small_exercises;
 
+mod church_fn_grade;
 mod church_grade;
 mod small_exercises_grade;
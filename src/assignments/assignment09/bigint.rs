@@ -4,6 +4,8 @@ use std::fmt;
 use std::iter::zip;
 use std::ops::*;
 
+use ndarray_rand::rand_distr::num_traits::CheckedAdd;
+
 /// An signed integer with infinite precision implemented with an "carrier" vector of `u32`s.
 ///
 /// The vector is interpreted as a base 2^(32 * (len(carrier) - 1)) integer, where negative
@@ -52,6 +54,15 @@ impl BigInt {
     }
 }
 
+impl From<u64> for BigInt {
+    fn from(n: u64) -> Self {
+        BigInt {
+            carrier: vec![0, (n >> 32) as u32, n as u32],
+        }
+        .truncate()
+    }
+}
+
 const SIGN_MASK: u32 = 1 << 31;
 
 impl BigInt {
@@ -209,6 +220,23 @@ impl Sub for BigInt {
     }
 }
 
+impl CheckedAdd for BigInt {
+    /// Never overflows.
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(BigInt::add_raw(self, rhs))
+    }
+}
+
+impl PartialEq for BigInt {
+    /// Compares the values, regardless of the carriers' lengths.
+    fn eq(&self, other: &Self) -> bool {
+        let len = self.carrier.len().max(other.carrier.len());
+        self.sign_extension(len).carrier == other.sign_extension(len).carrier
+    }
+}
+
+impl Eq for BigInt {}
+
 impl fmt::Display for BigInt {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Hex formatting so that each u32 can be formatted independently.
@@ -101,4 +101,19 @@ mod test {
 
         // TODO: add a test case testing sign extension.
     }
+
+    #[test]
+    fn test_inf_prec_eq() {
+        assert_eq!(BigInt::new(5), BigInt::new_large(vec![0, 0, 5]));
+        assert_eq!(
+            BigInt::new_large(vec![u32::MAX - 1]),
+            BigInt::new_large(vec![u32::MAX, u32::MAX - 1])
+        );
+        assert_ne!(BigInt::new(5), BigInt::new(6));
+        assert_ne!(
+            BigInt::new_large(vec![u32::MAX]),
+            BigInt::new_large(vec![0, u32::MAX])
+        );
+        assert_eq!(BigInt::new(1) + BigInt::new(1), BigInt::new(2));
+    }
 }
@@ -1,13 +1,21 @@
 //! Small exercises.
 
-use std::{collections::HashMap, f64};
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::{f64, iter};
 
 use itertools::Itertools;
+use ndarray_rand::rand_distr::num_traits::CheckedAdd;
+use rayon::prelude::*;
+
+use crate::assignments::assignment06::semiring::Semiring;
+use crate::assignments::assignment07::my_itertools::MyIterTools;
 
 /// Returns whether the given sequence is a fibonacci sequence starts from the given sequence's
 /// first two terms.
 ///
-/// Returns `true` if the length of sequence is less or equal than 2.
+/// Returns `true` if the length of sequence is less or equal than 2. Returns `false` if the sum of
+/// two terms overflows `T`, as the next term cannot be equal to it.
 ///
 /// # Example
 ///
@@ -16,29 +24,53 @@ use itertools::Itertools;
 ///
 /// assert_eq!(is_fibonacci([1, 1, 2, 3, 5, 8, 13].into_iter()), true);
 /// assert_eq!(is_fibonacci([1, 1, 2, 3, 5, 8, 14].into_iter()), false);
+/// assert_eq!(is_fibonacci([100u8, 100, 200].into_iter()), true);
+/// assert_eq!(is_fibonacci([100u8, 100, 200, 44].into_iter()), false);
 /// ```
-pub fn is_fibonacci(inner: impl Iterator<Item = i64>) -> bool {
-    let mut first = 0;
-    let mut second = 0;
-    for (i, x) in inner.enumerate() {
-        if i == 0 {
-            first = x;
-        } else if i == 1 {
-            second = x;
-        } else {
-            let next = first + second;
-            if next != x {
-                return false;
+pub fn is_fibonacci<T: CheckedAdd + PartialEq>(mut inner: impl Iterator<Item = T>) -> bool {
+    let (Some(mut first), Some(mut second)) = (inner.next(), inner.next()) else {
+        return true;
+    };
+    for x in inner {
+        match first.checked_add(&second) {
+            Some(next) if next == x => {
+                first = second;
+                second = next;
             }
-            first = second;
-            second = next
+            _ => return false,
         }
     }
     true
 }
 
+/// Returns whether the given sequence is approximately a fibonacci sequence starts from the given
+/// sequence's first two terms.
+///
+/// Each term may differ from the sum of the previous two terms by up to `ratio_tolerance` times
+/// the sum. The terms are checked against the sums of the given terms, so the errors do not
+/// accumulate.
+///
+/// Returns `true` if the length of sequence is less or equal than 2.
+///
+/// # Example
+///
+/// ```
+/// use cs220::assignments::assignment09::small_exercises::*;
+///
+/// assert_eq!(is_fibonacci_like([0.1, 0.2, 0.3, 0.5].into_iter(), 1e-9), true);
+/// assert_eq!(is_fibonacci_like([1.0, 1.0, 2.1].into_iter(), 0.01), false);
+/// assert_eq!(is_fibonacci_like([1.0, 1.0, 2.1].into_iter(), 0.1), true);
+/// ```
+pub fn is_fibonacci_like(inner: impl Iterator<Item = f64>, ratio_tolerance: f64) -> bool {
+    inner.tuple_windows().all(|(first, second, x)| {
+        (x - (first + second)).abs() <= ratio_tolerance * (first + second).abs()
+    })
+}
+
 /// Returns the sum of `f(v)` for all element `v` the given array.
 ///
+/// The sum is taken in any [`Semiring`], e.g. integers or polynomials.
+///
 /// # Example
 ///
 /// ```
@@ -47,14 +79,36 @@ pub fn is_fibonacci(inner: impl Iterator<Item = i64>) -> bool {
 /// assert_eq!(sigma([1, 2].into_iter(), |x| x + 2), 7);
 /// assert_eq!(sigma([1, 2].into_iter(), |x| x * 4), 12);
 /// ```
-pub fn sigma<T, F: Fn(T) -> i64>(inner: impl Iterator<Item = T>, f: F) -> i64 {
-    let mut sum = 0;
+pub fn sigma<T, R: Semiring, F: Fn(T) -> R>(inner: impl Iterator<Item = T>, f: F) -> R {
+    let mut sum = R::zero();
     for x in inner {
-        sum += f(x);
+        sum = R::add(&sum, &f(x));
     }
     sum
 }
 
+/// Like [`sigma`], but evaluates `f` and adds up the results in parallel.
+///
+/// Worthwhile when `f` is expensive.
+///
+/// # Example
+///
+/// ```
+/// use cs220::assignments::assignment09::small_exercises::*;
+///
+/// assert_eq!(par_sigma(1..=100u64, |x| x * x), 338_350);
+/// ```
+pub fn par_sigma<T, R, F>(inner: impl IntoParallelIterator<Item = T>, f: F) -> R
+where
+    R: Semiring + Send,
+    F: Fn(T) -> R + Sync + Send,
+{
+    inner
+        .into_par_iter()
+        .map(f)
+        .reduce(R::zero, |lhs, rhs| R::add(&lhs, &rhs))
+}
+
 /// Alternate elements from three iterators until they have run out.
 ///
 /// You can assume that the number of elements of three iterators are same.
@@ -100,9 +154,37 @@ pub fn interleave3<T>(
     merge
 }
 
+// Round-robin over an array of iterators, skipping the exhausted ones
+struct InterleaveN<I, const N: usize> {
+    iters: [I; N],
+    exhausted: [bool; N],
+    // Index of the iterator to take the next element from
+    next: usize,
+}
+
+impl<I: Iterator, const N: usize> Iterator for InterleaveN<I, N> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for _ in 0..N {
+            let i = self.next;
+            self.next = (i + 1) % N;
+            if self.exhausted[i] {
+                continue;
+            }
+            match self.iters[i].next() {
+                Some(item) => return Some(item),
+                None => self.exhausted[i] = true,
+            }
+        }
+        None
+    }
+}
+
 /// Alternate elements from array of n iterators until they have run out.
 ///
-/// You can assume that the number of elements of iterators are same.
+/// The elements are taken lazily, so the iterators may be infinite. If some iterators run out
+/// earlier, the others continue in turn.
 ///
 /// # Example
 ///
@@ -110,33 +192,61 @@ pub fn interleave3<T>(
 /// use cs220::assignments::assignment09::small_exercises::*;
 ///
 /// assert_eq!(
-///     interleave_n(&mut [[1, 2].into_iter(), [3, 4].into_iter(), [5, 6].into_iter()]),
+///     interleave_n([[1, 2].into_iter(), [3, 4].into_iter(), [5, 6].into_iter()])
+///         .collect::<Vec<_>>(),
 ///     vec![1, 3, 5, 2, 4, 6]
 /// );
+/// assert_eq!(
+///     interleave_n([vec![1].into_iter(), vec![2, 3, 4].into_iter()]).collect::<Vec<_>>(),
+///     vec![1, 2, 3, 4]
+/// );
 /// ```
-pub fn interleave_n<T, const N: usize>(
-    mut iters: [impl Iterator<Item = T>; N],
-) -> impl Iterator<Item = T> {
-    let mut merge: Vec<T> = Vec::new();
-    let mut err = 0;
-    loop {
-        for iter in iters.iter_mut() {
-            if let Some(x) = iter.next() {
-                merge.push(x);
-            } else {
-                err += 1;
-            }
-        }
+pub fn interleave_n<I: Iterator, const N: usize>(iters: [I; N]) -> impl Iterator<Item = I::Item> {
+    InterleaveN {
+        iters,
+        exhausted: [false; N],
+        next: 0,
+    }
+}
 
-        if err >= N {
-            break;
+/// Alternate elements from a runtime collection of iterators until they have run out, like
+/// [`interleave_n`].
+///
+/// # Example
+///
+/// ```
+/// use cs220::assignments::assignment09::small_exercises::*;
+///
+/// let iters: Vec<Box<dyn Iterator<Item = i32>>> = vec![
+///     Box::new(vec![1, 2, 3].into_iter()),
+///     Box::new(4..5),
+///     Box::new([5, 6].into_iter()),
+/// ];
+/// assert_eq!(interleave_dyn(iters).collect::<Vec<_>>(), vec![1, 4, 5, 2, 6, 3]);
+/// ```
+pub fn interleave_dyn<'a, T: 'a>(
+    iters: Vec<Box<dyn Iterator<Item = T> + 'a>>,
+) -> impl Iterator<Item = T> + 'a {
+    // The iterators that are not exhausted yet, in turn
+    let mut queue = VecDeque::from(iters);
+    iter::from_fn(move || {
+        while let Some(mut iter) = queue.pop_front() {
+            if let Some(item) = iter.next() {
+                queue.push_back(iter);
+                return Some(item);
+            }
         }
-    }
-    merge.into_iter()
+        None
+    })
 }
 
 /// Returns mean of k smallest value's mean.
 ///
+/// It uses [`MyIterTools::my_k_smallest`], which takes `O(n log k)` time and `O(k)` memory for `n`
+/// values.
+///
+/// Returns `None` if `k` is zero or greater than the number of values.
+///
 /// # Example
 ///
 /// ```
@@ -144,17 +254,26 @@ pub fn interleave_n<T, const N: usize>(
 ///
 /// assert_eq!(
 ///     k_smallest_mean(vec![1, 3, 2].into_iter(), 2),
-///     ((1 + 2) as f64 / 2.0)
+///     Some((1 + 2) as f64 / 2.0)
 /// );
 /// assert_eq!(
 ///     k_smallest_mean(vec![7, 5, 3, 6].into_iter(), 3),
-///     ((3 + 5 + 6) as f64 / 3.0)
+///     Some((3 + 5 + 6) as f64 / 3.0)
 /// );
+/// assert_eq!(k_smallest_mean(vec![7, 5].into_iter(), 3), None);
 /// ```
-pub fn k_smallest_mean(inner: impl Iterator<Item = i64>, k: usize) -> f64 {
-    let mut sort = inner.sorted();
-    let sum = sort.by_ref().take(k).sum::<i64>();
-    sum as f64 / k as f64
+pub fn k_smallest_mean(inner: impl Iterator<Item = i64>, k: usize) -> Option<f64> {
+    if k == 0 {
+        return None;
+    }
+
+    let smallest = inner.my_k_smallest(k);
+    if smallest.len() < k {
+        return None;
+    }
+    // The sum may overflow `i64`.
+    let sum = smallest.map(i128::from).sum::<i128>();
+    Some(sum as f64 / k as f64)
 }
 
 /// Returns mean for each class.
@@ -184,21 +303,122 @@ pub fn k_smallest_mean(inner: impl Iterator<Item = i64>, k: usize) -> f64 {
 /// );
 /// ```
 pub fn calculate_mean(inner: impl Iterator<Item = (String, i64)>) -> HashMap<String, f64> {
-    let mut process: HashMap<String, (i64, usize)> = HashMap::new();
-    let mut result: HashMap<String, f64> = HashMap::new();
-    for (courses, marks) in inner {
-        let _unused = process
-            .entry(courses)
-            .and_modify(|x| {
-                x.0 += marks;
-                x.1 += 1;
-            })
-            .or_insert((marks, 1));
+    class_statistics(inner)
+        .into_iter()
+        .map(|(class, stats)| (class, stats.mean))
+        .collect()
+}
+
+/// Statistics of the marks of a class.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClassStatistics {
+    /// Number of marks.
+    pub count: usize,
+    /// Mean of the marks.
+    pub mean: f64,
+    /// Lowest mark.
+    pub min: i64,
+    /// Highest mark.
+    pub max: i64,
+    /// Population standard deviation of the marks.
+    pub std_dev: f64,
+    /// Median of the marks, the mean of the two middle marks if `count` is even.
+    pub median: f64,
+}
+
+/// Running statistics of a class, updated one mark at a time.
+#[derive(Debug)]
+struct StatisticsAccumulator {
+    count: usize,
+    mean: f64,
+    /// Sum of squared differences from the current mean (Welford's algorithm).
+    m2: f64,
+    min: i64,
+    max: i64,
+    /// Kept for the median, which cannot be computed in a single pass.
+    marks: Vec<i64>,
+}
+
+impl StatisticsAccumulator {
+    fn new(mark: i64) -> Self {
+        Self {
+            count: 1,
+            mean: mark as f64,
+            m2: 0.0,
+            min: mark,
+            max: mark,
+            marks: vec![mark],
+        }
     }
-    for tmp in process {
-        let _unused = result.insert(tmp.0, tmp.1 .0 as f64 / tmp.1 .1 as f64);
+
+    fn push(&mut self, mark: i64) {
+        self.count += 1;
+        let delta = mark as f64 - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (mark as f64 - self.mean);
+        self.min = self.min.min(mark);
+        self.max = self.max.max(mark);
+        self.marks.push(mark);
+    }
+
+    fn finish(mut self) -> ClassStatistics {
+        let mid = self.count / 2;
+        let (lower, &mut upper, _) = self.marks.select_nth_unstable(mid);
+        let median = if self.count % 2 == 0 {
+            // The largest of the lower half is the other middle mark.
+            let lower = lower.iter().max().copied().unwrap_or(upper);
+            (lower as f64 + upper as f64) / 2.0
+        } else {
+            upper as f64
+        };
+        ClassStatistics {
+            count: self.count,
+            mean: self.mean,
+            min: self.min,
+            max: self.max,
+            std_dev: (self.m2 / self.count as f64).sqrt(),
+            median,
+        }
     }
-    result
+}
+
+/// Returns the [`ClassStatistics`] of each class, given the pairs of class and mark.
+///
+/// # Example
+///
+/// ```
+/// use cs220::assignments::assignment09::small_exercises::*;
+///
+/// let stats = class_statistics(
+///     [
+///         ("CS100".to_string(), 60),
+///         ("CS200".to_string(), 60),
+///         ("CS200".to_string(), 100),
+///         ("CS200".to_string(), 80),
+///     ]
+///     .into_iter(),
+/// );
+/// let cs200 = stats["CS200"];
+/// assert_eq!(cs200.count, 3);
+/// assert_eq!(cs200.mean, 80.0);
+/// assert_eq!((cs200.min, cs200.max), (60, 100));
+/// assert_eq!(cs200.median, 80.0);
+/// assert!((cs200.std_dev - 16.329931618554522).abs() < 1e-9);
+/// ```
+pub fn class_statistics(
+    inner: impl Iterator<Item = (String, i64)>,
+) -> HashMap<String, ClassStatistics> {
+    let mut accumulators: HashMap<String, StatisticsAccumulator> = HashMap::new();
+    for (class, mark) in inner {
+        let _unused = accumulators
+            .entry(class)
+            .and_modify(|acc| acc.push(mark))
+            .or_insert_with(|| StatisticsAccumulator::new(mark));
+    }
+    accumulators
+        .into_iter()
+        .map(|(class, acc)| (class, acc.finish()))
+        .collect()
 }
 
 /// Among the cartesian product of input vectors, return the number of sets whose sum equals `n`.
@@ -220,21 +440,48 @@ pub fn calculate_mean(inner: impl Iterator<Item = (String, i64)>) -> HashMap<Str
 /// assert_eq!(sum_is_n(vec![vec![1, 2, 3], vec![2, 3]], 2), 0);
 /// ```
 pub fn sum_is_n(inner: Vec<Vec<i64>>, n: i64) -> usize {
-    let mut sum_vec: Vec<i64> = Vec::new();
-    for (i, vec) in inner.iter().enumerate() {
-        if i == 0 {
-            sum_vec = vec.to_vec()
-        } else {
-            let mut tmp: Vec<i64> = Vec::new();
-            for item in &sum_vec {
-                for jtem in vec {
-                    tmp.push(item + jtem);
-                }
+    sum_count_distribution(inner)
+        .get(&n)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Among the cartesian product of input vectors, returns the number of sets for each sum.
+///
+/// Sums that no set adds up to are absent. If `inner` is empty, so is the result.
+///
+/// Only the number of sets per sum is kept while adding the vectors one by one, so the cost is
+/// bounded by the number of distinct sums rather than the size of the cartesian product.
+///
+/// # Example
+///
+/// ```
+/// use cs220::assignments::assignment09::small_exercises::*;
+///
+/// assert_eq!(
+///     sum_count_distribution(vec![vec![1, 2, 3], vec![2, 3]]),
+///     [(3, 1), (4, 2), (5, 2), (6, 1)].into_iter().collect()
+/// );
+/// ```
+pub fn sum_count_distribution(inner: Vec<Vec<i64>>) -> HashMap<i64, usize> {
+    let mut vecs = inner.into_iter();
+    let Some(first) = vecs.next() else {
+        return HashMap::new();
+    };
+    let mut counts: HashMap<i64, usize> = HashMap::new();
+    for item in first {
+        *counts.entry(item).or_default() += 1;
+    }
+    for vec in vecs {
+        let mut next: HashMap<i64, usize> = HashMap::new();
+        for (sum, count) in &counts {
+            for item in &vec {
+                *next.entry(sum + item).or_default() += count;
             }
-            sum_vec = tmp
         }
+        counts = next;
     }
-    sum_vec.iter().filter(|&&x| x == n).count()
+    counts
 }
 
 /// Returns a new vector that contains the item that appears `n` times in the input vector in
@@ -251,18 +498,53 @@ pub fn sum_is_n(inner: Vec<Vec<i64>>, n: i64) -> usize {
 /// assert_eq!(find_count_n(vec![1, 2, 3, 4, 4], 1), vec![1, 2, 3]);
 /// ```
 pub fn find_count_n(inner: Vec<usize>, n: usize) -> Vec<usize> {
-    let mut proc: HashMap<usize, usize> = HashMap::new();
-    for i in inner {
-        let _unused = proc.entry(i).and_modify(|count| *count += 1).or_insert(1);
-    }
-    let mut result = Vec::new();
-    for hm in proc {
-        if hm.1 == n {
-            result.push(hm.0);
-        }
+    count_occurrences(inner.into_iter())
+        .into_iter()
+        .filter(|(_, count)| *count == n)
+        .map(|(item, _)| item)
+        .sorted()
+        .collect()
+}
+
+/// Returns a new vector that contains the item that appears at least `n` times in the input vector
+/// in increasing order.
+///
+/// # Example
+///
+/// ```
+/// use cs220::assignments::assignment09::small_exercises::*;
+///
+/// assert_eq!(find_count_at_least(vec![1, 3, 3], 1), vec![1, 3]);
+/// assert_eq!(find_count_at_least(vec![1, 3, 3, 2, 2, 2], 2), vec![2, 3]);
+/// assert!(find_count_at_least(vec![1, 3, 3], 3).is_empty());
+/// ```
+pub fn find_count_at_least(inner: Vec<usize>, n: usize) -> Vec<usize> {
+    count_occurrences(inner.into_iter())
+        .into_iter()
+        .filter(|(_, count)| *count >= n)
+        .map(|(item, _)| item)
+        .sorted()
+        .collect()
+}
+
+/// Returns how many times each item appears in the given iterator.
+///
+/// # Example
+///
+/// ```
+/// use cs220::assignments::assignment09::small_exercises::*;
+///
+/// assert_eq!(
+///     count_occurrences("abca".chars()),
+///     [('a', 2), ('b', 1), ('c', 1)].into_iter().collect()
+/// );
+/// ```
+pub fn count_occurrences<T: Hash + Eq>(inner: impl Iterator<Item = T>) -> HashMap<T, usize> {
+    let mut counts = HashMap::new();
+    for item in inner {
+        *counts.entry(item).or_default() += 1;
     }
-    result.sort();
-    result
+    counts
 }
 
 /// Return the position of the median element in the vector.
@@ -285,22 +567,42 @@ pub fn find_count_n(inner: Vec<usize>, n: usize) -> Vec<usize> {
 /// assert_eq!(position_median(vec![1, 3, 3, 6, 7, 8, 9]), Some(3));
 /// assert_eq!(position_median(vec![1, 3, 3, 3]), Some(1));
 /// ```
-pub fn position_median<T: Ord + Clone>(inner: Vec<T>) -> Option<usize> {
-    if inner.is_empty() {
+pub fn position_median<T: Ord>(inner: Vec<T>) -> Option<usize> {
+    // For both odd and even, the required median is at 0-based index n/2
+    // (since for even you want the upper median).
+    let k = inner.len() / 2;
+    position_kth(inner, k)
+}
+
+/// Return the position of the `k`-th smallest element in the vector, counting from 0.
+///
+/// - If `k` is out of bounds, returns `None`.
+/// - If several elements are equally `k`-th smallest, the position of the first of them is
+///   returned.
+///
+/// Runs in linear time on average.
+///
+/// # Example
+///
+/// ```
+/// use cs220::assignments::assignment09::small_exercises::*;
+///
+/// assert_eq!(position_kth(vec![5, 1, 4, 1, 3], 0), Some(1));
+/// assert_eq!(position_kth(vec![5, 1, 4, 1, 3], 1), Some(1));
+/// assert_eq!(position_kth(vec![5, 1, 4, 1, 3], 4), Some(0));
+/// assert_eq!(position_kth(vec![5, 1, 4, 1, 3], 5), None);
+/// ```
+pub fn position_kth<T: Ord>(inner: Vec<T>, k: usize) -> Option<usize> {
+    if k >= inner.len() {
         return None;
     }
 
-    let n = inner.len();
-    // Clone to sort and find the median value
-    let mut sorted = inner.clone();
-    sorted.sort();
-
-    // For both odd and even, the required median is at 0-based index n/2
-    // (since for even you want the upper median).
-    let median_value = &sorted[n / 2];
+    // Select over the indices so that the elements are neither moved nor cloned.
+    let mut indices = (0..inner.len()).collect::<Vec<_>>();
+    let (_, &mut kth, _) = indices.select_nth_unstable_by(k, |&i, &j| inner[i].cmp(&inner[j]));
 
-    // Find the first position of that median value in the original vector
-    inner.iter().position(|x| x == median_value)
+    // Find the first position of that value in the original vector
+    inner.iter().position(|x| *x == inner[kth])
 }
 
 /// Returns the sum of all elements in a two-dimensional array.
@@ -327,17 +629,34 @@ pub fn two_dimensional_sum(inner: impl Iterator<Item = impl Iterator<Item = i64>
 ///
 /// Consult <https://en.wikipedia.org/wiki/Palindrome>.
 pub fn is_palindrome(s: String) -> bool {
-    if s.len() <= 1 {
-        true
-    } else {
-        let mut tmp = s.clone();
-        while tmp.len() >= 2 {
-            let a = tmp.remove(0);
-            let b = tmp.pop().unwrap_or_default();
-            if a != b {
-                return false;
-            }
+    is_palindrome_chars(s.chars())
+}
+
+/// Returns whether the given string is palindrome or not, considering only alphanumeric
+/// characters and ignoring case.
+///
+/// # Example
+///
+/// ```
+/// use cs220::assignments::assignment09::small_exercises::*;
+///
+/// assert!(is_palindrome_alphanumeric("A man, a plan, a canal: Panama!"));
+/// assert!(!is_palindrome("A man, a plan, a canal: Panama!".to_string()));
+/// ```
+pub fn is_palindrome_alphanumeric(s: &str) -> bool {
+    is_palindrome_chars(
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase),
+    )
+}
+
+/// Compares the characters from both ends toward the middle.
+fn is_palindrome_chars(mut chars: impl DoubleEndedIterator<Item = char>) -> bool {
+    while let (Some(front), Some(back)) = (chars.next(), chars.next_back()) {
+        if front != back {
+            return false;
         }
-        true
     }
+    true
 }
@@ -3,6 +3,8 @@ mod test {
 
     use ntest::{assert_false, assert_true};
 
+    use crate::assignments::assignment06::semiring::{Polynomial, Semiring};
+    use crate::assignments::assignment09::bigint::BigInt;
     use crate::assignments::assignment09::small_exercises::*;
 
     #[test]
@@ -14,7 +16,7 @@ mod test {
         assert!(is_fibonacci([0, 0, 0, 0, 0].into_iter()));
         assert!(is_fibonacci([1, 1].into_iter()));
         assert!(is_fibonacci([1].into_iter()));
-        assert!(is_fibonacci([].into_iter()));
+        assert!(is_fibonacci(std::iter::empty::<i64>()));
 
         assert!(!is_fibonacci([1, 1, 2, 2, 3, 3].into_iter()));
         assert!(!is_fibonacci([0, 0, 0, 0, 1].into_iter()));
@@ -22,6 +24,42 @@ mod test {
         assert!(!is_fibonacci([4, 3, 2, 1].into_iter()));
     }
 
+    #[test]
+    fn test_is_fibonacci_overflow() {
+        assert!(is_fibonacci([u64::MAX - 1, 1, u64::MAX].into_iter()));
+        assert!(!is_fibonacci([u64::MAX - 1, 1, u64::MAX, 0].into_iter()));
+        assert!(!is_fibonacci([i64::MIN, -1, i64::MAX].into_iter()));
+        assert!(!is_fibonacci([i8::MAX, i8::MAX, -2].into_iter()));
+
+        // The 100th fibonacci number does not fit in `u64`.
+        let mut fib = vec![BigInt::new(0), BigInt::new(1)];
+        for i in 2..100 {
+            let next = fib[i - 2].clone() + fib[i - 1].clone();
+            fib.push(next);
+        }
+        assert!(is_fibonacci(fib.clone().into_iter()));
+        fib[99] = fib[98].clone();
+        assert!(!is_fibonacci(fib.into_iter()));
+    }
+
+    #[test]
+    fn test_is_fibonacci_like() {
+        let golden = (1.0 + 5.0f64.sqrt()) / 2.0;
+        assert!(is_fibonacci_like((0..50).map(|i| golden.powi(i)), 1e-12));
+        assert!(is_fibonacci_like(
+            [0.1, 0.2, 0.3, 0.5, 0.8].into_iter(),
+            1e-12
+        ));
+        assert!(is_fibonacci_like([0.0, 0.0, 0.0].into_iter(), 0.0));
+        assert!(is_fibonacci_like([1.5].into_iter(), 0.0));
+        assert!(is_fibonacci_like(std::iter::empty(), 0.0));
+        assert!(is_fibonacci_like([-1.0, -1.0, -2.015].into_iter(), 0.01));
+
+        assert!(!is_fibonacci_like([-1.0, -1.0, -2.03].into_iter(), 0.01));
+        assert!(!is_fibonacci_like([1.0, 1.0, 2.0, 2.0].into_iter(), 0.1));
+        assert!(!is_fibonacci_like([0.0, 0.0, 1e-300].into_iter(), 0.5));
+    }
+
     #[test]
     fn test_sigma() {
         assert_eq!(sigma([].into_iter(), |x: i64| x * 2), 0);
@@ -49,6 +87,30 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_sigma_semiring() {
+        assert_eq!(sigma([1u64, 2, 3].into_iter(), |x| x * x), 14u64);
+        assert_eq!(sigma([0.5, 1.5].into_iter(), |x: f64| x * 2.0), 4.0);
+
+        // (x + 1) + (x + 2) + (x + 3) = 3x + 6
+        let poly: Polynomial<i64> = sigma(1..=3, |c| Polynomial::x().add(&Polynomial::term(c, 0)));
+        assert_eq!(poly, Polynomial::term(3, 1).add(&Polynomial::term(6, 0)));
+    }
+
+    #[test]
+    fn test_par_sigma() {
+        assert_eq!(par_sigma(Vec::<i64>::new(), |x| x), 0);
+        assert_eq!(par_sigma(vec![1, 2, 3], |x: i64| x * 5), 30);
+        assert_eq!(
+            par_sigma(0..10_000u64, |x| x % 7),
+            sigma(0..10_000u64, |x| x % 7)
+        );
+
+        // 1 + x + x^2 + ... + x^9
+        let poly: Polynomial<u64> = par_sigma(0..10u64, |n| Polynomial::term(1, n));
+        assert_eq!(poly.eval(2), 1023);
+    }
+
     #[test]
     fn test_interleave3() {
         assert_eq!(
@@ -106,34 +168,100 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_interleave_n_lazy() {
+        // Unequal lengths
+        assert_eq!(
+            interleave_n([
+                vec![1, 2, 3].into_iter(),
+                vec![].into_iter(),
+                vec![4].into_iter(),
+                vec![5, 6].into_iter()
+            ])
+            .collect::<Vec<_>>(),
+            vec![1, 4, 5, 2, 6, 3]
+        );
+        assert_eq!(interleave_n::<std::vec::IntoIter<i32>, 0>([]).next(), None);
+
+        // Infinite iterators
+        assert_eq!(
+            interleave_n([(0..).step_by(2), (1..).step_by(2)])
+                .take(6)
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4, 5]
+        );
+
+        // Elements are taken only when needed.
+        let mut taken = 0;
+        let counted = (0..10).inspect(|_| taken += 1);
+        let first = interleave_n([counted]).take(3).collect::<Vec<_>>();
+        assert_eq!(first, vec![0, 1, 2]);
+        assert_eq!(taken, 3);
+    }
+
+    #[test]
+    fn test_interleave_dyn() {
+        // Sources loaded at runtime
+        let sources = ["ab", "", "cde", "f"];
+        let iters = sources
+            .iter()
+            .map(|source| -> Box<dyn Iterator<Item = char>> { Box::new(source.chars()) })
+            .collect();
+        assert_eq!(interleave_dyn(iters).collect::<String>(), "acfbde");
+
+        assert_eq!(interleave_dyn::<i32>(vec![]).next(), None);
+
+        let iters: Vec<Box<dyn Iterator<Item = u32>>> = vec![
+            Box::new(0..),
+            Box::new([100, 101].into_iter()),
+            Box::new(std::iter::repeat(7)),
+        ];
+        assert_eq!(
+            interleave_dyn(iters).take(9).collect::<Vec<_>>(),
+            vec![0, 100, 7, 1, 101, 7, 2, 7, 3]
+        );
+    }
+
     #[test]
     fn test_k_smallest_man() {
         assert_eq!(
             k_smallest_mean(vec![1, 3, 2].into_iter(), 2),
-            ((1 + 2) as f64 / 2.0)
+            Some((1 + 2) as f64 / 2.0)
         );
         assert_eq!(
             k_smallest_mean(vec![5, 3, 7, 7].into_iter(), 2),
-            ((3 + 5) as f64 / 2.0)
+            Some((3 + 5) as f64 / 2.0)
         );
         assert_eq!(
             k_smallest_mean(vec![7, 5, 3, 6].into_iter(), 3),
-            ((3 + 5 + 6) as f64 / 3.0)
+            Some((3 + 5 + 6) as f64 / 3.0)
         );
         assert_eq!(
             k_smallest_mean(vec![1, 3, 2, 4, 4, 5, 6].into_iter(), 3),
-            ((1 + 2 + 3) as f64 / 3.0)
+            Some((1 + 2 + 3) as f64 / 3.0)
         );
-        assert_eq!(k_smallest_mean(vec![].into_iter(), 3), (0 as f64 / 3.0));
+        assert_eq!(k_smallest_mean(vec![].into_iter(), 3), None);
         assert_eq!(
             k_smallest_mean(
                 vec![6, 9, 1, 14, 0, 4, 8, 7, 11, 2, 10, 3, 13, 12, 5].into_iter(),
                 5
             ),
-            ((1 + 2 + 3 + 4) as f64 / 5.0)
+            Some((1 + 2 + 3 + 4) as f64 / 5.0)
         );
     }
 
+    #[test]
+    fn test_k_smallest_mean_edge_cases() {
+        assert_eq!(k_smallest_mean(vec![1, 2].into_iter(), 0), None);
+        assert_eq!(k_smallest_mean(vec![1, 2].into_iter(), 3), None);
+        assert_eq!(k_smallest_mean(vec![1, 2].into_iter(), 2), Some(1.5));
+        assert_eq!(
+            k_smallest_mean(vec![i64::MAX, i64::MAX, i64::MAX].into_iter(), 2),
+            Some(i64::MAX as f64)
+        );
+        assert_eq!(k_smallest_mean((0..1_000_000).rev(), 4), Some(1.5));
+    }
+
     #[test]
     fn test_calculate_mean() {
         assert_eq!(
@@ -177,6 +305,52 @@ mod test {
         )
     }
 
+    #[test]
+    fn test_class_statistics() {
+        let stats = class_statistics(
+            [
+                ("CS220".to_string(), 60),
+                ("CS420".to_string(), 60),
+                ("CS220".to_string(), 80),
+                ("CS431".to_string(), 75),
+                ("CS420".to_string(), 90),
+                ("CS220".to_string(), 100),
+                ("CS420".to_string(), 80),
+                ("CS420".to_string(), 50),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(stats.len(), 3);
+
+        let cs220 = stats["CS220"];
+        assert_eq!(cs220.count, 3);
+        assert_eq!((cs220.min, cs220.max), (60, 100));
+        assert!((cs220.mean - 80.0).abs() < 1e-9);
+        assert!((cs220.median - 80.0).abs() < 1e-9);
+        assert!((cs220.std_dev - (800.0f64 / 3.0).sqrt()).abs() < 1e-9);
+
+        let cs420 = stats["CS420"];
+        assert_eq!(cs420.count, 4);
+        assert_eq!((cs420.min, cs420.max), (50, 90));
+        assert!((cs420.mean - 70.0).abs() < 1e-9);
+        assert!((cs420.median - 70.0).abs() < 1e-9);
+        assert!((cs420.std_dev - 250.0f64.sqrt()).abs() < 1e-9);
+
+        assert_eq!(
+            stats["CS431"],
+            ClassStatistics {
+                count: 1,
+                mean: 75.0,
+                min: 75,
+                max: 75,
+                std_dev: 0.0,
+                median: 75.0,
+            }
+        );
+
+        assert!(class_statistics(std::iter::empty()).is_empty());
+    }
+
     #[test]
     fn test_sum_is_n() {
         assert_eq!(sum_is_n(vec![vec![1, 2, 3], vec![2, 3]], 3), 1);
@@ -205,10 +379,34 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_sum_count_distribution() {
+        assert!(sum_count_distribution(vec![]).is_empty());
+        assert!(sum_count_distribution(vec![vec![1, 2], vec![]]).is_empty());
+        assert_eq!(sum_is_n(vec![], 0), 0);
+
+        assert_eq!(
+            sum_count_distribution(vec![vec![1, 1, 2]]),
+            [(1, 2), (2, 1)].into_iter().collect()
+        );
+        assert_eq!(
+            sum_count_distribution(vec![vec![-1, 1], vec![-1, 1], vec![-1, 1]]),
+            [(-3, 1), (-1, 3), (1, 3), (3, 1)].into_iter().collect()
+        );
+
+        // The cartesian product has 2^30 sets.
+        let coins = vec![vec![0, 1]; 30];
+        let distribution = sum_count_distribution(coins.clone());
+        assert_eq!(distribution.len(), 31);
+        assert_eq!(distribution.values().sum::<usize>(), 1 << 30);
+        assert_eq!(distribution[&15], 155_117_520);
+        assert_eq!(sum_is_n(coins, 30), 1);
+    }
+
     // find_count_n
     #[test]
     fn test_find_count_n() {
-        assert_eq!(find_count_n(vec![], 1), vec![]);
+        assert_eq!(find_count_n(vec![], 1), Vec::<usize>::new());
         assert_eq!(find_count_n(vec![1, 2], 1), vec![1, 2]);
         assert_eq!(find_count_n(vec![1, 3, 3], 1), vec![1]);
         assert_eq!(find_count_n(vec![1, 3, 3], 2), vec![3]);
@@ -216,8 +414,35 @@ mod test {
         assert_eq!(find_count_n(vec![1, 3, 2, 3, 2, 3], 3), vec![3]);
         assert_eq!(find_count_n(vec![1, 2, 2, 3, 3, 4], 2), vec![2, 3]);
         assert_eq!(find_count_n(vec![1, 3, 2, 2, 3], 2), vec![2, 3]);
-        assert_eq!(find_count_n(vec![0, 2, 2, 4, 3], 0), vec![]);
-        assert_eq!(find_count_n(vec![1, 1, 1, 2, 2], 1), vec![]);
+        assert_eq!(find_count_n(vec![0, 2, 2, 4, 3], 0), Vec::<usize>::new());
+        assert_eq!(find_count_n(vec![1, 1, 1, 2, 2], 1), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_find_count_at_least() {
+        assert_eq!(find_count_at_least(vec![], 1), Vec::<usize>::new());
+        assert_eq!(find_count_at_least(vec![1, 2], 0), vec![1, 2]);
+        assert_eq!(find_count_at_least(vec![4, 1, 3, 3], 1), vec![1, 3, 4]);
+        assert_eq!(find_count_at_least(vec![1, 3, 2, 3, 2, 3], 2), vec![2, 3]);
+        assert_eq!(find_count_at_least(vec![1, 3, 2, 3, 2, 3], 3), vec![3]);
+        assert_eq!(
+            find_count_at_least(vec![1, 3, 2, 3, 2, 3], 4),
+            Vec::<usize>::new()
+        );
+    }
+
+    #[test]
+    fn test_count_occurrences() {
+        assert!(count_occurrences(std::iter::empty::<u8>()).is_empty());
+
+        let counts = count_occurrences("the cat and the hat".split(' '));
+        assert_eq!(counts.len(), 4);
+        assert_eq!(counts["the"], 2);
+        assert_eq!(counts["cat"], 1);
+        assert_eq!(counts.get("dog"), None);
+
+        let counts = count_occurrences((0..1000).map(|i| i % 3));
+        assert_eq!(counts, [(0, 334), (1, 333), (2, 333)].into_iter().collect());
     }
 
     #[test]
@@ -233,6 +458,34 @@ mod test {
         assert_eq!(position_median(vec![1, 2, 3, 4, 5, 6, 8, 9]), Some(4));
     }
 
+    #[test]
+    fn test_position_kth() {
+        assert_eq!(position_kth(Vec::<usize>::new(), 0), None);
+        assert_eq!(position_kth(vec![3], 0), Some(0));
+        assert_eq!(position_kth(vec![3], 1), None);
+        assert_eq!(position_kth(vec![3, 1, 2], 0), Some(1));
+        assert_eq!(position_kth(vec![3, 1, 2], 1), Some(2));
+        assert_eq!(position_kth(vec![3, 1, 2], 2), Some(0));
+        assert_eq!(position_kth(vec![2, 1, 2, 1, 2], 3), Some(0));
+        assert_eq!(position_kth(vec!["b", "a", "c"], 1), Some(0));
+
+        // Elements need not be `Clone`.
+        #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Mark(u32);
+        assert_eq!(position_kth(vec![Mark(90), Mark(70), Mark(80)], 1), Some(2));
+
+        let n = 1_000_000;
+        let inner = (0..n).map(|i| (i * 7919) % n).collect::<Vec<_>>();
+        for k in [0, 1, n / 2, n - 1] {
+            let pos = position_kth(inner.clone(), k).unwrap();
+            assert_eq!(inner[pos], k);
+        }
+        assert_eq!(
+            position_median(inner),
+            position_kth((0..n).collect(), n / 2)
+        );
+    }
+
     #[test]
     fn test_two_dimensional_sum() {
         assert_eq!(
@@ -265,4 +518,32 @@ mod test {
         assert_false!(is_palindrome("hello".to_string()));
         assert_false!(is_palindrome("apple".to_string()));
     }
+
+    #[test]
+    fn test_is_palindrome_unicode() {
+        assert_true!(is_palindrome("토마토".to_string()));
+        assert_true!(is_palindrome("été".to_string()));
+        assert_true!(is_palindrome("a🦀a".to_string()));
+        assert_false!(is_palindrome("가나".to_string()));
+        assert_false!(is_palindrome("Noon".to_string()));
+
+        let long = "ab".repeat(100_000) + "a";
+        assert_true!(is_palindrome(long));
+    }
+
+    #[test]
+    fn test_is_palindrome_alphanumeric() {
+        assert_true!(is_palindrome_alphanumeric(
+            "A man, a plan, a canal: Panama!"
+        ));
+        assert_true!(is_palindrome_alphanumeric("Was it a car or a cat I saw?"));
+        assert_true!(is_palindrome_alphanumeric("No 'x' in Nixon"));
+        assert_true!(is_palindrome_alphanumeric("Été!"));
+        assert_true!(is_palindrome_alphanumeric("12:21"));
+        assert_true!(is_palindrome_alphanumeric(""));
+        assert_true!(is_palindrome_alphanumeric("?!"));
+
+        assert_false!(is_palindrome_alphanumeric("Hello, world!"));
+        assert_false!(is_palindrome_alphanumeric("12:31"));
+    }
 }
@@ -2,7 +2,8 @@
 
 use std::{
     cmp::{Ordering, Reverse},
-    collections::{BinaryHeap, HashSet},
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    fmt, iter,
 };
 
 use itertools::*;
@@ -95,6 +96,112 @@ pub fn traverse_preorder<T>(root: Node<T>) -> Vec<T> {
     result
 }
 
+/// Traverses the tree in postorder.
+///
+/// The algorithm for postorder traversal is as follows:
+///
+/// 1. If the root is a non-leaf node, traverse each subtree from the child nodes.
+/// 2. Visit the root.
+///
+/// For example, the result of postorder traversal for the tree in [`traverse_preorder`] is
+/// `5 -> 6 -> 2 -> 3 -> 7 -> 8 -> 9 -> 4 -> 1`.
+pub fn traverse_postorder<T>(root: Node<T>) -> Vec<T> {
+    let mut result: Vec<T> = Vec::new();
+    match root {
+        Node::NonLeaf((t, vec)) => {
+            for v in vec {
+                result.extend(traverse_postorder(v));
+            }
+            result.push(t);
+        }
+        Node::Leaf(t) => {
+            result.push(t);
+        }
+    }
+    result
+}
+
+/// Traverses the tree in level order, i.e. visits the nodes by depth and then from left to right.
+///
+/// For example, the result of level order traversal for the tree in [`traverse_preorder`] is
+/// `1 -> 2 -> 3 -> 4 -> 5 -> 6 -> 7 -> 8 -> 9`.
+pub fn traverse_level_order<T>(root: Node<T>) -> Vec<T> {
+    let mut result: Vec<T> = Vec::new();
+    let mut queue = VecDeque::from([root]);
+    while let Some(node) = queue.pop_front() {
+        match node {
+            Node::NonLeaf((t, vec)) => {
+                result.push(t);
+                queue.extend(vec);
+            }
+            Node::Leaf(t) => {
+                result.push(t);
+            }
+        }
+    }
+    result
+}
+
+impl<T> Node<T> {
+    /// Returns the name of the node.
+    pub fn value(&self) -> &T {
+        match self {
+            Node::NonLeaf((t, _)) | Node::Leaf(t) => t,
+        }
+    }
+
+    /// Returns the child nodes, which are empty for a leaf node.
+    pub fn children(&self) -> &[Node<T>] {
+        match self {
+            Node::NonLeaf((_, vec)) => vec,
+            Node::Leaf(_) => &[],
+        }
+    }
+
+    /// Iterates the names of the nodes in preorder without consuming the tree.
+    ///
+    /// See [`traverse_preorder`].
+    pub fn iter_preorder(&self) -> impl Iterator<Item = &T> {
+        let mut stack = vec![self];
+        iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children().iter().rev());
+            Some(node.value())
+        })
+    }
+
+    /// Iterates the names of the nodes in postorder without consuming the tree.
+    ///
+    /// See [`traverse_postorder`].
+    pub fn iter_postorder(&self) -> impl Iterator<Item = &T> {
+        // The nodes on the path from the root, with the number of their visited children.
+        let mut stack = vec![(self, 0)];
+        iter::from_fn(move || loop {
+            let (node, visited) = stack.last_mut()?;
+            let node = *node;
+            if let Some(child) = node.children().get(*visited) {
+                *visited += 1;
+                stack.push((child, 0));
+            } else {
+                let _unused = stack.pop();
+                return Some(node.value());
+            }
+        })
+    }
+
+    /// Iterates the names of the nodes in level order without consuming the tree.
+    ///
+    /// See [`traverse_level_order`].
+    pub fn iter_level_order(&self) -> impl Iterator<Item = &T> {
+        let mut queue = VecDeque::from([self]);
+        iter::from_fn(move || {
+            let node = queue.pop_front()?;
+            queue.extend(node.children());
+            Some(node.value())
+        })
+    }
+}
+
 /// File
 #[derive(Debug)]
 pub enum File {
@@ -111,6 +218,134 @@ pub enum File {
     Data(String, usize),
 }
 
+/// Error for building a [`File`] tree from paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilePathError {
+    /// No paths are given.
+    NoPaths,
+    /// The path is empty or has an empty component, e.g. `"root//a"`.
+    EmptyComponent(String),
+    /// The path does not start with the name of the root.
+    RootMismatch(String),
+    /// A file in the middle of the path is not a directory.
+    NotADirectory(String),
+    /// The path to insert data at is a directory.
+    IsADirectory(String),
+}
+
+impl fmt::Display for FilePathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilePathError::NoPaths => write!(f, "no paths given"),
+            FilePathError::EmptyComponent(path) => write!(f, "{path}: empty file name"),
+            FilePathError::RootMismatch(path) => write!(f, "{path}: not under the root"),
+            FilePathError::NotADirectory(path) => write!(f, "{path}: not a directory"),
+            FilePathError::IsADirectory(path) => write!(f, "{path}: is a directory"),
+        }
+    }
+}
+
+impl std::error::Error for FilePathError {}
+
+impl File {
+    /// Builds the tree of data files at the given `/`-separated paths with the given sizes, e.g.
+    /// `("root/a/a1", 1)`. The directories are created as needed, and the files are in the order
+    /// they first appear.
+    ///
+    /// All paths must start with the same root directory.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cs220::assignments::assignment10::small_exercises::*;
+    ///
+    /// let root = File::from_paths(&[("root/a/a1", 1), ("root/a/a2", 3), ("root/c", 8)]).unwrap();
+    /// assert_eq!(root.total_size(), 12);
+    /// assert_eq!(root.find("root/a").map(File::total_size), Some(4));
+    /// ```
+    pub fn from_paths(paths: &[(&str, usize)]) -> Result<Self, FilePathError> {
+        let (first, _) = paths.first().ok_or(FilePathError::NoPaths)?;
+        let root_name = first.split('/').next().unwrap_or_default();
+        let mut root = File::Directory(root_name.to_string(), Vec::new());
+        for (path, size) in paths {
+            root.insert(path, *size)?;
+        }
+        Ok(root)
+    }
+
+    /// Returns the name of the file.
+    pub fn name(&self) -> &str {
+        match self {
+            File::Directory(name, _) | File::Data(name, _) => name,
+        }
+    }
+
+    /// Returns the size of the file, which is the sum of the sizes of its sub-files for a
+    /// directory.
+    pub fn total_size(&self) -> usize {
+        match self {
+            File::Directory(_, files) => files.iter().map(File::total_size).sum(),
+            File::Data(_, size) => *size,
+        }
+    }
+
+    /// Finds the file at the given `/`-separated path, which starts with the name of `self`.
+    pub fn find(&self, path: &str) -> Option<&File> {
+        let mut components = path.split('/');
+        if components.next() != Some(self.name()) {
+            return None;
+        }
+        components.try_fold(self, |file, component| match file {
+            File::Directory(_, files) => files.iter().find(|fl| fl.name() == component),
+            File::Data(..) => None,
+        })
+    }
+
+    /// Inserts data of the given size at the given `/`-separated path, which starts with the name
+    /// of `self`, creating the directories as needed. If there is data at the path, its size is
+    /// replaced.
+    pub fn insert(&mut self, path: &str, size: usize) -> Result<(), FilePathError> {
+        let components = path.split('/').collect::<Vec<_>>();
+        if components.iter().any(|component| component.is_empty()) {
+            return Err(FilePathError::EmptyComponent(path.to_string()));
+        }
+        let Some((name, dirs)) = components.split_last() else {
+            unreachable!("`split` yields at least one component");
+        };
+        let Some((root, dirs)) = dirs.split_first() else {
+            return Err(FilePathError::IsADirectory(path.to_string()));
+        };
+        if *root != self.name() {
+            return Err(FilePathError::RootMismatch(path.to_string()));
+        }
+
+        let mut dir = self;
+        for component in dirs {
+            let File::Directory(_, files) = dir else {
+                return Err(FilePathError::NotADirectory(path.to_string()));
+            };
+            let index = match files.iter().position(|fl| fl.name() == *component) {
+                Some(index) => index,
+                None => {
+                    files.push(File::Directory(component.to_string(), Vec::new()));
+                    files.len() - 1
+                }
+            };
+            dir = &mut files[index];
+        }
+
+        let File::Directory(_, files) = dir else {
+            return Err(FilePathError::NotADirectory(path.to_string()));
+        };
+        match files.iter_mut().find(|fl| fl.name() == *name) {
+            Some(File::Data(_, old_size)) => *old_size = size,
+            Some(File::Directory(..)) => return Err(FilePathError::IsADirectory(path.to_string())),
+            None => files.push(File::Data(name.to_string(), size)),
+        }
+        Ok(())
+    }
+}
+
 /// Given a file, summarize all subfiles and sizes in ascending order of size.
 ///
 /// - Its behaviour is the same as the `du | sort -h` command on Linux.
@@ -178,6 +413,166 @@ pub fn du_sort(root: &File) -> Vec<(&str, usize)> {
     result
 }
 
+/// Options of [`du`], mirroring the flags of the `du` command.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DuOptions {
+    max_depth: Option<usize>,
+    full_paths: bool,
+    human_readable: bool,
+}
+
+impl DuOptions {
+    /// Creates the default options: all files, by name, with sizes in bytes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only reports the files at most `max_depth` levels below the root, like `du --max-depth`.
+    /// The root is at depth 0. The sizes of the directories still include the deeper files.
+    pub fn max_depth(self, max_depth: usize) -> Self {
+        Self {
+            max_depth: Some(max_depth),
+            ..self
+        }
+    }
+
+    /// Reports the files by their paths from the root joined with `/`, e.g. `"root/a/a1"`, instead
+    /// of by their names.
+    pub fn full_paths(self, full_paths: bool) -> Self {
+        Self { full_paths, ..self }
+    }
+
+    /// Displays the sizes in powers of 1024 (e.g. `1.5 KiB`) like `du -h`. See
+    /// [`human_readable_size`].
+    pub fn human_readable(self, human_readable: bool) -> Self {
+        Self {
+            human_readable,
+            ..self
+        }
+    }
+}
+
+/// A file in the [`DuReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuEntry {
+    /// The name or the full path of the file, depending on [`DuOptions::full_paths`].
+    pub path: String,
+    /// The size of the file.
+    pub size: usize,
+    /// The number of levels below the root.
+    pub depth: usize,
+}
+
+/// The result of [`du`].
+///
+/// Its `Display` implementation prints a line of `size<TAB>path` for each entry, like `du`.
+#[derive(Debug, Clone)]
+pub struct DuReport {
+    entries: Vec<DuEntry>,
+    human_readable: bool,
+}
+
+impl DuReport {
+    /// Returns the entries in ascending order of size, and then of path.
+    pub fn entries(&self) -> &[DuEntry] {
+        &self.entries
+    }
+
+    /// Returns the entries in ascending order of size, and then of path.
+    pub fn into_entries(self) -> Vec<DuEntry> {
+        self.entries
+    }
+}
+
+impl fmt::Display for DuReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            if self.human_readable {
+                writeln!(f, "{}\t{}", human_readable_size(entry.size), entry.path)?;
+            } else {
+                writeln!(f, "{}\t{}", entry.size, entry.path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats `size` bytes in the largest unit among B, KiB, MiB, GiB, ... that keeps the number at
+/// least 1, with one decimal place for the units other than B.
+///
+/// For instance, `human_readable_size(1536)` is `"1.5 KiB"`.
+pub fn human_readable_size(size: usize) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    if size < 1024 {
+        return format!("{size} B");
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    // Move up before the value would be rounded to 1024.0.
+    while value >= 1023.95 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", UNITS[unit])
+}
+
+/// Summarizes all subfiles and sizes like [`du_sort`], with the given options.
+///
+/// # Example
+///
+/// ```
+/// use cs220::assignments::assignment10::small_exercises::*;
+///
+/// let root = File::Directory(
+///     "root".to_string(),
+///     vec![
+///         File::Directory("a".to_string(), vec![File::Data("a1".to_string(), 2048)]),
+///         File::Data("b".to_string(), 100),
+///     ],
+/// );
+/// let report = du(&root, DuOptions::new().max_depth(1).full_paths(true).human_readable(true));
+/// assert_eq!(report.to_string(), "100 B\troot/b\n2.0 KiB\troot/a\n2.1 KiB\troot\n");
+/// ```
+pub fn du(root: &File, options: DuOptions) -> DuReport {
+    let mut entries = Vec::new();
+    let _unused = du_visit(root, "", 0, options, &mut entries);
+    entries.sort_by(|a, b| a.size.cmp(&b.size).then_with(|| a.path.cmp(&b.path)));
+    DuReport {
+        entries,
+        human_readable: options.human_readable,
+    }
+}
+
+// Push the entries of `file` and its subfiles, and return the size of `file`.
+fn du_visit(
+    file: &File,
+    parent: &str,
+    depth: usize,
+    options: DuOptions,
+    entries: &mut Vec<DuEntry>,
+) -> usize {
+    let (name, size) = match file {
+        File::Directory(name, files) => {
+            let path = format!("{parent}{name}/");
+            let size = files
+                .iter()
+                .map(|fl| du_visit(fl, &path, depth + 1, options, entries))
+                .sum();
+            (name, size)
+        }
+        File::Data(name, size) => (name, *size),
+    };
+    if depth <= options.max_depth.unwrap_or(usize::MAX) {
+        let path = if options.full_paths {
+            format!("{parent}{name}")
+        } else {
+            name.clone()
+        };
+        entries.push(DuEntry { path, size, depth });
+    }
+    size
+}
+
 /// Remove all even numbers inside a vector using the given mutable reference.
 /// That is, you must modify the vector using the given mutable reference instead
 /// of returning a new vector.
@@ -233,22 +628,62 @@ pub fn remove_duplicate(inner: &mut Vec<i64>) {
 ///  20231234 |    Mike   |     ME
 /// ```
 pub fn natural_join(table1: Vec<Vec<String>>, table2: Vec<Vec<String>>) -> Vec<Vec<String>> {
+    join_on(table1, 0, table2, 0)
+}
+
+/// Returns the join of two tables on column `key1` of table1 and column `key2` of table2.
+/// That is, for each pair of a row from table1 and a row from table2, if `row1[key1]` and
+/// `row2[key2]` are equal, then add all elements of `row2` except `row2[key2]` to `row1` and add it
+/// to the results.
+///
+/// The rows may have any number of columns. Rows without the key column are never joined.
+///
+/// The join is done by hashing the keys of table1, so it takes time proportional to the sizes of
+/// the tables and of the result.
+///
+/// # Example
+///
+/// ```text
+///        table1                           table2
+/// ---------------------------     ----------------------
+///  Jack |  20230001 |  Seoul        CS  |    20230001
+///  Mike |  20231234 |  Busan        EE  |    20230001
+///                                   ME  |    20231234
+///
+///
+///   join_on(table1, 1, table2, 1)
+/// ---------------------------------
+///  Jack |  20230001 |  Seoul |  CS
+///  Jack |  20230001 |  Seoul |  EE
+///  Mike |  20231234 |  Busan |  ME
+/// ```
+pub fn join_on(
+    table1: Vec<Vec<String>>,
+    key1: usize,
+    table2: Vec<Vec<String>>,
+    key2: usize,
+) -> Vec<Vec<String>> {
+    let mut index: HashMap<&str, Vec<&Vec<String>>> = HashMap::new();
+    for row1 in &table1 {
+        if let Some(key) = row1.get(key1) {
+            index.entry(key).or_default().push(row1);
+        }
+    }
+
     let mut result = Vec::new();
     for row2 in &table2 {
-        let [id2, course]: &[String; 2] = row2
-            .as_slice()
-            .try_into()
-            .expect("each row must have 2 columns");
-        for row1 in &table1 {
-            let [id1, name]: &[String; 2] = row1
-                .as_slice()
-                .try_into()
-                .expect("each row must have 2 columns");
-            if id1 == id2 {
-                let tmp: Vec<String> = [id2, name, course].iter().map(|s| s.to_string()).collect();
-                result.push(tmp);
-                break;
-            }
+        let Some(rows1) = row2.get(key2).and_then(|key| index.get(key.as_str())) else {
+            continue;
+        };
+        for row1 in rows1 {
+            let mut row = row1.to_vec();
+            row.extend(
+                row2.iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != key2)
+                    .map(|(_, s)| s.clone()),
+            );
+            result.push(row);
         }
     }
     result
@@ -38,6 +38,89 @@ mod test {
         assert_eq!(traverse_preorder(root), vec![1, 2, 5, 6, 3, 4, 7, 8, 9]);
     }
 
+    fn example_tree() -> Node<i32> {
+        Node::NonLeaf((
+            1,
+            vec![
+                Node::NonLeaf((2, vec![Node::Leaf(5), Node::Leaf(6)])),
+                Node::Leaf(3),
+                Node::NonLeaf((4, vec![Node::Leaf(7), Node::Leaf(8), Node::Leaf(9)])),
+            ],
+        ))
+    }
+
+    #[test]
+    fn test_traverse_postorder() {
+        assert_eq!(
+            traverse_postorder(example_tree()),
+            vec![5, 6, 2, 3, 7, 8, 9, 4, 1]
+        );
+        assert_eq!(traverse_postorder(Node::Leaf("a")), vec!["a"]);
+        assert_eq!(traverse_postorder(Node::NonLeaf(("a", vec![]))), vec!["a"]);
+    }
+
+    #[test]
+    fn test_traverse_level_order() {
+        assert_eq!(
+            traverse_level_order(example_tree()),
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9]
+        );
+
+        let root = Node::NonLeaf((
+            'a',
+            vec![
+                Node::NonLeaf(('b', vec![Node::NonLeaf(('d', vec![Node::Leaf('f')]))])),
+                Node::NonLeaf(('c', vec![Node::Leaf('e')])),
+            ],
+        ));
+        assert_eq!(
+            traverse_level_order(root),
+            vec!['a', 'b', 'c', 'd', 'e', 'f']
+        );
+    }
+
+    #[test]
+    fn test_iter_order() {
+        let root = example_tree();
+
+        assert_eq!(
+            root.iter_preorder().copied().collect::<Vec<_>>(),
+            traverse_preorder(example_tree())
+        );
+        assert_eq!(
+            root.iter_postorder().copied().collect::<Vec<_>>(),
+            traverse_postorder(example_tree())
+        );
+        assert_eq!(
+            root.iter_level_order().copied().collect::<Vec<_>>(),
+            traverse_level_order(example_tree())
+        );
+
+        // The iterators are lazy and leave the tree intact.
+        assert_eq!(root.iter_preorder().nth(2), Some(&5));
+        assert_eq!(root.iter_postorder().find(|&&t| t > 6), Some(&7));
+        assert_eq!(root.iter_level_order().take(3).count(), 3);
+        assert_eq!(*root.value(), 1);
+        assert_eq!(root.children().len(), 3);
+
+        let leaf = Node::Leaf("leaf".to_string());
+        assert_eq!(leaf.iter_preorder().collect::<Vec<_>>(), vec!["leaf"]);
+        assert_eq!(leaf.iter_postorder().collect::<Vec<_>>(), vec!["leaf"]);
+        assert_eq!(leaf.iter_level_order().collect::<Vec<_>>(), vec!["leaf"]);
+    }
+
+    #[test]
+    fn test_iter_order_deep() {
+        // A path of 10,000 nodes
+        let mut root = Node::Leaf(0);
+        for i in 1..10_000 {
+            root = Node::NonLeaf((i, vec![root]));
+        }
+        assert_eq!(root.iter_preorder().last(), Some(&0));
+        assert_eq!(root.iter_postorder().next(), Some(&0));
+        assert_eq!(root.iter_level_order().count(), 10_000);
+    }
+
     #[test]
     fn test_du_sort() {
         let rootfile = File::Directory(
@@ -158,6 +241,174 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_file_from_paths() {
+        let rootfile = File::from_paths(&[
+            ("root/a/a1", 1),
+            ("root/a/a2", 3),
+            ("root/b/b1", 3),
+            ("root/b/b2", 15),
+            ("root/c", 8),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            du_sort(&rootfile),
+            vec![
+                ("a1", 1),
+                ("a2", 3),
+                ("b1", 3),
+                ("a", 4),
+                ("c", 8),
+                ("b2", 15),
+                ("b", 18),
+                ("root", 30)
+            ]
+        );
+        assert_eq!(rootfile.name(), "root");
+        assert_eq!(rootfile.total_size(), 30);
+
+        assert_eq!(File::from_paths(&[]).unwrap_err(), FilePathError::NoPaths);
+        assert_eq!(
+            File::from_paths(&[("root/a", 1), ("other/b", 2)]).unwrap_err(),
+            FilePathError::RootMismatch("other/b".to_string())
+        );
+        assert_eq!(
+            File::from_paths(&[("root/a", 1), ("root/a/b", 2)]).unwrap_err(),
+            FilePathError::NotADirectory("root/a/b".to_string())
+        );
+        assert_eq!(
+            File::from_paths(&[("root/a/b", 1), ("root/a", 2)]).unwrap_err(),
+            FilePathError::IsADirectory("root/a".to_string())
+        );
+        assert_eq!(
+            File::from_paths(&[("root//a", 1)]).unwrap_err(),
+            FilePathError::EmptyComponent("root//a".to_string())
+        );
+        assert_eq!(
+            File::from_paths(&[("root", 1)]).unwrap_err(),
+            FilePathError::IsADirectory("root".to_string())
+        );
+    }
+
+    #[test]
+    fn test_file_find_insert() {
+        let mut rootfile = File::from_paths(&[("root/a/a1", 1), ("root/c", 8)]).unwrap();
+
+        assert_eq!(rootfile.find("root").map(File::name), Some("root"));
+        assert_eq!(rootfile.find("root/a").map(File::total_size), Some(1));
+        assert_eq!(rootfile.find("root/a/a1").map(File::total_size), Some(1));
+        assert!(rootfile.find("root/b").is_none());
+        assert!(rootfile.find("root/c/x").is_none());
+        assert!(rootfile.find("a/a1").is_none());
+
+        rootfile.insert("root/a/a3/x", 10).unwrap();
+        rootfile.insert("root/c", 5).unwrap();
+        assert_eq!(rootfile.find("root/a").map(File::total_size), Some(11));
+        assert_eq!(rootfile.find("root/c").map(File::total_size), Some(5));
+        assert_eq!(rootfile.total_size(), 16);
+
+        assert_eq!(
+            rootfile.insert("root/a", 1),
+            Err(FilePathError::IsADirectory("root/a".to_string()))
+        );
+        assert_eq!(
+            rootfile.insert("root/c/d", 1),
+            Err(FilePathError::NotADirectory("root/c/d".to_string()))
+        );
+        assert_eq!(
+            rootfile.insert("", 1),
+            Err(FilePathError::EmptyComponent("".to_string()))
+        );
+        assert_eq!(rootfile.total_size(), 16);
+    }
+
+    #[test]
+    fn test_du() {
+        let rootfile = File::Directory(
+            "root".to_string(),
+            vec![
+                File::Directory(
+                    "a".to_string(),
+                    vec![
+                        File::Data("a1".to_string(), 1),
+                        File::Directory("a2".to_string(), vec![File::Data("x".to_string(), 3)]),
+                    ],
+                ),
+                File::Directory("b".to_string(), vec![File::Data("x".to_string(), 15)]),
+            ],
+        );
+        let entries = |options| {
+            du(&rootfile, options)
+                .into_entries()
+                .into_iter()
+                .map(|entry| (entry.path, entry.size, entry.depth))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            entries(DuOptions::new()),
+            vec![
+                ("a1".to_string(), 1, 2),
+                ("a2".to_string(), 3, 2),
+                ("x".to_string(), 3, 3),
+                ("a".to_string(), 4, 1),
+                ("b".to_string(), 15, 1),
+                ("x".to_string(), 15, 2),
+                ("root".to_string(), 19, 0),
+            ]
+        );
+        assert_eq!(
+            entries(DuOptions::new().full_paths(true)),
+            vec![
+                ("root/a/a1".to_string(), 1, 2),
+                ("root/a/a2".to_string(), 3, 2),
+                ("root/a/a2/x".to_string(), 3, 3),
+                ("root/a".to_string(), 4, 1),
+                ("root/b".to_string(), 15, 1),
+                ("root/b/x".to_string(), 15, 2),
+                ("root".to_string(), 19, 0),
+            ]
+        );
+        assert_eq!(
+            entries(DuOptions::new().max_depth(1)),
+            vec![
+                ("a".to_string(), 4, 1),
+                ("b".to_string(), 15, 1),
+                ("root".to_string(), 19, 0),
+            ]
+        );
+        assert_eq!(
+            entries(DuOptions::new().max_depth(0)),
+            vec![("root".to_string(), 19, 0)]
+        );
+
+        assert_eq!(
+            du(&rootfile, DuOptions::new().max_depth(1).full_paths(true)).to_string(),
+            "4\troot/a\n15\troot/b\n19\troot\n"
+        );
+        assert_eq!(
+            du(
+                &File::Data("big".to_string(), 3 << 30),
+                DuOptions::new().human_readable(true)
+            )
+            .to_string(),
+            "3.0 GiB\tbig\n"
+        );
+    }
+
+    #[test]
+    fn test_human_readable_size() {
+        assert_eq!(human_readable_size(0), "0 B");
+        assert_eq!(human_readable_size(1023), "1023 B");
+        assert_eq!(human_readable_size(1024), "1.0 KiB");
+        assert_eq!(human_readable_size(1536), "1.5 KiB");
+        assert_eq!(human_readable_size(1024 * 1024 - 1), "1.0 MiB");
+        assert_eq!(human_readable_size(1024 * 1023), "1023.0 KiB");
+        assert_eq!(human_readable_size(5 << 20), "5.0 MiB");
+        assert_eq!(human_readable_size(usize::MAX), "16.0 EiB");
+    }
+
     #[test]
     fn test_remove_even() {
         let mut vec = vec![1, 2, 3, 4, 5];
@@ -270,6 +521,71 @@ mod test {
         );
     }
 
+    fn table(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|row| row.iter().map(|s| s.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_join_on() {
+        let table1 = table(&[
+            &["Jack", "20230001", "Seoul"],
+            &["Mike", "20231234", "Busan"],
+            &["Jane", "20230001", "Daejeon"],
+            &["Anna"],
+        ]);
+        let table2 = table(&[
+            &["CS", "20230001"],
+            &["EE", "20230001"],
+            &["ME", "20231234"],
+            &["BS", "20239999"],
+            &[],
+        ]);
+
+        assert_eq!(
+            HashSet::<Vec<String>>::from_iter(join_on(table1.clone(), 1, table2.clone(), 1)),
+            HashSet::<Vec<String>>::from_iter(table(&[
+                &["Jack", "20230001", "Seoul", "CS"],
+                &["Jane", "20230001", "Daejeon", "CS"],
+                &["Jack", "20230001", "Seoul", "EE"],
+                &["Jane", "20230001", "Daejeon", "EE"],
+                &["Mike", "20231234", "Busan", "ME"],
+            ]))
+        );
+
+        // Rows of any width
+        assert_eq!(
+            join_on(
+                table(&[&["x", "1"], &["y", "z", "2"]]),
+                1,
+                table(&[&["1", "a", "b", "c"], &["z"]]),
+                0
+            ),
+            table(&[&["x", "1", "a", "b", "c"], &["y", "z", "2"]])
+        );
+
+        assert!(join_on(table1, 5, table2, 1).is_empty());
+        assert!(join_on(Vec::new(), 0, table(&[&["a"]]), 0).is_empty());
+    }
+
+    #[test]
+    fn test_join_on_large() {
+        let n = 100_000;
+        let table1 = (0..n)
+            .map(|i| vec![i.to_string(), format!("name{i}")])
+            .collect();
+        let table2 = (0..n)
+            .rev()
+            .map(|i| vec![format!("dept{}", i % 7), (i * 2).to_string()])
+            .collect();
+        let result = join_on(table1, 0, table2, 1);
+        assert_eq!(result.len(), n / 2);
+        assert!(result
+            .iter()
+            .all(|row| row[0].parse::<usize>().unwrap() % 2 == 0 && row.len() == 3));
+    }
+
     #[test]
     fn test_pythagorean() {
         let pythagoreans = [
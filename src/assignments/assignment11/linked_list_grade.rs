@@ -25,7 +25,7 @@ mod test_linked_list {
 
     #[test]
     fn test_from_into_vec() {
-        assert_eq!(SinglyLinkedList::<i32>::new().into_vec(), vec![]);
+        assert_eq!(SinglyLinkedList::<i32>::new().into_vec(), Vec::<i32>::new());
         assert_eq!(
             SinglyLinkedList::from_vec(vec![1, 2, 3]).into_vec(),
             vec![1, 2, 3]
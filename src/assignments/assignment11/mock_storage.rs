@@ -5,30 +5,1030 @@
 //! Refer `mock_storage_grade.rs` for test cases.
 
 use std::cell::{Ref, RefCell};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, SystemTime};
+#[cfg(feature = "persist")]
+use std::{fs, io, path::Path};
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+#[cfg(feature = "persist")]
+use serde::{Deserialize, Serialize};
+
+use crate::assignments::assignment10::small_exercises::File;
 
 /// Mock storage.
+///
+/// File names are `/`-separated paths. Directories are not stored on their own: a directory
+/// exists as long as some file lies under it.
+///
+/// Each user owns the top-level directory named after the user, and may be given a quota on the
+/// total size of the files in it.
+///
+/// The files are guarded by `C`, which is [`RefCell`] by default. Use [`SyncMockStorage`] to share
+/// a storage between threads.
 #[derive(Debug)]
-pub struct MockStorage {
+pub struct MockStorage<C: StateCell = RefCell<StorageState>> {
     /// Files stored in the storage.
+    state: C,
+
+    /// What to do when an upload would exceed the capacity.
+    policy: EvictionPolicy,
+}
+
+/// Policy consulted by [`MockStorage`] when an upload would exceed its capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvictionPolicy {
+    /// Rejects the upload.
+    #[default]
+    RejectWhenFull,
+
+    /// Evicts the least recently used (uploaded or downloaded) files until the new file fits.
+    EvictLru,
+
+    /// Evicts the largest files until the new file fits.
+    EvictLargest,
+}
+
+/// Event on a [`MockStorage`], passed to the callbacks registered with
+/// [`MockStorage::subscribe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageEvent {
+    /// A new file is uploaded.
+    Upload {
+        /// Path of the file.
+        path: String,
+        /// Size of the file.
+        size: usize,
+    },
+
+    /// An existing file is overwritten.
+    Overwrite {
+        /// Path of the file.
+        path: String,
+        /// Size of the file before the upload.
+        old_size: usize,
+        /// Size of the file after the upload.
+        new_size: usize,
+    },
+
+    /// A file is deleted.
+    Delete {
+        /// Path of the file.
+        path: String,
+        /// Size of the file.
+        size: usize,
+    },
+
+    /// A file is evicted to make room for another file.
+    Evict {
+        /// Path of the file.
+        path: String,
+        /// Size of the file.
+        size: usize,
+    },
+
+    /// An expired file is removed by [`MockStorage::gc`].
+    Expire {
+        /// Path of the file.
+        path: String,
+        /// Size of the file.
+        size: usize,
+    },
+}
+
+/// Source of the current time for a [`MockStorage`], set by [`MockStorage::with_clock`].
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> SystemTime;
+}
+
+/// Clock that reads the system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Clock that only moves when it is told to.
+#[derive(Debug)]
+pub struct MockClock {
+    now: Mutex<SystemTime>,
+}
+
+impl MockClock {
+    /// Creates a new clock stopped at `now`.
+    pub fn new(now: SystemTime) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.now.lock().unwrap() += duration;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().unwrap()
+    }
+}
+
+/// Error returned by [`MockStorage::upload_batch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchError {
+    /// Index of the first file in the batch that could not be uploaded.
+    pub index: usize,
+
+    /// Name of the file.
+    pub name: String,
+
+    /// Insufficient memory size to upload the file, or 0 if the file could not be uploaded for
+    /// another reason, e.g., an invalid path.
+    pub excess: usize,
+}
+
+/// Faults injected into the uploads of a [`MockStorage`], set by [`MockStorage::set_faults`].
+///
+/// Uploads failed by injected faults return `Err(0)`, like uploads to invalid paths. A genuine lack
+/// of space never does.
+#[derive(Debug, Clone, Default)]
+pub struct FaultConfig {
+    /// Makes the `n`-th upload (counting from 1) since the faults are set fail.
+    pub fail_nth_upload: Option<usize>,
+
+    /// Delay of each upload request.
+    pub latency: Duration,
+
+    /// Probability that an upload fails spuriously, between 0 and 1.
     ///
-    /// Each entry of the hashmap represents the `(name, size)` of the file.
-    files: RefCell<HashMap<String, usize>>,
+    /// Values above 1 are treated as 1, and values below 0 and NaN as 0.
+    pub failure_rate: f64,
+
+    /// Seed for the spurious failures, so that they are reproducible.
+    pub seed: u64,
+}
+
+/// Faults injected into the uploads, with the state to decide which uploads fail.
+#[derive(Debug, Clone)]
+struct Faults {
+    /// Configuration of the faults.
+    config: FaultConfig,
+
+    /// Number of uploads since the faults are set.
+    uploads: usize,
+
+    /// Random number generator for the spurious failures.
+    rng: StdRng,
+}
+
+impl Faults {
+    /// Creates the faults for the given configuration, clamping its failure rate to `0.0..=1.0`.
+    fn new(mut config: FaultConfig) -> Self {
+        config.failure_rate = if config.failure_rate.is_nan() {
+            0.0
+        } else {
+            config.failure_rate.clamp(0.0, 1.0)
+        };
+        let rng = StdRng::seed_from_u64(config.seed);
+        Self {
+            config,
+            uploads: 0,
+            rng,
+        }
+    }
+
+    /// Counts an upload, returning `true` if it should fail.
+    fn inject(&mut self) -> bool {
+        self.uploads += 1;
+        if self.config.fail_nth_upload == Some(self.uploads) {
+            return true;
+        }
+        self.config.failure_rate > 0.0 && self.rng.gen_bool(self.config.failure_rate)
+    }
+}
+
+impl Default for Faults {
+    fn default() -> Self {
+        Self::new(FaultConfig::default())
+    }
+}
+
+/// Callback registered with [`MockStorage::subscribe`].
+type Subscriber = Arc<dyn Fn(&StorageEvent) + Send + Sync>;
 
+/// Mock storage that can be shared between threads.
+pub type SyncMockStorage = MockStorage<RwLock<StorageState>>;
+
+/// Files stored in a [`MockStorage`].
+#[derive(Default, Clone)]
+pub struct StorageState {
     /// Capacity of the storage.
     ///
-    /// The total size of files stored on the storage cannot exceed the capacity.
+    /// The total size of files stored and reserved on the storage cannot exceed the capacity.
     capacity: usize,
+
+    /// Each entry of the hashmap represents the `(path, entry)` of the file, where the path is
+    /// normalized by [`normalize`].
+    files: HashMap<String, FileEntry>,
+
+    /// Number of uploads and downloads so far, used to order files by recency.
+    ticks: u64,
+
+    /// Source of the current time, or `None` to read the system time.
+    time: Option<Arc<dyn Clock>>,
+
+    /// Paths of the evicted files, in the order of eviction.
+    evicted: Vec<String>,
+
+    /// Paths uploaded so far by the running [`MockStorage::upload_batch`], which are not evicted.
+    batch: HashSet<String>,
+
+    /// Known users and their quotas, if any.
+    users: BTreeMap<String, Option<usize>>,
+
+    /// Space reserved for files that have not been uploaded yet, as `(path, size)`.
+    reserved: HashMap<String, usize>,
+
+    /// Partial files of the chunked uploads in progress, as `(path, size)`.
+    ///
+    /// Like reservations, they count towards the capacity and the quotas, but are not files.
+    parts: HashMap<String, usize>,
+
+    /// Faults injected into the uploads.
+    faults: Faults,
+
+    /// Callbacks to notify of events.
+    subscribers: Vec<Subscriber>,
+
+    /// Events that have not been passed to the subscribers yet.
+    events: Vec<StorageEvent>,
+}
+
+impl fmt::Debug for StorageState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StorageState")
+            .field("capacity", &self.capacity)
+            .field("files", &self.files)
+            .field("ticks", &self.ticks)
+            .field("time", &self.time)
+            .field("evicted", &self.evicted)
+            .field("batch", &self.batch)
+            .field("users", &self.users)
+            .field("reserved", &self.reserved)
+            .field("parts", &self.parts)
+            .field("faults", &self.faults)
+            .field("subscribers", &self.subscribers.len())
+            .field("events", &self.events)
+            .finish()
+    }
+}
+
+/// A file stored in a [`MockStorage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "persist", derive(Serialize, Deserialize))]
+pub struct FileEntry {
+    /// Size of the file.
+    pub size: usize,
+
+    /// Contents of the file, or `None` if only its size was uploaded.
+    pub data: Option<Vec<u8>>,
+
+    /// When the file was first uploaded.
+    pub created: SystemTime,
+
+    /// When the file was last uploaded.
+    pub modified: SystemTime,
+
+    /// When the file expires, or `None` if it never expires.
+    ///
+    /// Expired files are kept until they are removed by [`MockStorage::gc`].
+    pub expires: Option<SystemTime>,
+
+    /// Value of [`StorageState::ticks`] when the file was last used.
+    last_used: u64,
+}
+
+/// Snapshot of a [`MockStorage`] saved by [`MockStorage::save_to_json`].
+#[cfg(feature = "persist")]
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    /// Stored files.
+    files: BTreeMap<String, FileEntry>,
+
+    /// Known users and their quotas, if any.
+    users: BTreeMap<String, Option<usize>>,
+}
+
+#[cfg(feature = "persist")]
+impl Snapshot {
+    /// Checks that the snapshot is a state that a storage of the given capacity can be in: the
+    /// paths are normalized and do not conflict with the directory hierarchy, the sizes match the
+    /// contents, the users are valid, and the files fit in the capacity.
+    ///
+    /// The files of a user may exceed the quota of the user, as after [`MockStorage::set_quota`].
+    ///
+    /// Returns `Err` with the reason otherwise.
+    fn validate(&self, capacity: usize) -> Result<(), String> {
+        for (path, entry) in &self.files {
+            if path.is_empty() || normalize(path) != *path {
+                return Err(format!("`{path}` is not a normalized file path"));
+            }
+            if let Some(parent) = path
+                .match_indices('/')
+                .map(|(i, _)| &path[..i])
+                .find(|parent| self.files.contains_key(*parent))
+            {
+                return Err(format!("`{parent}` is both a file and a directory"));
+            }
+            if entry
+                .data
+                .as_ref()
+                .is_some_and(|data| data.len() != entry.size)
+            {
+                return Err(format!("the size of `{path}` does not match its contents"));
+            }
+        }
+
+        if let Some(user) = self.users.keys().find(|user| user_dir(user).is_none()) {
+            return Err(format!("`{user}` is not a valid user name"));
+        }
+
+        let used = self.files.values().map(|entry| entry.size).sum::<usize>();
+        if used > capacity {
+            return Err(format!(
+                "saved files of size {used} exceed the capacity {capacity}"
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl StorageState {
+    /// Returns the current time.
+    fn now(&self) -> SystemTime {
+        self.time
+            .as_ref()
+            .map_or_else(SystemTime::now, |clock| clock.now())
+    }
+
+    /// Returns the total size of the stored files.
+    fn used(&self) -> usize {
+        self.files.values().map(|entry| entry.size).sum()
+    }
+
+    /// Returns the total size of the reserved space and the partial files under the (normalized)
+    /// directory `dir`.
+    fn reserved_in(&self, dir: &str) -> usize {
+        self.reserved
+            .iter()
+            .chain(&self.parts)
+            .filter(|(path, _)| is_inside(path, dir))
+            .map(|(_, size)| size)
+            .sum()
+    }
+
+    /// Checks that `size` more bytes fit in the storage and in the quota of the user owning the
+    /// (normalized) `path`, considering the reserved space.
+    ///
+    /// Returns `Err` with insufficient memory size otherwise.
+    fn check_space(&self, path: &str, size: usize) -> Result<(), usize> {
+        if let Some((user, _)) = path.split_once('/') {
+            if let Some(Some(quota)) = self.users.get(user) {
+                let user_total = self.du(user) + self.reserved_in(user) + size;
+                if user_total > *quota {
+                    return Err(user_total - quota);
+                }
+            }
+        }
+        let total = self.used() + self.reserved_in("") + size;
+        if total > self.capacity {
+            return Err(total - self.capacity);
+        }
+        Ok(())
+    }
+
+    /// Returns the total size of the (normalized) `path`.
+    fn du(&self, path: &str) -> usize {
+        self.files
+            .iter()
+            .filter(|(file, _)| *file == path || is_inside(file, path))
+            .map(|(_, entry)| entry.size)
+            .sum()
+    }
+
+    /// Checks that a file can be stored at the (normalized) `path`, i.e., the path is not the root
+    /// directory, is not a directory, and has no file as a parent directory.
+    ///
+    /// Returns `Err(0)` otherwise.
+    fn check_path(&self, path: &str) -> Result<(), usize> {
+        if path.is_empty()
+            || self
+                .files
+                .keys()
+                .any(|file| is_inside(file, path) || is_inside(path, file))
+        {
+            return Err(0);
+        }
+        Ok(())
+    }
+
+    /// Uploads a file to the (normalized) `path`, evicting other files according to `policy` if
+    /// needed.
+    ///
+    /// If `data` is given, `size` must be its length.
+    ///
+    /// Returns `Err` with insufficient memory size if there is no free space to upload a file, or
+    /// `Err(0)` if the path is invalid (see [`StorageState::check_path`]).
+    fn upload(
+        &mut self,
+        path: String,
+        size: usize,
+        data: Option<Vec<u8>>,
+        policy: EvictionPolicy,
+    ) -> Result<(), usize> {
+        if let Some(data) = &data {
+            debug_assert_eq!(data.len(), size);
+        }
+
+        // 0. Fail if a fault is injected.
+        if self.faults.inject() {
+            return Err(0);
+        }
+
+        // 1. Check that the path does not conflict with the directory hierarchy.
+        self.check_path(&path)?;
+
+        // 2. Check if we are overwriting a file
+        // If it exists, we get its old size. If not, old size is 0.
+        let old_size = self.files.get(&path).map_or(0, |entry| entry.size);
+
+        // 3. Check the quota of the user owning the path, if any.
+        if let Some((user, _)) = path.split_once('/') {
+            if let Some(Some(quota)) = self.users.get(user) {
+                let user_total = (self.du(user) + self.reserved_in(user) - old_size) + size;
+                if user_total > *quota {
+                    return Err(user_total - quota);
+                }
+            }
+        }
+        let current_used = self.used() + self.reserved_in("");
+
+        // 4. Calculate what the NEW total would be
+        // We subtract the old file (reclaiming space) and add the new one.
+        let new_total = (current_used - old_size) + size;
+
+        // 5. Check Capacity, evicting other files if the policy allows it and it helps.
+        if new_total > self.capacity {
+            let reserved = self.reserved_in("");
+            if policy == EvictionPolicy::RejectWhenFull
+                || size + reserved > self.capacity
+                || !self.evict(&path, new_total - self.capacity, policy)
+            {
+                return Err(new_total - self.capacity); // Return how much we are over
+            }
+        }
+
+        // 6. Insert (Overwrite)
+        // insert() automatically overwrites if the key exists, so keep the creation time first.
+        let now = self.now();
+        let created = self.files.get(&path).map_or(now, |entry| entry.created);
+        self.events.push(if self.files.contains_key(&path) {
+            StorageEvent::Overwrite {
+                path: path.clone(),
+                old_size,
+                new_size: size,
+            }
+        } else {
+            StorageEvent::Upload {
+                path: path.clone(),
+                size,
+            }
+        });
+        self.ticks += 1;
+        let _unused = self.files.insert(
+            path,
+            FileEntry {
+                size,
+                data,
+                created,
+                modified: now,
+                expires: None,
+                last_used: self.ticks,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Evicts files other than `keep` and the files of the running batch in the order given by
+    /// `policy` until at least `needed` bytes are freed.
+    ///
+    /// Returns `false`, evicting nothing, if the files that may be evicted are not enough.
+    fn evict(&mut self, keep: &str, needed: usize, policy: EvictionPolicy) -> bool {
+        let mut victims: Vec<(String, usize, u64)> = self
+            .files
+            .iter()
+            .filter(|(path, _)| *path != keep && !self.batch.contains(*path))
+            .map(|(path, entry)| (path.clone(), entry.size, entry.last_used))
+            .collect();
+        if victims.iter().map(|(_, size, _)| size).sum::<usize>() < needed {
+            return false;
+        }
+        match policy {
+            EvictionPolicy::RejectWhenFull => return false,
+            EvictionPolicy::EvictLru => victims.sort_by_key(|(_, _, last_used)| *last_used),
+            EvictionPolicy::EvictLargest => {
+                victims.sort_by(|(a, x, _), (b, y, _)| y.cmp(x).then_with(|| a.cmp(b)))
+            }
+        }
+
+        let mut freed = 0;
+        for (path, size, _) in victims {
+            if freed >= needed {
+                break;
+            }
+            let _unused = self.files.remove(&path);
+            freed += size;
+            self.events.push(StorageEvent::Evict {
+                path: path.clone(),
+                size,
+            });
+            self.evicted.push(path);
+        }
+        true
+    }
+
+    /// Deletes the (normalized) `path` recursively, returning the number of bytes freed.
+    fn delete(&mut self, path: &str) -> usize {
+        let mut deleted: Vec<(String, usize)> = self
+            .files
+            .iter()
+            .filter(|(file, _)| *file == path || is_inside(file, path))
+            .map(|(file, entry)| (file.clone(), entry.size))
+            .collect();
+        deleted.sort();
+
+        let mut freed = 0;
+        for (path, size) in deleted {
+            let _unused = self.files.remove(&path);
+            freed += size;
+            self.events.push(StorageEvent::Delete { path, size });
+        }
+        freed
+    }
+}
+
+/// Interior mutability for the state of a [`MockStorage`].
+pub trait StateCell {
+    /// Wraps the state.
+    fn new(state: StorageState) -> Self;
+
+    /// Calls `f` with a shared reference to the state.
+    fn read<R>(&self, f: impl FnOnce(&StorageState) -> R) -> R;
+
+    /// Calls `f` with a mutable reference to the state.
+    fn write<R>(&self, f: impl FnOnce(&mut StorageState) -> R) -> R;
+}
+
+impl StateCell for RefCell<StorageState> {
+    fn new(state: StorageState) -> Self {
+        RefCell::new(state)
+    }
+
+    fn read<R>(&self, f: impl FnOnce(&StorageState) -> R) -> R {
+        f(&self.borrow())
+    }
+
+    fn write<R>(&self, f: impl FnOnce(&mut StorageState) -> R) -> R {
+        f(&mut self.borrow_mut())
+    }
+}
+
+impl StateCell for RwLock<StorageState> {
+    fn new(state: StorageState) -> Self {
+        RwLock::new(state)
+    }
+
+    fn read<R>(&self, f: impl FnOnce(&StorageState) -> R) -> R {
+        f(&self.read().unwrap())
+    }
+
+    fn write<R>(&self, f: impl FnOnce(&mut StorageState) -> R) -> R {
+        f(&mut self.write().unwrap())
+    }
 }
 
 impl MockStorage {
     /// Creates a new mock storage.
     pub fn new(capacity: usize) -> Self {
+        Self::with_capacity(capacity)
+    }
+}
+
+impl<C: StateCell> MockStorage<C> {
+    /// Creates a new mock storage guarded by `C`.
+    ///
+    /// For example, `SyncMockStorage::with_capacity(100)` creates a storage that can be shared
+    /// between threads.
+    pub fn with_capacity(capacity: usize) -> Self {
         Self {
-            files: RefCell::new(HashMap::new()),
-            capacity,
+            state: C::new(StorageState {
+                capacity,
+                ..StorageState::default()
+            }),
+            policy: EvictionPolicy::default(),
+        }
+    }
+
+    /// Sets the policy applied when an upload would exceed the capacity.
+    pub fn with_eviction_policy(mut self, policy: EvictionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Returns the eviction policy of the storage.
+    pub fn eviction_policy(&self) -> EvictionPolicy {
+        self.policy
+    }
+
+    /// Registers `callback` to be called on every upload, overwrite, deletion, and eviction.
+    ///
+    /// Callbacks are called after the storage is updated, so they may access the storage.
+    pub fn subscribe(&self, callback: impl Fn(&StorageEvent) + Send + Sync + 'static) {
+        self.state
+            .write(|state| state.subscribers.push(Arc::new(callback)));
+    }
+
+    /// Calls `f` with a mutable reference to the state, and then passes the events it caused to
+    /// the subscribers.
+    fn update<R>(&self, f: impl FnOnce(&mut StorageState) -> R) -> R {
+        let (result, events, subscribers) = self.state.write(|state| {
+            let result = f(state);
+            let events = std::mem::take(&mut state.events);
+            (result, events, state.subscribers.clone())
+        });
+        for event in &events {
+            for subscriber in &subscribers {
+                subscriber(event);
+            }
         }
+        result
+    }
+
+    /// Like [`MockStorage::update`], but delays by the latency of the injected faults first.
+    fn update_slowly<R>(&self, f: impl FnOnce(&mut StorageState) -> R) -> R {
+        let latency = self.state.read(|state| state.faults.config.latency);
+        if !latency.is_zero() {
+            thread::sleep(latency);
+        }
+        self.update(f)
+    }
+
+    /// Sets the source of the current time, used for timestamps and expiry.
+    ///
+    /// By default, the storage reads the system time.
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        self.state.write(|state| state.time = Some(clock));
+        self
+    }
+
+    /// Sets the faults to inject into the uploads, replacing the previous ones.
+    ///
+    /// Use `FaultConfig::default()` to stop injecting faults.
+    pub fn set_faults(&self, config: FaultConfig) {
+        self.state.write(|state| state.faults = Faults::new(config));
+    }
+
+    /// Returns the paths of the files evicted so far, in the order of eviction.
+    pub fn evicted(&self) -> Vec<String> {
+        self.state.read(|state| state.evicted.clone())
     }
+
+    /// Returns `true` if a file with the given name is stored.
+    pub fn contains(&self, name: &str) -> bool {
+        self.state
+            .read(|state| state.files.contains_key(&normalize(name)))
+    }
+
+    /// Returns the size of the file with the given name, or `None` if there is no such file.
+    pub fn size_of(&self, name: &str) -> Option<usize> {
+        self.state
+            .read(|state| state.files.get(&normalize(name)).map(|entry| entry.size))
+    }
+
+    /// Uploads a file with the given contents. If a file with the same name already exists in the
+    /// storage, overwrite it.
+    ///
+    /// Returns `Err` with insufficient memory size if there is no free space to upload a file.
+    pub fn upload_data(&self, name: &str, data: Vec<u8>) -> Result<(), usize> {
+        self.update_slowly(|state| {
+            state.upload(normalize(name), data.len(), Some(data), self.policy)
+        })
+    }
+
+    /// Uploads the `(name, size)` files in order, as a single transaction.
+    ///
+    /// If any of the files cannot be uploaded, none of them are stored, and `Err` reports the first
+    /// such file with its insufficient memory size. Files of the batch are never evicted to make
+    /// room for later files of the batch.
+    pub fn upload_batch(&self, files: &[(&str, usize)]) -> Result<(), BatchError> {
+        self.update_slowly(|state| {
+            let backup = state.clone();
+            for (index, (name, size)) in files.iter().enumerate() {
+                let path = normalize(name);
+                let result = state.upload(path.clone(), *size, None, self.policy);
+                let _unused = state.batch.insert(path);
+                if let Err(excess) = result {
+                    // Keep counting the uploads for the injected faults.
+                    let faults = state.faults.clone();
+                    *state = backup;
+                    state.faults = faults;
+                    return Err(BatchError {
+                        index,
+                        name: name.to_string(),
+                        excess,
+                    });
+                }
+            }
+            state.batch.clear();
+            Ok(())
+        })
+    }
+
+    /// Changes the capacity of the storage.
+    ///
+    /// Returns `Err` with insufficient memory size if the files stored and reserved on the storage
+    /// do not fit in the new capacity.
+    pub fn resize_capacity(&self, capacity: usize) -> Result<(), usize> {
+        self.state.write(|state| {
+            let total = state.used() + state.reserved_in("");
+            if total > capacity {
+                return Err(total - capacity);
+            }
+            state.capacity = capacity;
+            Ok(())
+        })
+    }
+
+    /// Reserves space to upload a file of the given size later with [`MockStorage::commit`].
+    /// Reserving space for the same name again replaces the previous reservation.
+    ///
+    /// The reserved space counts towards the capacity and the quotas, but not towards
+    /// [`Storage::used`]. Files are never evicted to make room for a reservation.
+    ///
+    /// Returns `Err` with insufficient memory size if there is no free space to reserve, or
+    /// `Err(0)` if no file can be uploaded to `name` (see [`Storage::upload`]).
+    pub fn reserve(&self, name: &str, size: usize) -> Result<(), usize> {
+        let path = normalize(name);
+        self.state.write(|state| {
+            state.check_path(&path)?;
+            let old = state.reserved.remove(&path);
+            if let Err(excess) = state.check_space(&path, size) {
+                if let Some(old) = old {
+                    let _unused = state.reserved.insert(path, old);
+                }
+                return Err(excess);
+            }
+            let _unused = state.reserved.insert(path, size);
+            Ok(())
+        })
+    }
+
+    /// Uploads the file for which space is reserved, releasing the reservation.
+    ///
+    /// Returns `Err` with insufficient memory size if the file cannot be uploaded anyway, e.g.,
+    /// because the quota of its owner has been lowered since the reservation. Returns `Err(0)` if
+    /// there is no reservation for the file, or if the file cannot be uploaded for another reason
+    /// (see [`Storage::upload`]).
+    pub fn commit(&self, name: &str) -> Result<(), usize> {
+        let path = normalize(name);
+        self.update_slowly(|state| {
+            let size = state.reserved.remove(&path).ok_or(0_usize)?;
+            state.upload(path, size, None, EvictionPolicy::RejectWhenFull)
+        })
+    }
+
+    /// Releases the space reserved for a file.
+    ///
+    /// Returns `false` if there is no reservation for the file.
+    pub fn abort(&self, name: &str) -> bool {
+        self.state
+            .write(|state| state.reserved.remove(&normalize(name)).is_some())
+    }
+
+    /// Returns the total size of the reserved space.
+    pub fn reserved(&self) -> usize {
+        self.state.read(|state| state.reserved.values().sum())
+    }
+
+    /// Uploads a file that expires after `ttl`. If a file with the same name already exists in the
+    /// storage, overwrite it.
+    ///
+    /// Expired files keep occupying space until they are removed by [`MockStorage::gc`].
+    ///
+    /// Returns `Err` with insufficient memory size if there is no free space to upload a file.
+    pub fn upload_with_ttl(&self, name: &str, size: usize, ttl: Duration) -> Result<(), usize> {
+        let path = normalize(name);
+        self.update_slowly(|state| {
+            state.upload(path.clone(), size, None, self.policy)?;
+            let expires = state.now().checked_add(ttl);
+            if let Some(entry) = state.files.get_mut(&path) {
+                entry.expires = expires;
+            }
+            Ok(())
+        })
+    }
+
+    /// Removes the expired files.
+    ///
+    /// Returns the number of bytes reclaimed.
+    pub fn gc(&self) -> usize {
+        self.update(|state| {
+            let now = state.now();
+            let mut expired: Vec<(String, usize)> = state
+                .files
+                .iter()
+                .filter(|(_, entry)| entry.expires.is_some_and(|expires| expires <= now))
+                .map(|(path, entry)| (path.clone(), entry.size))
+                .collect();
+            expired.sort();
+
+            let mut reclaimed = 0;
+            for (path, size) in expired {
+                let _unused = state.files.remove(&path);
+                reclaimed += size;
+                state.events.push(StorageEvent::Expire { path, size });
+            }
+            reclaimed
+        })
+    }
+
+    /// Returns the contents of the file with the given name, or `None` if there is no such file.
+    ///
+    /// A file uploaded only with its size reads as zeros.
+    pub fn download(&self, name: &str) -> Option<Vec<u8>> {
+        self.state.write(|state| {
+            state.ticks += 1;
+            let ticks = state.ticks;
+            let entry = state.files.get_mut(&normalize(name))?;
+            entry.last_used = ticks;
+            Some(entry.data.clone().unwrap_or_else(|| vec![0; entry.size]))
+        })
+    }
+
+    /// Returns the entry of the file with the given name, or `None` if there is no such file.
+    pub fn metadata(&self, name: &str) -> Option<FileEntry> {
+        self.state
+            .read(|state| state.files.get(&normalize(name)).cloned())
+    }
+
+    /// Sets the quota of `user`, i.e., the maximum total size of the files in the namespace of
+    /// `user`.
+    ///
+    /// Files that are already stored are kept even if they exceed the new quota.
+    ///
+    /// Returns `Err(0)` if `user` is not a valid user name, i.e., it is empty or contains `/`.
+    pub fn set_quota(&self, user: &str, quota: usize) -> Result<(), usize> {
+        let user = user_dir(user).ok_or(0_usize)?;
+        self.state.write(|state| {
+            let _unused = state.users.insert(user, Some(quota));
+        });
+        Ok(())
+    }
+
+    /// Returns the quota of `user`, or `None` if `user` has no quota.
+    pub fn quota(&self, user: &str) -> Option<usize> {
+        let user = user_dir(user)?;
+        self.state
+            .read(|state| state.users.get(&user).copied().flatten())
+    }
+
+    /// Returns `true` if some file lies under the directory `path`.
+    ///
+    /// The root directory (`""` or `"/"`) always exists.
+    pub fn is_dir(&self, path: &str) -> bool {
+        let path = normalize(path);
+        path.is_empty()
+            || self
+                .state
+                .read(|state| state.files.keys().any(|file| is_inside(file, &path)))
+    }
+
+    /// Returns the total size of `path`, like `du -s`.
+    ///
+    /// For a file, it is the size of the file. For a directory, it is the sum of the sizes of all
+    /// files under it. Returns 0 if there is nothing at `path`.
+    pub fn du(&self, path: &str) -> usize {
+        self.state.read(|state| state.du(&normalize(path)))
+    }
+
+    /// Saves the files and the users of the storage to the JSON file at `path`.
+    #[cfg(feature = "persist")]
+    pub fn save_to_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let snapshot = self.state.read(|state| Snapshot {
+            files: state
+                .files
+                .iter()
+                .map(|(path, entry)| (path.clone(), entry.clone()))
+                .collect(),
+            users: state.users.clone(),
+        });
+        fs::write(path, serde_json::to_string_pretty(&snapshot)?)
+    }
+
+    /// Replaces the files and the users of the storage with the ones saved to the JSON file at
+    /// `path` by [`MockStorage::save_to_json`].
+    ///
+    /// Pending reservations and partial files are released. Subscribers are not notified.
+    ///
+    /// Returns `Err` if the file cannot be read. Returns `Err` of kind
+    /// [`io::ErrorKind::InvalidData`] if the saved state is not one the storage can be in, e.g.,
+    /// if a path is both a file and a directory, or if the saved files do not fit in the capacity.
+    /// The storage is left unchanged on `Err`.
+    #[cfg(feature = "persist")]
+    pub fn load_from_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let snapshot: Snapshot = serde_json::from_str(&fs::read_to_string(path)?)?;
+
+        self.state.write(|state| {
+            snapshot
+                .validate(state.capacity)
+                .map_err(|reason| io::Error::new(io::ErrorKind::InvalidData, reason))?;
+
+            state.reserved.clear();
+            state.parts.clear();
+            state.ticks = snapshot
+                .files
+                .values()
+                .map(|entry| entry.last_used)
+                .max()
+                .unwrap_or(0);
+            state.files = snapshot.files.into_iter().collect();
+            state.users = snapshot.users;
+            Ok(())
+        })
+    }
+
+    /// Returns the directory hierarchy of the storage.
+    ///
+    /// The root is a directory named `"/"`, and the entries of each directory are sorted by name.
+    pub fn tree(&self) -> File {
+        self.state.read(|state| {
+            let mut entries: Vec<(Vec<&str>, usize)> = state
+                .files
+                .iter()
+                .map(|(path, entry)| (path.split('/').collect(), entry.size))
+                .collect();
+            entries.sort();
+            File::Directory("/".to_string(), build_tree(&entries))
+        })
+    }
+}
+
+/// Normalizes a `/`-separated path by dropping empty components, so that `"/a//b/"` and `"a/b"`
+/// name the same file. The root directory is normalized to `""`.
+fn normalize(path: &str) -> String {
+    path.split('/').filter(|c| !c.is_empty()).join("/")
+}
+
+/// Returns the namespace of `user`, i.e., the name of the top-level directory owned by `user`.
+///
+/// Returns `None` if `user` is not a valid directory name.
+fn user_dir(user: &str) -> Option<String> {
+    (!user.is_empty() && !user.contains('/')).then(|| user.to_string())
+}
+
+/// Returns `true` if the (normalized) `path` lies strictly under the (normalized) directory `dir`.
+fn is_inside(path: &str, dir: &str) -> bool {
+    dir.is_empty()
+        || path
+            .strip_prefix(dir)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Builds the entries of a directory from the sorted paths of the files under it, relative to the
+/// directory.
+fn build_tree(entries: &[(Vec<&str>, usize)]) -> Vec<File> {
+    let mut result = Vec::new();
+    for (name, group) in &entries.iter().chunk_by(|(path, _)| path[0]) {
+        let group: Vec<(Vec<&str>, usize)> = group
+            .map(|(path, size)| (path[1..].to_vec(), *size))
+            .collect();
+        match group.as_slice() {
+            [(rest, size)] if rest.is_empty() => result.push(File::Data(name.to_string(), *size)),
+            _ => result.push(File::Directory(name.to_string(), build_tree(&group))),
+        }
+    }
+    result
 }
 
 /// Trait for storage object.
@@ -38,49 +1038,266 @@ pub trait Storage {
     /// Returns `Err` with insufficient memory size if there is no free space to upload a file.
     fn upload(&self, name: &str, size: usize) -> Result<(), usize>;
 
+    /// Uploads a file to the namespace of `user`. If a file with the same name already exists in
+    /// the namespace, overwrite it.
+    ///
+    /// Returns `Err` with insufficient memory size if there is no free space to upload a file,
+    /// either in the storage or in the quota of `user`. Returns `Err(0)` if `user` is not a valid
+    /// user name, i.e., it is empty or contains `/`, or if `name` is empty.
+    ///
+    /// By default, uploads the file to `user/name` with [`Storage::upload`].
+    fn upload_as(&self, user: &str, name: &str, size: usize) -> Result<(), usize> {
+        let user = user_dir(user).ok_or(0_usize)?;
+        let name = normalize(name);
+        if name.is_empty() {
+            return Err(0);
+        }
+        self.upload(&format!("{user}/{name}"), size)
+    }
+
     /// Returns the used memory size of the storage.
     fn used(&self) -> usize;
 
+    /// Returns the memory size used by `user`, or 0 if `user` is not a valid user name.
+    ///
+    /// By default, sums up the sizes of the files in the namespace of `user` in [`Storage::list`].
+    fn used_by(&self, user: &str) -> usize {
+        let Some(user) = user_dir(user) else {
+            return 0;
+        };
+        self.list()
+            .iter()
+            .filter(|(name, _)| is_inside(&normalize(name), &user))
+            .map(|(_, size)| size)
+            .sum()
+    }
+
+    /// Returns the users of the storage, sorted by name.
+    ///
+    /// By default, returns the top-level directories in [`Storage::list`].
+    fn users(&self) -> Vec<String> {
+        self.list()
+            .iter()
+            .filter_map(|(name, _)| {
+                normalize(name)
+                    .split_once('/')
+                    .map(|(user, _)| user.to_string())
+            })
+            .sorted()
+            .dedup()
+            .collect()
+    }
+
     /// Returns the capacity of the storage.
     fn capacity(&self) -> usize;
+
+    /// Returns the `(name, size)` of every stored file, sorted by name.
+    fn list(&self) -> Vec<(String, usize)>;
+
+    /// Deletes a file.
+    ///
+    /// Returns the number of bytes freed.
+    fn delete(&self, name: &str) -> usize;
+
+    /// Uploads the partial file of a chunked upload to `name` (see [`FileUploader::begin_upload`]),
+    /// replacing the previous partial file of `name` if any.
+    ///
+    /// Partial files are kept apart from the files: they are not listed, and do not replace or
+    /// evict any file.
+    ///
+    /// Returns `Err` with insufficient memory size if there is no free space to upload the partial
+    /// file, or `Err(0)` if no file can be uploaded to `name` (see [`Storage::upload`]).
+    fn upload_part(&self, name: &str, size: usize) -> Result<(), usize>;
+
+    /// Returns the size of the partial file of `name`, or `None` if there is no such partial file.
+    fn part_size(&self, name: &str) -> Option<usize>;
+
+    /// Deletes the partial file of `name`.
+    ///
+    /// Returns the number of bytes freed.
+    fn delete_part(&self, name: &str) -> usize;
 }
 
-impl Storage for MockStorage {
+impl<C: StateCell> Storage for MockStorage<C> {
+    /// Returns `Err(0)` if the path is empty, if the path is a directory, or if a parent directory
+    /// in the path is a file.
     fn upload(&self, _name: &str, _size: usize) -> Result<(), usize> {
-        // We cannot call self.used() here because it tries to borrow() again (panic!).
-        let mut files = self.files.borrow_mut();
+        // We cannot call self.used() inside because it tries to borrow() again (panic!).
+        self.update_slowly(|state| state.upload(normalize(_name), _size, None, self.policy))
+    }
 
-        // 2. Calculate current usage manually from our mutable reference
-        let current_used: usize = files.values().sum();
+    fn upload_as(&self, user: &str, name: &str, size: usize) -> Result<(), usize> {
+        let user = user_dir(user).ok_or(0_usize)?;
+        let name = normalize(name);
+        if name.is_empty() {
+            return Err(0);
+        }
+        let path = format!("{user}/{name}");
+        self.update_slowly(|state| {
+            let _unused = state.users.entry(user).or_default();
+            state.upload(path, size, None, self.policy)
+        })
+    }
 
-        // 3. Check if we are overwriting a file
-        // If it exists, we get its old size. If not, old size is 0.
-        let old_size = files.get(_name).copied().unwrap_or(0);
+    fn used(&self) -> usize {
+        self.state.read(StorageState::used)
+    }
 
-        // 4. Calculate what the NEW total would be
-        // We subtract the old file (reclaiming space) and add the new one.
-        let new_total = (current_used - old_size) + _size;
+    fn used_by(&self, user: &str) -> usize {
+        let Some(user) = user_dir(user) else {
+            return 0;
+        };
+        self.state.read(|state| state.du(&user))
+    }
 
-        // 5. Check Capacity
-        if new_total > self.capacity {
-            return Err(new_total - self.capacity); // Return how much we are over
-        }
+    fn users(&self) -> Vec<String> {
+        self.state
+            .read(|state| state.users.keys().cloned().collect())
+    }
 
-        // 6. Insert (Overwrite)
-        // insert() automatically overwrites if the key exists.
-        let _unused = files.insert(_name.to_string(), _size);
+    fn capacity(&self) -> usize {
+        self.state.read(|state| state.capacity)
+    }
 
-        Ok(())
+    fn list(&self) -> Vec<(String, usize)> {
+        self.state.read(|state| {
+            let mut list: Vec<(String, usize)> = state
+                .files
+                .iter()
+                .map(|(name, entry)| (name.clone(), entry.size))
+                .collect();
+            list.sort();
+            list
+        })
+    }
+
+    /// Deletes the file at `name`, or the directory at `name` together with everything under it.
+    fn delete(&self, name: &str) -> usize {
+        let path = normalize(name);
+        self.update(|state| state.delete(&path))
+    }
+
+    /// Partial files count towards the capacity and the quotas like reservations (see
+    /// [`MockStorage::reserve`]), and are subject to the injected faults like uploads.
+    fn upload_part(&self, name: &str, size: usize) -> Result<(), usize> {
+        let path = normalize(name);
+        self.update_slowly(|state| {
+            if state.faults.inject() {
+                return Err(0);
+            }
+            state.check_path(&path)?;
+            let old = state.parts.remove(&path);
+            if let Err(excess) = state.check_space(&path, size) {
+                if let Some(old) = old {
+                    let _unused = state.parts.insert(path, old);
+                }
+                return Err(excess);
+            }
+            let _unused = state.parts.insert(path, size);
+            Ok(())
+        })
+    }
+
+    fn part_size(&self, name: &str) -> Option<usize> {
+        self.state
+            .read(|state| state.parts.get(&normalize(name)).copied())
+    }
+
+    fn delete_part(&self, name: &str) -> usize {
+        self.state
+            .write(|state| state.parts.remove(&normalize(name)).unwrap_or(0))
+    }
+}
+
+impl<T: Storage + ?Sized> Storage for &T {
+    fn upload(&self, name: &str, size: usize) -> Result<(), usize> {
+        (**self).upload(name, size)
+    }
+
+    fn upload_as(&self, user: &str, name: &str, size: usize) -> Result<(), usize> {
+        (**self).upload_as(user, name, size)
     }
 
     fn used(&self) -> usize {
-        let files = self.files.borrow();
+        (**self).used()
+    }
 
-        files.values().sum()
+    fn used_by(&self, user: &str) -> usize {
+        (**self).used_by(user)
+    }
+
+    fn users(&self) -> Vec<String> {
+        (**self).users()
     }
 
     fn capacity(&self) -> usize {
-        self.capacity
+        (**self).capacity()
+    }
+
+    fn list(&self) -> Vec<(String, usize)> {
+        (**self).list()
+    }
+
+    fn delete(&self, name: &str) -> usize {
+        (**self).delete(name)
+    }
+
+    fn upload_part(&self, name: &str, size: usize) -> Result<(), usize> {
+        (**self).upload_part(name, size)
+    }
+
+    fn part_size(&self, name: &str) -> Option<usize> {
+        (**self).part_size(name)
+    }
+
+    fn delete_part(&self, name: &str) -> usize {
+        (**self).delete_part(name)
+    }
+}
+
+impl<T: Storage + ?Sized> Storage for Arc<T> {
+    fn upload(&self, name: &str, size: usize) -> Result<(), usize> {
+        (**self).upload(name, size)
+    }
+
+    fn upload_as(&self, user: &str, name: &str, size: usize) -> Result<(), usize> {
+        (**self).upload_as(user, name, size)
+    }
+
+    fn used(&self) -> usize {
+        (**self).used()
+    }
+
+    fn used_by(&self, user: &str) -> usize {
+        (**self).used_by(user)
+    }
+
+    fn users(&self) -> Vec<String> {
+        (**self).users()
+    }
+
+    fn capacity(&self) -> usize {
+        (**self).capacity()
+    }
+
+    fn list(&self) -> Vec<(String, usize)> {
+        (**self).list()
+    }
+
+    fn delete(&self, name: &str) -> usize {
+        (**self).delete(name)
+    }
+
+    fn upload_part(&self, name: &str, size: usize) -> Result<(), usize> {
+        (**self).upload_part(name, size)
+    }
+
+    fn part_size(&self, name: &str) -> Option<usize> {
+        (**self).part_size(name)
+    }
+
+    fn delete_part(&self, name: &str) -> usize {
+        (**self).delete_part(name)
     }
 }
 
@@ -102,6 +1319,166 @@ impl<'a, T: Storage> FileUploader<'a, T> {
     pub fn upload(&self, _name: &str, _size: usize) -> Result<(), usize> {
         self.storage.upload(_name, _size)
     }
+
+    /// Begins to upload a file of `total_size` to the internal storage in chunks.
+    ///
+    /// The chunks are staged in the partial file of `name` (see [`Storage::upload_part`]), which
+    /// starts empty and grows with each chunk written through the returned handle, so the capacity
+    /// of the storage is enforced chunk by chunk. The existing file of the same name is kept intact
+    /// until [`ChunkedUpload::finish`] replaces it. Dropping the handle without calling
+    /// [`ChunkedUpload::finish`] or [`ChunkedUpload::abort`] leaves the partial file.
+    ///
+    /// Returns `Err` with insufficient memory size if the empty partial file cannot be created, or
+    /// `Err(0)` if no file can be uploaded to `name` or another chunked upload to `name` is in
+    /// progress.
+    pub fn begin_upload(
+        &self,
+        name: &str,
+        total_size: usize,
+    ) -> Result<ChunkedUpload<'a, T>, usize> {
+        if self.storage.part_size(name).is_some() {
+            return Err(0);
+        }
+        self.storage.upload_part(name, 0)?;
+        Ok(ChunkedUpload {
+            storage: self.storage,
+            name: name.to_string(),
+            total_size,
+            written: 0,
+        })
+    }
+}
+
+/// Error of a chunked upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadError {
+    /// There is no free space to write the chunk. It contains the insufficient memory size.
+    InsufficientSpace(usize),
+
+    /// The chunk exceeds the total size of the file. It contains the exceeding size.
+    TooLarge(usize),
+
+    /// The file is finished before all of it is written. It contains the remaining size.
+    Incomplete(usize),
+}
+
+/// Handle of a file being uploaded in chunks, returned by [`FileUploader::begin_upload`].
+#[derive(Debug)]
+pub struct ChunkedUpload<'a, T: Storage> {
+    storage: &'a T,
+    name: String,
+    total_size: usize,
+    written: usize,
+}
+
+impl<T: Storage> ChunkedUpload<'_, T> {
+    /// Returns the size written so far.
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Returns the size that remains to be written.
+    pub fn remaining(&self) -> usize {
+        self.total_size - self.written
+    }
+
+    /// Writes a chunk of the given size.
+    ///
+    /// If it fails, nothing is written, and the chunk may be written again later.
+    pub fn write_chunk(&mut self, size: usize) -> Result<(), UploadError> {
+        if size > self.remaining() {
+            return Err(UploadError::TooLarge(size - self.remaining()));
+        }
+        self.storage
+            .upload_part(&self.name, self.written + size)
+            .map_err(UploadError::InsufficientSpace)?;
+        self.written += size;
+        Ok(())
+    }
+
+    /// Finishes the upload, replacing the file with the partial file.
+    ///
+    /// Returns `Err` if the whole file is not written yet, or if the file cannot be replaced, e.g.,
+    /// because of an injected fault. In either case, the partial file is deleted and the existing
+    /// file of the same name is kept intact.
+    pub fn finish(self) -> Result<(), UploadError> {
+        if self.remaining() > 0 {
+            let remaining = self.remaining();
+            self.abort();
+            return Err(UploadError::Incomplete(remaining));
+        }
+        // The partial file is deleted first, so that the space it frees is available.
+        let _unused = self.storage.delete_part(&self.name);
+        self.storage
+            .upload(&self.name, self.total_size)
+            .map_err(UploadError::InsufficientSpace)
+    }
+
+    /// Aborts the upload, deleting the partial file. The existing file of the same name is kept
+    /// intact.
+    pub fn abort(self) {
+        let _unused = self.storage.delete_part(&self.name);
+    }
+}
+
+/// Usage report of a storage, generated by [`UsageAnalyzer::report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageReport {
+    /// Capacity of the storage.
+    pub capacity: usize,
+
+    /// Used memory size of the storage.
+    pub used: usize,
+
+    /// Free memory size of the storage.
+    pub free: usize,
+
+    /// The `(name, size)` of the largest files, in descending order of size.
+    ///
+    /// If the size is the same, the files are sorted by name.
+    pub largest: Vec<(String, usize)>,
+
+    /// Number and total size of the files of each size range, in ascending order of size.
+    ///
+    /// Only size ranges with at least one file are reported.
+    pub histogram: Vec<SizeBucket>,
+}
+
+/// Files whose sizes are in `min..max`.
+///
+/// Size ranges are `0..1`, `1..2`, `2..4`, `4..8`, and so on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeBucket {
+    /// Minimum size of the files (inclusive).
+    pub min: usize,
+
+    /// Maximum size of the files (exclusive).
+    pub max: usize,
+
+    /// Number of the files.
+    pub files: usize,
+
+    /// Total size of the files.
+    pub used: usize,
+}
+
+impl SizeBucket {
+    /// Returns the empty bucket containing `size`.
+    fn of(size: usize) -> Self {
+        let (min, max) = match size {
+            0 => (0, 1),
+            _ => {
+                let min: usize = 1 << size.ilog2();
+                (min, min.saturating_mul(2))
+            }
+        };
+        Self {
+            min,
+            max,
+            files: 0,
+            used: 0,
+        }
+    }
 }
 
 /// Storage usage analyzer.
@@ -122,4 +1499,52 @@ impl<'a, T: Storage> UsageAnalyzer<'a, T> {
         let used_ratio = self.storage.used() as f64 / self.storage.capacity() as f64;
         used_ratio < self.bound
     }
+
+    /// Returns the report of the usage of the internal storage, with the `top_n` largest files.
+    pub fn report(&self, top_n: usize) -> UsageReport {
+        let capacity = self.storage.capacity();
+        let used = self.storage.used();
+        let files = self.storage.list();
+
+        let mut largest = files.clone();
+        largest.sort_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+        largest.truncate(top_n);
+
+        let mut histogram: BTreeMap<usize, SizeBucket> = BTreeMap::new();
+        for (_, size) in files {
+            let bucket = SizeBucket::of(size);
+            let bucket = histogram.entry(bucket.min).or_insert(bucket);
+            bucket.files += 1;
+            bucket.used += size;
+        }
+
+        UsageReport {
+            capacity,
+            used,
+            free: capacity.saturating_sub(used),
+            largest,
+            histogram: histogram.into_values().collect(),
+        }
+    }
+
+    /// Returns the `(name, size)` of the files larger than `threshold`, sorted by name.
+    pub fn files_over(&self, threshold: usize) -> Vec<(String, usize)> {
+        self.storage
+            .list()
+            .into_iter()
+            .filter(|(_, size)| *size > threshold)
+            .collect()
+    }
+
+    /// Returns the `(user, used)` of every user of the internal storage, sorted by user.
+    pub fn usage_by_user(&self) -> Vec<(String, usize)> {
+        self.storage
+            .users()
+            .into_iter()
+            .map(|user| {
+                let used = self.storage.used_by(&user);
+                (user, used)
+            })
+            .collect()
+    }
 }
@@ -2,6 +2,11 @@
 
 #[cfg(test)]
 mod test_mock_storage {
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, Instant, SystemTime};
+
+    use crate::assignments::assignment10::small_exercises::File;
     use crate::assignments::assignment11::mock_storage::*;
 
     #[test]
@@ -28,4 +33,1011 @@ mod test_mock_storage {
         assert!(uploader1.upload("file3.txt", 10).is_ok());
         assert!(usage_analyzer.is_usage_under_bound());
     }
+
+    #[test]
+    fn test_mock_storage_list() {
+        let mock_storage = MockStorage::new(100);
+        assert!(mock_storage.list().is_empty());
+
+        let uploader = FileUploader::new(&mock_storage);
+        assert!(uploader.upload("b.txt", 20).is_ok());
+        assert!(uploader.upload("a.txt", 30).is_ok());
+        assert!(uploader.upload("c.txt", 60).is_err());
+
+        assert_eq!(
+            mock_storage.list(),
+            vec![("a.txt".to_string(), 30), ("b.txt".to_string(), 20)]
+        );
+        assert!(mock_storage.contains("a.txt"));
+        assert!(!mock_storage.contains("c.txt"));
+        assert_eq!(mock_storage.size_of("b.txt"), Some(20));
+        assert_eq!(mock_storage.size_of("c.txt"), None);
+
+        assert!(uploader.upload("b.txt", 5).is_ok());
+        assert_eq!(mock_storage.size_of("b.txt"), Some(5));
+    }
+
+    #[test]
+    fn test_mock_storage_directory() {
+        let mock_storage = MockStorage::new(100);
+        let uploader = FileUploader::new(&mock_storage);
+
+        assert!(uploader.upload("/home/a/x.txt", 10).is_ok());
+        assert!(uploader.upload("home/a/y.txt", 20).is_ok());
+        assert!(uploader.upload("home/b//z.txt", 30).is_ok());
+        assert!(uploader.upload("readme", 5).is_ok());
+
+        assert!(mock_storage.contains("home/a/x.txt"));
+        assert!(mock_storage.is_dir("/home/a/"));
+        assert!(!mock_storage.is_dir("home/a/x.txt"));
+        assert!(!mock_storage.is_dir("home/c"));
+
+        assert_eq!(mock_storage.du("home/a"), 30);
+        assert_eq!(mock_storage.du("home"), 60);
+        assert_eq!(mock_storage.du("/"), 65);
+        assert_eq!(mock_storage.du("readme"), 5);
+        assert_eq!(mock_storage.du("home/c"), 0);
+
+        let tree = format!("{:?}", mock_storage.tree());
+        let expected = File::Directory(
+            "/".to_string(),
+            vec![
+                File::Directory(
+                    "home".to_string(),
+                    vec![
+                        File::Directory(
+                            "a".to_string(),
+                            vec![
+                                File::Data("x.txt".to_string(), 10),
+                                File::Data("y.txt".to_string(), 20),
+                            ],
+                        ),
+                        File::Directory("b".to_string(), vec![File::Data("z.txt".to_string(), 30)]),
+                    ],
+                ),
+                File::Data("readme".to_string(), 5),
+            ],
+        );
+        assert_eq!(tree, format!("{expected:?}"));
+
+        assert_eq!(mock_storage.delete("home/a"), 30);
+        assert!(!mock_storage.is_dir("home/a"));
+        assert_eq!(mock_storage.used(), 35);
+        assert_eq!(mock_storage.delete("home/a"), 0);
+        assert_eq!(mock_storage.delete("readme"), 5);
+        assert_eq!(mock_storage.list(), vec![("home/b/z.txt".to_string(), 30)]);
+    }
+
+    #[test]
+    fn test_mock_storage_file_as_directory() {
+        let mock_storage = MockStorage::new(100);
+        assert!(mock_storage.upload("a", 10).is_ok());
+        assert_eq!(mock_storage.upload("a/b", 10), Err(0));
+        assert_eq!(mock_storage.upload("a/b/c", 10), Err(0));
+        assert_eq!(mock_storage.used(), 10);
+    }
+
+    #[test]
+    fn test_mock_storage_invalid_path() {
+        let mock_storage = MockStorage::new(100);
+        assert_eq!(mock_storage.upload("", 10), Err(0));
+        assert_eq!(mock_storage.upload("/", 10), Err(0));
+
+        assert!(mock_storage.upload("a/b", 10).is_ok());
+        assert_eq!(mock_storage.upload("a", 10), Err(0));
+        assert_eq!(mock_storage.upload("/a/", 10), Err(0));
+        assert_eq!(mock_storage.size_of("a/b"), Some(10));
+        assert_eq!(mock_storage.used(), 10);
+
+        // The storage keeps working after the invalid uploads.
+        assert!(mock_storage.upload("a/c", 20).is_ok());
+        assert_eq!(mock_storage.used(), 30);
+    }
+
+    #[test]
+    fn test_sync_mock_storage() {
+        let mock_storage = SyncMockStorage::with_capacity(1000);
+        let usage_analyzer = UsageAnalyzer::new(&mock_storage, 0.5);
+
+        thread::scope(|s| {
+            for t in 0..4 {
+                let mock_storage = &mock_storage;
+                let _unused = s.spawn(move || {
+                    let uploader = FileUploader::new(mock_storage);
+                    for i in 0..10 {
+                        assert!(uploader.upload(&format!("{t}/{i}"), 10).is_ok());
+                    }
+                });
+            }
+        });
+
+        assert_eq!(mock_storage.used(), 400);
+        assert!(usage_analyzer.is_usage_under_bound());
+        assert_eq!(mock_storage.list().len(), 40);
+    }
+
+    #[test]
+    fn test_sync_mock_storage_invalid_requests() {
+        let mock_storage = Arc::new(SyncMockStorage::with_capacity(100));
+        assert!(mock_storage.upload("dir/file", 10).is_ok());
+
+        // Invalid requests fail without poisoning the lock shared by the threads.
+        let handles = (0..4)
+            .map(|t| {
+                let mock_storage = Arc::clone(&mock_storage);
+                thread::spawn(move || {
+                    assert_eq!(mock_storage.upload("", 1), Err(0));
+                    assert_eq!(mock_storage.upload("dir", 1), Err(0));
+                    assert_eq!(mock_storage.upload("dir/file/x", 1), Err(0));
+                    assert_eq!(mock_storage.upload_as("a/b", "x", 1), Err(0));
+                    assert_eq!(mock_storage.used_by(""), 0);
+                    assert_eq!(mock_storage.commit(&format!("none{t}")), Err(0));
+                    mock_storage.upload(&format!("file{t}"), 10)
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            assert!(handle.join().unwrap().is_ok());
+        }
+        assert_eq!(mock_storage.used(), 50);
+        assert_eq!(mock_storage.list().len(), 5);
+    }
+
+    #[test]
+    fn test_storage_blanket_impls() {
+        let mock_storage = Arc::new(SyncMockStorage::with_capacity(100));
+
+        let handles = (0..4)
+            .map(|t| {
+                let mock_storage = Arc::clone(&mock_storage);
+                thread::spawn(move || {
+                    let uploader = FileUploader::new(&mock_storage);
+                    uploader.upload(&format!("file{t}"), 30)
+                })
+            })
+            .collect::<Vec<_>>();
+        let results = handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 3);
+        assert!(results.contains(&Err(20)));
+        assert_eq!(mock_storage.used(), 90);
+
+        let mock_storage = MockStorage::new(100);
+        let by_ref = &mock_storage;
+        let uploader = FileUploader::new(&by_ref);
+        assert!(uploader.upload("file", 60).is_ok());
+        assert!(!UsageAnalyzer::new(&by_ref, 0.5).is_usage_under_bound());
+    }
+
+    #[test]
+    fn test_invalid_user() {
+        let mock_storage = SyncMockStorage::with_capacity(100);
+        assert_eq!(mock_storage.upload_as("", "a.txt", 10), Err(0));
+        assert_eq!(mock_storage.upload_as("a/b", "a.txt", 10), Err(0));
+        assert_eq!(mock_storage.upload_as("alice", "", 10), Err(0));
+        assert_eq!(mock_storage.upload_as("alice", "/", 10), Err(0));
+        assert_eq!(mock_storage.used_by(""), 0);
+        assert_eq!(mock_storage.used_by("a/b"), 0);
+        assert_eq!(mock_storage.quota("a/b"), None);
+        assert_eq!(mock_storage.set_quota("", 10), Err(0));
+        assert_eq!(mock_storage.set_quota("a/b", 10), Err(0));
+        assert!(mock_storage.users().is_empty());
+
+        // The storage keeps working after the invalid requests.
+        assert!(mock_storage.upload_as("alice", "a.txt", 10).is_ok());
+        assert_eq!(mock_storage.used_by("alice"), 10);
+    }
+
+    /// Storage that only implements the required methods of [`Storage`].
+    #[derive(Debug)]
+    struct ListStorage {
+        capacity: usize,
+        files: Mutex<Vec<(String, usize)>>,
+        parts: Mutex<Vec<(String, usize)>>,
+    }
+
+    impl Storage for ListStorage {
+        fn upload(&self, name: &str, size: usize) -> Result<(), usize> {
+            let mut files = self.files.lock().unwrap();
+            files.retain(|(file, _)| file != name);
+            let used = files.iter().map(|(_, size)| size).sum::<usize>();
+            if used + size > self.capacity {
+                return Err(used + size - self.capacity);
+            }
+            files.push((name.to_string(), size));
+            files.sort();
+            Ok(())
+        }
+
+        fn used(&self) -> usize {
+            self.files
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(_, size)| size)
+                .sum()
+        }
+
+        fn capacity(&self) -> usize {
+            self.capacity
+        }
+
+        fn list(&self) -> Vec<(String, usize)> {
+            self.files.lock().unwrap().clone()
+        }
+
+        fn delete(&self, name: &str) -> usize {
+            let mut files = self.files.lock().unwrap();
+            let used = files.iter().map(|(_, size)| size).sum::<usize>();
+            files.retain(|(file, _)| file != name);
+            used - files.iter().map(|(_, size)| size).sum::<usize>()
+        }
+
+        fn upload_part(&self, name: &str, size: usize) -> Result<(), usize> {
+            let _unused = self.delete_part(name);
+            self.parts.lock().unwrap().push((name.to_string(), size));
+            Ok(())
+        }
+
+        fn part_size(&self, name: &str) -> Option<usize> {
+            self.parts
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(part, _)| part == name)
+                .map(|(_, size)| *size)
+        }
+
+        fn delete_part(&self, name: &str) -> usize {
+            let size = self.part_size(name).unwrap_or(0);
+            self.parts.lock().unwrap().retain(|(part, _)| part != name);
+            size
+        }
+    }
+
+    #[test]
+    fn test_storage_default_methods() {
+        let storage = ListStorage {
+            capacity: 100,
+            files: Mutex::new(Vec::new()),
+            parts: Mutex::new(Vec::new()),
+        };
+        assert!(storage.upload_as("bob", "a.txt", 20).is_ok());
+        assert!(storage.upload_as("alice", "a.txt", 10).is_ok());
+        assert!(storage.upload_as("alice", "b/c.txt", 30).is_ok());
+        assert!(storage.upload("shared.txt", 5).is_ok());
+        assert_eq!(storage.upload_as("a/b", "a.txt", 10), Err(0));
+        assert_eq!(storage.upload_as("alice", "d.txt", 40), Err(5));
+
+        assert_eq!(storage.used_by("alice"), 40);
+        assert_eq!(storage.used_by("bob"), 20);
+        assert_eq!(storage.used_by("carol"), 0);
+        assert_eq!(
+            storage.users(),
+            vec!["alice".to_string(), "bob".to_string()]
+        );
+        assert_eq!(
+            UsageAnalyzer::new(&storage, 0.5).usage_by_user(),
+            vec![("alice".to_string(), 40), ("bob".to_string(), 20)]
+        );
+    }
+
+    #[test]
+    fn test_eviction_policy() {
+        let mock_storage = MockStorage::new(100);
+        assert_eq!(
+            mock_storage.eviction_policy(),
+            EvictionPolicy::RejectWhenFull
+        );
+        assert!(mock_storage.upload("a", 60).is_ok());
+        assert_eq!(mock_storage.upload("b", 50), Err(10));
+        assert!(mock_storage.evicted().is_empty());
+
+        let mock_storage = MockStorage::new(100).with_eviction_policy(EvictionPolicy::EvictLru);
+        assert!(mock_storage.upload("a", 30).is_ok());
+        assert!(mock_storage.upload("b", 40).is_ok());
+        assert!(mock_storage.upload("c", 20).is_ok());
+        assert!(mock_storage.upload("a", 30).is_ok());
+        assert!(mock_storage.upload("d", 50).is_ok());
+        assert_eq!(mock_storage.evicted(), vec!["b".to_string()]);
+        assert!(mock_storage.upload("e", 40).is_ok());
+        assert_eq!(
+            mock_storage.evicted(),
+            vec!["b".to_string(), "c".to_string(), "a".to_string()]
+        );
+        assert_eq!(
+            mock_storage.list(),
+            vec![("d".to_string(), 50), ("e".to_string(), 40)]
+        );
+
+        // A file larger than the whole storage is rejected without evicting anything.
+        assert_eq!(mock_storage.upload("f", 101), Err(91));
+        assert_eq!(mock_storage.used(), 90);
+
+        let mock_storage = MockStorage::new(100).with_eviction_policy(EvictionPolicy::EvictLargest);
+        assert!(mock_storage.upload("a", 10).is_ok());
+        assert!(mock_storage.upload("b", 50).is_ok());
+        assert!(mock_storage.upload("c", 30).is_ok());
+        assert!(mock_storage.upload("d", 20).is_ok());
+        assert_eq!(mock_storage.evicted(), vec!["b".to_string()]);
+        assert!(mock_storage.upload("d", 70).is_ok());
+        assert_eq!(
+            mock_storage.evicted(),
+            vec!["b".to_string(), "c".to_string()]
+        );
+        assert_eq!(mock_storage.used(), 80);
+    }
+
+    #[test]
+    fn test_file_contents() {
+        let mock_storage = MockStorage::new(10);
+
+        assert!(mock_storage
+            .upload_data("hello.txt", b"hello".to_vec())
+            .is_ok());
+        assert_eq!(mock_storage.download("hello.txt"), Some(b"hello".to_vec()));
+        assert_eq!(mock_storage.size_of("hello.txt"), Some(5));
+        assert_eq!(
+            mock_storage.upload_data("world.txt", b"world!".to_vec()),
+            Err(1)
+        );
+
+        assert!(mock_storage.upload("zeros", 3).is_ok());
+        assert_eq!(mock_storage.download("zeros"), Some(vec![0; 3]));
+        assert_eq!(mock_storage.metadata("zeros").unwrap().data, None);
+        assert_eq!(mock_storage.download("missing"), None);
+
+        let entry = mock_storage.metadata("hello.txt").unwrap();
+        assert_eq!(entry.created, entry.modified);
+        assert!(mock_storage
+            .upload_data("hello.txt", b"hi".to_vec())
+            .is_ok());
+        let new_entry = mock_storage.metadata("hello.txt").unwrap();
+        assert_eq!(new_entry.size, 2);
+        assert_eq!(new_entry.data, Some(b"hi".to_vec()));
+        assert_eq!(new_entry.created, entry.created);
+        assert!(new_entry.modified >= entry.modified);
+    }
+
+    #[test]
+    fn test_download_counts_as_use() {
+        let mock_storage = MockStorage::new(100).with_eviction_policy(EvictionPolicy::EvictLru);
+        assert!(mock_storage.upload("a", 40).is_ok());
+        assert!(mock_storage.upload("b", 40).is_ok());
+        assert!(mock_storage.download("a").is_some());
+        assert!(mock_storage.upload("c", 40).is_ok());
+        assert_eq!(mock_storage.evicted(), vec!["b".to_string()]);
+    }
+
+    #[test]
+    fn test_user_quota() {
+        let mock_storage = MockStorage::new(100);
+        let usage_analyzer = UsageAnalyzer::new(&mock_storage, 0.75);
+        assert!(mock_storage.set_quota("alice", 30).is_ok());
+        assert_eq!(mock_storage.quota("alice"), Some(30));
+        assert_eq!(mock_storage.quota("bob"), None);
+
+        assert!(mock_storage.upload_as("alice", "a.txt", 20).is_ok());
+        assert_eq!(mock_storage.upload_as("alice", "b.txt", 20), Err(10));
+        assert!(mock_storage.upload_as("alice", "a.txt", 30).is_ok());
+        assert!(mock_storage.upload_as("bob", "a.txt", 50).is_ok());
+        assert_eq!(mock_storage.upload_as("bob", "b.txt", 30), Err(10));
+
+        // Files in the namespace of a user count towards the quota however they are uploaded.
+        assert_eq!(mock_storage.upload("alice/c.txt", 1), Err(1));
+        assert!(mock_storage.upload("shared.txt", 10).is_ok());
+
+        assert!(mock_storage.contains("alice/a.txt"));
+        assert_eq!(mock_storage.used_by("alice"), 30);
+        assert_eq!(mock_storage.used_by("bob"), 50);
+        assert_eq!(mock_storage.used_by("carol"), 0);
+        assert_eq!(mock_storage.used(), 90);
+        assert_eq!(
+            usage_analyzer.usage_by_user(),
+            vec![("alice".to_string(), 30), ("bob".to_string(), 50)]
+        );
+    }
+
+    #[test]
+    fn test_subscribe() {
+        let mock_storage = Arc::new(
+            SyncMockStorage::with_capacity(100).with_eviction_policy(EvictionPolicy::EvictLargest),
+        );
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = Arc::clone(&events);
+        mock_storage.subscribe(move |event| events_clone.lock().unwrap().push(event.clone()));
+
+        // Callbacks may access the storage.
+        let used = Arc::new(Mutex::new(Vec::new()));
+        let used_clone = Arc::clone(&used);
+        let mock_storage_clone = Arc::clone(&mock_storage);
+        mock_storage.subscribe(move |_| used_clone.lock().unwrap().push(mock_storage_clone.used()));
+
+        assert!(mock_storage.upload("dir/a", 60).is_ok());
+        assert!(mock_storage.upload("dir/b", 30).is_ok());
+        assert!(mock_storage.upload("dir/b", 20).is_ok());
+        assert!(mock_storage.upload("c", 30).is_ok());
+        assert!(mock_storage.upload("d", 200).is_err());
+        assert_eq!(mock_storage.delete("dir"), 20);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                StorageEvent::Upload {
+                    path: "dir/a".to_string(),
+                    size: 60
+                },
+                StorageEvent::Upload {
+                    path: "dir/b".to_string(),
+                    size: 30
+                },
+                StorageEvent::Overwrite {
+                    path: "dir/b".to_string(),
+                    old_size: 30,
+                    new_size: 20
+                },
+                StorageEvent::Evict {
+                    path: "dir/a".to_string(),
+                    size: 60
+                },
+                StorageEvent::Upload {
+                    path: "c".to_string(),
+                    size: 30
+                },
+                StorageEvent::Delete {
+                    path: "dir/b".to_string(),
+                    size: 20
+                },
+            ]
+        );
+        assert_eq!(*used.lock().unwrap(), vec![60, 90, 80, 50, 50, 30]);
+    }
+
+    #[cfg(feature = "persist")]
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("mock_storage_{}.json", std::process::id()));
+
+        let mock_storage = MockStorage::new(100);
+        assert!(mock_storage.set_quota("alice", 30).is_ok());
+        assert!(mock_storage.upload_as("alice", "a.txt", 20).is_ok());
+        assert!(mock_storage.upload_data("b.txt", b"hello".to_vec()).is_ok());
+        mock_storage.save_to_json(&path).unwrap();
+
+        let restored = SyncMockStorage::with_capacity(100);
+        assert!(restored.upload("c.txt", 10).is_ok());
+        restored.load_from_json(&path).unwrap();
+        assert_eq!(restored.list(), mock_storage.list());
+        assert_eq!(restored.metadata("b.txt"), mock_storage.metadata("b.txt"));
+        assert_eq!(restored.download("b.txt"), Some(b"hello".to_vec()));
+        assert_eq!(restored.quota("alice"), Some(30));
+        assert_eq!(restored.upload_as("alice", "b.txt", 20), Err(10));
+
+        let small = MockStorage::new(10);
+        assert!(small.load_from_json(&path).is_err());
+        assert!(small.list().is_empty());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "persist")]
+    #[test]
+    fn test_load_invalid_snapshot() {
+        let path =
+            std::env::temp_dir().join(format!("mock_storage_invalid_{}.json", std::process::id()));
+        let entry = |size: usize, data: Option<&str>| {
+            let data = data.map_or("null".to_string(), |data| format!("{:?}", data.as_bytes()));
+            format!(
+                r#"{{"size": {size}, "data": {data},
+                    "created": {{"secs_since_epoch": 0, "nanos_since_epoch": 0}},
+                    "modified": {{"secs_since_epoch": 0, "nanos_since_epoch": 0}},
+                    "expires": null, "last_used": 1}}"#
+            )
+        };
+        let load = |files: &[(&str, String)], users: &str| {
+            let files = files
+                .iter()
+                .map(|(name, entry)| format!("{name:?}: {entry}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            std::fs::write(
+                &path,
+                format!(r#"{{"files": {{{files}}}, "users": {users}}}"#),
+            )
+            .unwrap();
+            let mock_storage = MockStorage::new(100);
+            assert!(mock_storage.upload("kept", 1).is_ok());
+            let result = mock_storage.load_from_json(&path);
+            if result.is_err() {
+                assert_eq!(mock_storage.list(), vec![("kept".to_string(), 1)]);
+            }
+            result.map_err(|err| err.kind())
+        };
+
+        assert_eq!(load(&[("a", entry(1, None))], "{}"), Ok(()));
+        assert_eq!(load(&[("b", entry(2, Some("hi")))], "{}"), Ok(()));
+
+        let invalid = Err(std::io::ErrorKind::InvalidData);
+        assert_eq!(
+            load(&[("a", entry(1, None)), ("a/b", entry(1, None))], "{}"),
+            invalid
+        );
+        assert_eq!(load(&[("/a", entry(1, None))], "{}"), invalid);
+        assert_eq!(load(&[("a//b", entry(1, None))], "{}"), invalid);
+        assert_eq!(load(&[("", entry(1, None))], "{}"), invalid);
+        assert_eq!(load(&[("b", entry(3, Some("hi")))], "{}"), invalid);
+        assert_eq!(load(&[("a", entry(101, None))], "{}"), invalid);
+        assert_eq!(
+            load(&[("alice/a", entry(20, None))], r#"{"alice": 10}"#),
+            Ok(())
+        );
+        assert_eq!(
+            load(
+                &[("alice/a", entry(10, None))],
+                r#"{"alice": 10, "bob": null}"#
+            ),
+            Ok(())
+        );
+        assert_eq!(load(&[], r#"{"a/b": null}"#), invalid);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "persist")]
+    #[test]
+    fn test_save_and_load_over_quota() {
+        let path =
+            std::env::temp_dir().join(format!("mock_storage_quota_{}.json", std::process::id()));
+        let mock_storage = MockStorage::new(100);
+        assert!(mock_storage.upload_as("alice", "f", 50).is_ok());
+        assert!(mock_storage.set_quota("alice", 10).is_ok());
+        mock_storage.save_to_json(&path).unwrap();
+
+        let restored = MockStorage::new(100);
+        restored.load_from_json(&path).unwrap();
+        assert_eq!(restored.list(), mock_storage.list());
+        assert_eq!(restored.quota("alice"), Some(10));
+        assert_eq!(restored.used_by("alice"), 50);
+        assert_eq!(restored.upload_as("alice", "g", 1), Err(41));
+
+        mock_storage.load_from_json(&path).unwrap();
+        assert_eq!(mock_storage.used_by("alice"), 50);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "persist")]
+    #[test]
+    fn test_load_releases_reservations() {
+        let path =
+            std::env::temp_dir().join(format!("mock_storage_reserved_{}.json", std::process::id()));
+        let mock_storage = MockStorage::new(100);
+        assert!(mock_storage.upload("a/b", 60).is_ok());
+        mock_storage.save_to_json(&path).unwrap();
+
+        let restored = MockStorage::new(100);
+        assert!(restored.reserve("a", 50).is_ok());
+        restored.load_from_json(&path).unwrap();
+        assert_eq!(restored.reserved(), 0);
+        assert_eq!(restored.commit("a"), Err(0));
+        assert_eq!(restored.used(), 60);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_upload_batch() {
+        let mock_storage = MockStorage::new(100);
+        let events = Arc::new(Mutex::new(0));
+        let events_clone = Arc::clone(&events);
+        mock_storage.subscribe(move |_| *events_clone.lock().unwrap() += 1);
+
+        assert!(mock_storage.upload("a", 20).is_ok());
+        assert_eq!(
+            mock_storage.upload_batch(&[("b", 30), ("c", 40), ("a", 40), ("d", 10)]),
+            Err(BatchError {
+                index: 2,
+                name: "a".to_string(),
+                excess: 10
+            })
+        );
+        assert_eq!(mock_storage.list(), vec![("a".to_string(), 20)]);
+        assert_eq!(*events.lock().unwrap(), 1);
+
+        assert!(mock_storage
+            .upload_batch(&[("b", 30), ("c", 40), ("a", 30)])
+            .is_ok());
+        assert_eq!(mock_storage.used(), 100);
+        assert_eq!(*events.lock().unwrap(), 4);
+        assert!(mock_storage.upload_batch(&[]).is_ok());
+    }
+
+    #[test]
+    fn test_upload_batch_with_eviction() {
+        for policy in [EvictionPolicy::EvictLru, EvictionPolicy::EvictLargest] {
+            let mock_storage = MockStorage::new(100).with_eviction_policy(policy);
+            assert!(mock_storage.upload("x", 30).is_ok());
+            assert_eq!(
+                mock_storage.upload_batch(&[("a", 60), ("b", 60)]),
+                Err(BatchError {
+                    index: 1,
+                    name: "b".to_string(),
+                    excess: 50
+                })
+            );
+            assert_eq!(mock_storage.list(), vec![("x".to_string(), 30)]);
+            assert!(mock_storage.evicted().is_empty());
+
+            assert!(mock_storage.upload_batch(&[("a", 50), ("b", 50)]).is_ok());
+            assert!(mock_storage.contains("a") && mock_storage.contains("b"));
+            assert_eq!(mock_storage.evicted(), vec!["x".to_string()]);
+
+            // Files outside the batch are still evicted for single uploads.
+            assert!(mock_storage.upload("c", 10).is_ok());
+            assert_eq!(mock_storage.used(), 60);
+        }
+    }
+
+    #[test]
+    fn test_resize_capacity() {
+        let mock_storage = MockStorage::new(100);
+        assert!(mock_storage.upload("a", 60).is_ok());
+        assert_eq!(mock_storage.resize_capacity(50), Err(10));
+        assert_eq!(mock_storage.capacity(), 100);
+        assert!(mock_storage.resize_capacity(60).is_ok());
+        assert_eq!(mock_storage.capacity(), 60);
+        assert_eq!(mock_storage.upload("b", 1), Err(1));
+        assert!(mock_storage.resize_capacity(200).is_ok());
+        assert!(mock_storage.upload("b", 100).is_ok());
+    }
+
+    #[test]
+    fn test_reserve() {
+        let mock_storage = MockStorage::new(100);
+        assert!(mock_storage.upload("a", 30).is_ok());
+        assert!(mock_storage.reserve("b", 50).is_ok());
+        assert_eq!(mock_storage.reserved(), 50);
+        assert_eq!(mock_storage.used(), 30);
+        assert!(!mock_storage.contains("b"));
+
+        // Reserved space is not available to other uploads.
+        assert_eq!(mock_storage.upload("c", 30), Err(10));
+        assert_eq!(mock_storage.reserve("c", 30), Err(10));
+        assert_eq!(mock_storage.resize_capacity(70), Err(10));
+
+        assert!(mock_storage.commit("b").is_ok());
+        assert_eq!(mock_storage.size_of("b"), Some(50));
+        assert_eq!(mock_storage.reserved(), 0);
+
+        assert!(mock_storage.reserve("c", 20).is_ok());
+        assert_eq!(mock_storage.reserve("c", 30), Err(10));
+        assert_eq!(mock_storage.reserved(), 20);
+        assert!(mock_storage.abort("c"));
+        assert!(!mock_storage.abort("c"));
+        assert_eq!(mock_storage.reserved(), 0);
+        assert!(mock_storage.upload("c", 20).is_ok());
+
+        assert!(mock_storage.set_quota("alice", 10).is_ok());
+        assert!(mock_storage.resize_capacity(200).is_ok());
+        assert!(mock_storage.reserve("alice/a", 10).is_ok());
+        assert_eq!(mock_storage.upload_as("alice", "b", 1), Err(1));
+        assert!(mock_storage.set_quota("alice", 5).is_ok());
+        assert_eq!(mock_storage.commit("alice/a"), Err(5));
+    }
+
+    #[test]
+    fn test_reserve_invalid() {
+        let mock_storage = MockStorage::new(100);
+        assert!(mock_storage.upload("a/b", 10).is_ok());
+        assert_eq!(mock_storage.reserve("", 10), Err(0));
+        assert_eq!(mock_storage.reserve("a", 10), Err(0));
+        assert_eq!(mock_storage.reserve("a/b/c", 10), Err(0));
+        assert_eq!(mock_storage.reserved(), 0);
+
+        assert_eq!(mock_storage.commit("c"), Err(0));
+        assert!(mock_storage.reserve("c", 10).is_ok());
+        assert!(mock_storage.commit("c").is_ok());
+        assert_eq!(mock_storage.commit("c"), Err(0));
+        assert_eq!(mock_storage.size_of("c"), Some(10));
+    }
+
+    #[test]
+    fn test_usage_report() {
+        let mock_storage = MockStorage::new(100);
+        let usage_analyzer = UsageAnalyzer::new(&mock_storage, 0.75);
+        assert!(mock_storage
+            .upload_batch(&[("a", 0), ("b", 1), ("c", 5), ("d", 7), ("e", 7), ("f", 40)])
+            .is_ok());
+
+        assert_eq!(
+            usage_analyzer.report(3),
+            UsageReport {
+                capacity: 100,
+                used: 60,
+                free: 40,
+                largest: vec![
+                    ("f".to_string(), 40),
+                    ("d".to_string(), 7),
+                    ("e".to_string(), 7)
+                ],
+                histogram: vec![
+                    SizeBucket {
+                        min: 0,
+                        max: 1,
+                        files: 1,
+                        used: 0
+                    },
+                    SizeBucket {
+                        min: 1,
+                        max: 2,
+                        files: 1,
+                        used: 1
+                    },
+                    SizeBucket {
+                        min: 4,
+                        max: 8,
+                        files: 3,
+                        used: 19
+                    },
+                    SizeBucket {
+                        min: 32,
+                        max: 64,
+                        files: 1,
+                        used: 40
+                    },
+                ],
+            }
+        );
+        assert_eq!(usage_analyzer.report(10).largest.len(), 6);
+        assert_eq!(
+            usage_analyzer.files_over(6),
+            vec![
+                ("d".to_string(), 7),
+                ("e".to_string(), 7),
+                ("f".to_string(), 40)
+            ]
+        );
+        assert!(usage_analyzer.files_over(40).is_empty());
+    }
+
+    #[test]
+    fn test_chunked_upload() {
+        let mock_storage = MockStorage::new(100);
+        let uploader = FileUploader::new(&mock_storage);
+        assert!(uploader.upload("a", 60).is_ok());
+
+        let mut upload = uploader.begin_upload("b", 30).unwrap();
+        assert_eq!(mock_storage.part_size("b"), Some(0));
+        assert!(!mock_storage.contains("b"));
+        assert!(upload.write_chunk(10).is_ok());
+        assert!(upload.write_chunk(10).is_ok());
+        assert_eq!(mock_storage.part_size("b"), Some(20));
+        assert_eq!(mock_storage.list(), vec![("a".to_string(), 60)]);
+        assert_eq!(upload.write_chunk(20), Err(UploadError::TooLarge(10)));
+        assert!(upload.write_chunk(10).is_ok());
+        assert_eq!(upload.remaining(), 0);
+        assert!(upload.finish().is_ok());
+        assert_eq!(mock_storage.size_of("b"), Some(30));
+        assert_eq!(mock_storage.part_size("b"), None);
+
+        // Capacity is enforced chunk by chunk.
+        let mut upload = uploader.begin_upload("c", 20).unwrap();
+        assert!(upload.write_chunk(5).is_ok());
+        assert_eq!(
+            upload.write_chunk(10),
+            Err(UploadError::InsufficientSpace(5))
+        );
+        assert_eq!(upload.written(), 5);
+        assert_eq!(mock_storage.used(), 90);
+        assert_eq!(mock_storage.delete("a"), 60);
+        assert!(upload.write_chunk(15).is_ok());
+        assert!(upload.finish().is_ok());
+
+        let mut upload = uploader.begin_upload("d", 20).unwrap();
+        assert!(upload.write_chunk(10).is_ok());
+        upload.abort();
+        assert!(!mock_storage.contains("d"));
+        assert_eq!(mock_storage.part_size("d"), None);
+
+        // The existing file is kept until the upload is finished.
+        let mut upload = uploader.begin_upload("b", 20).unwrap();
+        assert_eq!(mock_storage.size_of("b"), Some(30));
+        assert!(upload.write_chunk(10).is_ok());
+        assert_eq!(upload.finish(), Err(UploadError::Incomplete(10)));
+        assert_eq!(
+            mock_storage.list(),
+            vec![("b".to_string(), 30), ("c".to_string(), 20)]
+        );
+    }
+
+    #[test]
+    fn test_chunked_upload_failure_keeps_file() {
+        let mock_storage = MockStorage::new(100);
+        let uploader = FileUploader::new(&mock_storage);
+        assert!(mock_storage.upload_data("a", b"original".to_vec()).is_ok());
+        assert!(mock_storage.upload("b", 80).is_ok());
+
+        // The first chunk fails.
+        let mut upload = uploader.begin_upload("a", 20).unwrap();
+        assert_eq!(
+            upload.write_chunk(20),
+            Err(UploadError::InsufficientSpace(8))
+        );
+        upload.abort();
+        assert_eq!(mock_storage.download("a"), Some(b"original".to_vec()));
+
+        // Replacing the file fails at the end.
+        assert_eq!(mock_storage.delete("b"), 80);
+        let mut upload = uploader.begin_upload("a", 20).unwrap();
+        assert!(upload.write_chunk(20).is_ok());
+        mock_storage.set_faults(FaultConfig {
+            fail_nth_upload: Some(1),
+            ..FaultConfig::default()
+        });
+        assert_eq!(upload.finish(), Err(UploadError::InsufficientSpace(0)));
+        assert_eq!(mock_storage.download("a"), Some(b"original".to_vec()));
+        assert_eq!(mock_storage.list(), vec![("a".to_string(), 8)]);
+
+        // Invalid paths fail before any chunk is written.
+        assert_eq!(uploader.begin_upload("a/b", 10).err(), Some(0));
+        assert_eq!(uploader.begin_upload("", 10).err(), Some(0));
+    }
+
+    #[test]
+    fn test_chunked_upload_staging() {
+        let mock_storage = MockStorage::new(100).with_eviction_policy(EvictionPolicy::EvictLru);
+        let uploader = FileUploader::new(&mock_storage);
+        assert!(mock_storage.upload("a", 60).is_ok());
+        assert!(mock_storage.upload("a.part", 10).is_ok());
+
+        // Only one chunked upload to a file at a time.
+        let mut upload = uploader.begin_upload("a", 30).unwrap();
+        assert_eq!(uploader.begin_upload("a", 30).err(), Some(0));
+        assert!(upload.write_chunk(30).is_ok());
+
+        // Chunks neither touch user files nor evict any file.
+        let mut other = uploader.begin_upload("b", 10).unwrap();
+        assert_eq!(
+            other.write_chunk(10),
+            Err(UploadError::InsufficientSpace(10))
+        );
+        other.abort();
+        assert_eq!(mock_storage.reserved(), 0);
+        assert_eq!(
+            mock_storage.list(),
+            vec![("a".to_string(), 60), ("a.part".to_string(), 10)]
+        );
+        assert!(mock_storage.evicted().is_empty());
+
+        assert!(upload.finish().is_ok());
+        assert_eq!(
+            mock_storage.list(),
+            vec![("a".to_string(), 30), ("a.part".to_string(), 10)]
+        );
+        assert!(uploader.begin_upload("a", 10).is_ok());
+    }
+
+    #[test]
+    fn test_fault_injection() {
+        let mock_storage = MockStorage::new(100);
+        mock_storage.set_faults(FaultConfig {
+            fail_nth_upload: Some(2),
+            ..FaultConfig::default()
+        });
+        assert!(mock_storage.upload("a", 10).is_ok());
+        assert_eq!(mock_storage.upload("b", 10), Err(0));
+        assert!(mock_storage.upload("b", 10).is_ok());
+        assert_eq!(mock_storage.list().len(), 2);
+
+        // A batch is rolled back on an injected fault, but can be retried.
+        mock_storage.set_faults(FaultConfig {
+            fail_nth_upload: Some(2),
+            ..FaultConfig::default()
+        });
+        assert_eq!(
+            mock_storage.upload_batch(&[("c", 10), ("d", 10)]),
+            Err(BatchError {
+                index: 1,
+                name: "d".to_string(),
+                excess: 0
+            })
+        );
+        assert!(mock_storage.upload_batch(&[("c", 10), ("d", 10)]).is_ok());
+
+        let spurious = |seed| {
+            let mock_storage = MockStorage::new(100);
+            mock_storage.set_faults(FaultConfig {
+                failure_rate: 0.5,
+                seed,
+                ..FaultConfig::default()
+            });
+            (0..20)
+                .map(|i| mock_storage.upload(&format!("{i}"), 1).is_ok())
+                .collect::<Vec<_>>()
+        };
+        let results = spurious(7);
+        assert_eq!(results, spurious(7));
+        assert!(results.contains(&true));
+        assert!(results.contains(&false));
+
+        mock_storage.set_faults(FaultConfig {
+            latency: Duration::from_millis(20),
+            ..FaultConfig::default()
+        });
+        let start = Instant::now();
+        assert!(mock_storage.upload("e", 10).is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(20));
+
+        mock_storage.set_faults(FaultConfig::default());
+        assert!(mock_storage.upload("f", 10).is_ok());
+    }
+
+    #[test]
+    fn test_fault_injection_invalid_rate() {
+        let mock_storage = SyncMockStorage::with_capacity(100);
+        mock_storage.set_faults(FaultConfig {
+            failure_rate: 1.5,
+            ..FaultConfig::default()
+        });
+        assert_eq!(mock_storage.upload_data("x", vec![1]), Err(0));
+        assert_eq!(mock_storage.upload("x", 1), Err(0));
+        assert!(!mock_storage.contains("x"));
+
+        for failure_rate in [-0.5, f64::NAN] {
+            mock_storage.set_faults(FaultConfig {
+                failure_rate,
+                ..FaultConfig::default()
+            });
+            assert!(mock_storage.upload("x", 1).is_ok());
+        }
+        assert!(mock_storage.contains("x"));
+    }
+
+    #[test]
+    fn test_ttl_and_gc() {
+        let clock = Arc::new(MockClock::new(SystemTime::UNIX_EPOCH));
+        let mock_storage = MockStorage::new(100).with_clock(clock.clone());
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let events_clone = Arc::clone(&events);
+        mock_storage.subscribe(move |event| events_clone.lock().unwrap().push(event.clone()));
+
+        assert!(mock_storage
+            .upload_with_ttl("a", 40, Duration::from_secs(10))
+            .is_ok());
+        assert!(mock_storage
+            .upload_with_ttl("b", 30, Duration::from_secs(20))
+            .is_ok());
+        assert!(mock_storage.upload("c", 20).is_ok());
+        assert_eq!(
+            mock_storage.metadata("a").unwrap().expires,
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(10))
+        );
+        assert_eq!(mock_storage.metadata("c").unwrap().expires, None);
+        assert_eq!(mock_storage.gc(), 0);
+
+        // Expired files occupy space until they are collected.
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(mock_storage.upload("d", 20), Err(10));
+        assert_eq!(mock_storage.gc(), 40);
+        assert!(!mock_storage.contains("a"));
+        assert!(mock_storage.upload("d", 20).is_ok());
+        assert_eq!(
+            mock_storage.metadata("d").unwrap().created,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(10)
+        );
+
+        // Overwriting a file clears its expiry.
+        assert!(mock_storage.upload("b", 30).is_ok());
+        clock.advance(Duration::from_secs(100));
+        assert_eq!(mock_storage.gc(), 0);
+        assert_eq!(mock_storage.used(), 70);
+
+        assert!(events.lock().unwrap().contains(&StorageEvent::Expire {
+            path: "a".to_string(),
+            size: 40
+        }));
+    }
 }
@@ -22,12 +22,12 @@ mod test_demux {
 
         let thread_rx1 = thread::spawn(move || {
             let sum: u32 = rx1.iter().sum();
-            assert_eq!(sum, (0..100).filter(|x| x % 2 == 0).sum());
+            assert_eq!(sum, (0..100).filter(|x| x % 2 == 0).sum::<u32>());
         });
 
         let thread_rx2 = thread::spawn(move || {
             let sum: u32 = rx2.iter().sum();
-            assert_eq!(sum, (0..100).filter(|x| x % 2 != 0).sum());
+            assert_eq!(sum, (0..100).filter(|x| x % 2 != 0).sum::<u32>());
         });
 
         thread_tx.join().unwrap();
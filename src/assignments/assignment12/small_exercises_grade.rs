@@ -40,8 +40,8 @@ mod test_pingpong {
                     || v.windows(2).map(|x| x[0] * x[1]).sum::<u32>(),
                 );
 
-                assert_eq!(r1, v.iter().sum());
-                assert_eq!(r2, v.windows(2).map(|x| x[0] * x[1]).sum());
+                assert_eq!(r1, v.iter().sum::<u32>());
+                assert_eq!(r2, v.windows(2).map(|x| x[0] * x[1]).sum::<u32>());
             });
         }
     }
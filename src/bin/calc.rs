@@ -39,6 +39,32 @@ impl<'a> BufRead for Input<'a> {
     }
 }
 
+fn run(context: &mut context::Context, line: &str) -> Result<(), error::CalcError> {
+    match parser::parse_statement(line)? {
+        syntax::Statement::Command(command) => {
+            let (variable, value) = context.calc_complex_command(&command)?;
+            println!("{} = {}", variable, value);
+        }
+        syntax::Statement::Vars => {
+            let mut variables = context.variables().collect::<Vec<_>>();
+            variables.sort_by_key(|&(variable, _)| variable);
+            for (variable, value) in variables {
+                println!("{} = {}", variable, value);
+            }
+        }
+        syntax::Statement::Delete(variable) => {
+            if context.remove_variable(&variable).is_none() {
+                return Err(error::CalcError::UndefinedVariable(variable));
+            }
+        }
+        syntax::Statement::Clear => context.reset(),
+        syntax::Statement::Diff(expression) => {
+            println!("d/dx = {}", diff::differentiate(&expression)?);
+        }
+    }
+    Ok(())
+}
+
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
@@ -60,9 +86,19 @@ fn main() -> Result<()> {
 
     let mut context = context::Context::new();
     for line in input.lines() {
-        let command = parser::parse_command(&line?)?;
-        let (variable, value) = context.calc_command(&command)?;
-        println!("{} = {}", variable, value);
+        let line = line?;
+        if let Err(error) = run(&mut context, &line) {
+            // Underlines the offending part of the line.
+            if let Some(span) = error.span(&line) {
+                eprintln!("{}", line);
+                eprintln!(
+                    "{}{}",
+                    " ".repeat(span.start),
+                    "^".repeat((span.end - span.start).max(1))
+                );
+            }
+            eprintln!("error: {}", error);
+        }
     }
 
     Ok(())
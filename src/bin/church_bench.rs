@@ -0,0 +1,57 @@
+use std::rc::Rc;
+use std::time::Instant;
+
+use cs220::assignments::assignment08::{church, church_fn};
+
+const SIZE: usize = 100_000;
+
+/// Builds `SIZE` as `10 * 10 * 10 * 10 * 10`, where `10 = succ(succ(2 * 2 * 2))`.
+fn rc_dyn_composed() {
+    let eight = church::mult(church::two(), church::mult(church::two(), church::two()));
+    let ten = church::succ(church::succ(eight));
+    let n = (0..4).fold(Rc::clone(&ten), |acc, _| church::mult(Rc::clone(&ten), acc));
+    assert_eq!(church::to_usize::<()>(n), SIZE);
+}
+
+/// Builds `SIZE` in the same way as [`rc_dyn_composed`].
+///
+/// The structure of the numeral is in its type, so the compiler may fold the whole application.
+fn static_composed() {
+    let eight = church_fn::mult(
+        church_fn::two(),
+        church_fn::mult(church_fn::two(), church_fn::two()),
+    );
+    let ten = church_fn::succ(church_fn::succ(eight));
+    let n = church_fn::mult(
+        &ten,
+        church_fn::mult(&ten, church_fn::mult(&ten, church_fn::mult(&ten, &ten))),
+    );
+    assert_eq!(church_fn::to_usize(n), SIZE);
+}
+
+/// Builds `SIZE` as a chain of `succ`s.
+fn rc_dyn_succ_chain() {
+    assert_eq!(church::to_usize(church::from_usize::<()>(SIZE)), SIZE);
+}
+
+/// `church_fn::from_usize` is not composed of combinators, but applies `f` in a plain loop.
+fn static_loop() {
+    assert_eq!(church_fn::to_usize(church_fn::from_usize(SIZE)), SIZE);
+}
+
+fn bench<F>(name: &str, f: F)
+where
+    F: FnOnce(),
+{
+    let begin = Instant::now();
+    f();
+    let elapsed = begin.elapsed();
+    println!("{}: {:.2?}", name, elapsed);
+}
+
+fn main() {
+    bench("rc_dyn_composed", rc_dyn_composed);
+    bench("static_composed", static_composed);
+    bench("rc_dyn_succ_chain", rc_dyn_succ_chain);
+    bench("static_loop", static_loop);
+}
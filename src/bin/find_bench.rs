@@ -0,0 +1,30 @@
+use std::time::Instant;
+
+use cs220::assignments::assignment07::small_exercises::{find, find_naive};
+
+const BASE_SIZE: usize = 1_000_000;
+const QUERY_SIZE: usize = 1_000;
+
+fn bench<F>(name: &str, f: F)
+where
+    F: FnOnce() -> usize,
+{
+    let begin = Instant::now();
+    let count = f();
+    let elapsed = begin.elapsed();
+    println!("{}: {} matches, {:.2?}", name, count, elapsed);
+}
+
+fn main() {
+    // `aaa...a` in `aaa...a` matches everywhere, and `aaa...ab` in `aaa...a` almost matches
+    // everywhere.
+    let base = vec![b'a'; BASE_SIZE];
+    let all_a = vec![b'a'; QUERY_SIZE];
+    let mut last_b = all_a.clone();
+    last_b[QUERY_SIZE - 1] = b'b';
+
+    bench("kmp   aaaa", || find(&all_a, &base).count());
+    bench("naive aaaa", || find_naive(&all_a, &base).count());
+    bench("kmp   aaab", || find(&last_b, &base).count());
+    bench("naive aaab", || find_naive(&last_b, &base).count());
+}
@@ -0,0 +1,47 @@
+use std::time::Instant;
+
+use cs220::assignments::assignment09::small_exercises::k_smallest_mean;
+
+const SIZE: usize = 10_000_000;
+const K: usize = 100;
+
+// Pseudo-random values from a linear congruential generator
+fn stream() -> impl Iterator<Item = i64> {
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    (0..SIZE).map(move |_| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 33) as i64
+    })
+}
+
+fn sorted() -> Option<f64> {
+    let mut values = stream().collect::<Vec<_>>();
+    values.sort_unstable();
+    let sum = values
+        .get(..K)?
+        .iter()
+        .map(|&v| i128::from(v))
+        .sum::<i128>();
+    Some(sum as f64 / K as f64)
+}
+
+fn heap() -> Option<f64> {
+    k_smallest_mean(stream(), K)
+}
+
+fn bench<F>(name: &str, f: F)
+where
+    F: FnOnce() -> Option<f64>,
+{
+    let begin = Instant::now();
+    let mean = f();
+    let elapsed = begin.elapsed();
+    println!("{}: {:?}, {:.2?}", name, mean, elapsed);
+}
+
+fn main() {
+    bench("sort", sorted);
+    bench("heap", heap);
+}